@@ -1,7 +1,8 @@
 use std::path::Path;
 
 use crate::parse::asm::{
-    FunctionDefinition, Instruction, Operand, ProgramDefinition, Reg, UnaryOperator,
+    BinaryOperator, CondCode, FunctionDefinition, Instruction, Operand, ProgramDefinition, Reg,
+    UnaryOperator,
 };
 
 pub fn emit(output: &Path, node: ProgramDefinition) -> std::io::Result<()> {
@@ -16,7 +17,10 @@ pub fn emit_operand(node: Operand) -> String {
         Operand::Imm(val) => format!("${}", val),
         Operand::Register(reg) => match reg {
             Reg::AX => "%eax".to_string(),
+            Reg::CX => "%ecx".to_string(),
+            Reg::DX => "%edx".to_string(),
             Reg::R10D => "%r10d".to_string(),
+            Reg::R11D => "%r11d".to_string(),
         },
         Operand::Stack(offset) => format!("{}(%rbp)", offset),
         Operand::PseudoRegister(_) => {
@@ -25,6 +29,20 @@ pub fn emit_operand(node: Operand) -> String {
     }
 }
 
+/// Emit the operand as a 1-byte operand, which only differs from the 4-byte form for registers
+pub fn emit_byte_operand(node: Operand) -> String {
+    match node {
+        Operand::Register(reg) => match reg {
+            Reg::AX => "%al".to_string(),
+            Reg::CX => "%cl".to_string(),
+            Reg::DX => "%dl".to_string(),
+            Reg::R10D => "%r10b".to_string(),
+            Reg::R11D => "%r11b".to_string(),
+        },
+        _ => emit_operand(node),
+    }
+}
+
 pub fn emit_unary_operator(node: UnaryOperator) -> String {
     match node {
         UnaryOperator::Neg => "negl".to_string(),
@@ -32,7 +50,38 @@ pub fn emit_unary_operator(node: UnaryOperator) -> String {
     }
 }
 
-pub fn emit_instruction(node: Instruction) -> Vec<String> {
+pub fn emit_binary_operator(node: BinaryOperator) -> String {
+    match node {
+        BinaryOperator::Add => "addl".to_string(),
+        BinaryOperator::Sub => "subl".to_string(),
+        BinaryOperator::Mult => "imull".to_string(),
+        BinaryOperator::And => "andl".to_string(),
+        BinaryOperator::Or => "orl".to_string(),
+        BinaryOperator::Xor => "xorl".to_string(),
+        BinaryOperator::Sal => "sall".to_string(),
+        BinaryOperator::Sar => "sarl".to_string(),
+    }
+}
+
+pub fn emit_cond_code(node: CondCode) -> String {
+    match node {
+        CondCode::E => "e".to_string(),
+        CondCode::NE => "ne".to_string(),
+        CondCode::L => "l".to_string(),
+        CondCode::LE => "le".to_string(),
+        CondCode::G => "g".to_string(),
+        CondCode::GE => "ge".to_string(),
+    }
+}
+
+/// Labels are prefixed with `.L` so that the assembler treats them as local labels, which don't
+/// end up in the symbol table (and so can't clash with function names). Labels are only unique
+/// within a function, so they're also qualified with the name of the function they're in.
+pub fn emit_label(function_name: &str, identifier: &str) -> String {
+    format!(".L{}.{}", function_name, identifier)
+}
+
+pub fn emit_instruction(node: Instruction, function_name: &str) -> Vec<String> {
     let mut lines = Vec::new();
 
     match node {
@@ -54,6 +103,45 @@ pub fn emit_instruction(node: Instruction) -> Vec<String> {
             let dst_string = emit_operand(dst);
             lines.push(format!("    {} {}", op_string, dst_string));
         }
+        Instruction::Binary { op, src, dst } => {
+            // A shift count that isn't an immediate value has to be given as the one-byte `%cl`
+            let src_string = match op {
+                BinaryOperator::Sal | BinaryOperator::Sar => emit_byte_operand(src),
+                _ => emit_operand(src),
+            };
+            let op_string = emit_binary_operator(op);
+            let dst_string = emit_operand(dst);
+            lines.push(format!("    {} {}, {}", op_string, src_string, dst_string));
+        }
+        Instruction::Idiv(operand) => {
+            let operand_string = emit_operand(operand);
+            lines.push(format!("    idivl {}", operand_string));
+        }
+        Instruction::Cdq => lines.push("    cdq".to_string()),
+        Instruction::Cmp { left, right } => {
+            let left_string = emit_operand(left);
+            let right_string = emit_operand(right);
+            lines.push(format!("    cmpl {}, {}", left_string, right_string));
+        }
+        Instruction::Jmp(target) => {
+            lines.push(format!("    jmp {}", emit_label(function_name, &target)))
+        }
+        Instruction::JmpCC { condition, target } => {
+            let condition_string = emit_cond_code(condition);
+            lines.push(format!(
+                "    j{} {}",
+                condition_string,
+                emit_label(function_name, &target)
+            ));
+        }
+        Instruction::Label(identifier) => {
+            lines.push(format!("{}:", emit_label(function_name, &identifier)))
+        }
+        Instruction::SetCC { condition, dst } => {
+            let condition_string = emit_cond_code(condition);
+            let dst_string = emit_byte_operand(dst);
+            lines.push(format!("    set{} {}", condition_string, dst_string));
+        }
     }
 
     lines
@@ -69,7 +157,7 @@ pub fn emit_function_definition(node: FunctionDefinition) -> Vec<String> {
                 "    movq %rsp, %rbp".to_string(),
             ];
             for instruction in instructions {
-                let mut instruction_strings = emit_instruction(instruction);
+                let mut instruction_strings = emit_instruction(instruction, &name);
                 lines.append(&mut instruction_strings);
             }
             lines
@@ -79,7 +167,10 @@ pub fn emit_function_definition(node: FunctionDefinition) -> Vec<String> {
 
 pub fn emit_program_definition(node: ProgramDefinition) -> Vec<String> {
     match node {
-        ProgramDefinition::Program(func_defn) => emit_function_definition(func_defn),
+        ProgramDefinition::Program(func_defns) => func_defns
+            .into_iter()
+            .flat_map(emit_function_definition)
+            .collect(),
     }
 }
 
@@ -151,7 +242,7 @@ mod tests {
             src: Operand::Imm(value),
             dst: Operand::Register(Reg::AX),
         };
-        let asm_code = emit_instruction(ast_node);
+        let asm_code = emit_instruction(ast_node, "main");
         let expected_asm_code = vec!["    movl $2, %eax"];
         assert_eq!(asm_code, expected_asm_code);
     }
@@ -159,7 +250,7 @@ mod tests {
     #[test]
     fn emit_ret_instruction() {
         let ast_node = Instruction::Ret;
-        let asm_code = emit_instruction(ast_node);
+        let asm_code = emit_instruction(ast_node, "main");
         let expected_asm_code = vec![
             "    movq %rbp, %rsp".to_string(),
             "    popq %rbp".to_string(),
@@ -172,7 +263,7 @@ mod tests {
     fn emit_allocate_stack_instruction() {
         let offset = 8;
         let ast_node = Instruction::AllocateStack(offset);
-        let asm_code = emit_instruction(ast_node);
+        let asm_code = emit_instruction(ast_node, "main");
         let expected_asm_code = vec![format!("    subq ${}, %rsp", offset)];
         assert_eq!(asm_code, expected_asm_code);
     }
@@ -184,7 +275,7 @@ mod tests {
             op: UnaryOperator::Neg,
             dst: Operand::Imm(value),
         };
-        let asm_code = emit_instruction(ast_node);
+        let asm_code = emit_instruction(ast_node, "main");
         let expected_asm_code = vec![format!("    negl ${}", value)];
         assert_eq!(asm_code, expected_asm_code);
     }
@@ -233,7 +324,7 @@ mod tests {
             name: identifier.to_string(),
             instructions,
         };
-        let ast_node = ProgramDefinition::Program(function_defn);
+        let ast_node = ProgramDefinition::Program(vec![function_defn]);
         let asm_code = emit_program_definition(ast_node);
         let expected_asm_code = vec![
             format!("    .globl {}", identifier.to_string()),
@@ -247,4 +338,268 @@ mod tests {
         ];
         assert_eq!(asm_code, expected_asm_code);
     }
+
+    #[test]
+    fn emit_mov_instruction_with_imm_operand_larger_than_one_byte() {
+        let value = 300;
+        let ast_node = Instruction::Mov {
+            src: Operand::Imm(value),
+            dst: Operand::Register(Reg::AX),
+        };
+        let asm_code = emit_instruction(ast_node, "main");
+        let expected_asm_code = vec!["    movl $300, %eax"];
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_register_dx_operand() {
+        let ast_node = Operand::Register(Reg::DX);
+        let asm_code = emit_operand(ast_node);
+        let expected_asm_code = "%edx";
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_register_r11d_operand() {
+        let ast_node = Operand::Register(Reg::R11D);
+        let asm_code = emit_operand(ast_node);
+        let expected_asm_code = "%r11d";
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_binary_operators() {
+        let ast_nodes = [
+            (BinaryOperator::Add, "addl"),
+            (BinaryOperator::Sub, "subl"),
+            (BinaryOperator::Mult, "imull"),
+            (BinaryOperator::And, "andl"),
+            (BinaryOperator::Or, "orl"),
+            (BinaryOperator::Xor, "xorl"),
+            (BinaryOperator::Sal, "sall"),
+            (BinaryOperator::Sar, "sarl"),
+        ];
+        for (ast_node, expected_asm_code) in ast_nodes {
+            let asm_code = emit_binary_operator(ast_node);
+            assert_eq!(asm_code, expected_asm_code);
+        }
+    }
+
+    #[test]
+    fn emit_binary_instruction() {
+        let value = 2;
+        let ast_node = Instruction::Binary {
+            op: BinaryOperator::Sub,
+            src: Operand::Imm(value),
+            dst: Operand::Stack(-4),
+        };
+        let asm_code = emit_instruction(ast_node, "main");
+        let expected_asm_code = vec![format!("    subl ${}, -4(%rbp)", value)];
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_shift_instruction_with_immediate_count() {
+        let ast_node = Instruction::Binary {
+            op: BinaryOperator::Sal,
+            src: Operand::Imm(4),
+            dst: Operand::Stack(-4),
+        };
+        let asm_code = emit_instruction(ast_node, "main");
+        let expected_asm_code = vec!["    sall $4, -4(%rbp)"];
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_shift_instruction_with_count_in_cl_register() {
+        let ast_node = Instruction::Binary {
+            op: BinaryOperator::Sar,
+            src: Operand::Register(Reg::CX),
+            dst: Operand::Stack(-4),
+        };
+        let asm_code = emit_instruction(ast_node, "main");
+        let expected_asm_code = vec!["    sarl %cl, -4(%rbp)"];
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_idiv_instruction() {
+        let ast_node = Instruction::Idiv(Operand::Register(Reg::R10D));
+        let asm_code = emit_instruction(ast_node, "main");
+        let expected_asm_code = vec!["    idivl %r10d"];
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_cdq_instruction() {
+        let ast_node = Instruction::Cdq;
+        let asm_code = emit_instruction(ast_node, "main");
+        let expected_asm_code = vec!["    cdq"];
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_program_definition_with_two_function_definitions() {
+        let function_defns = ["foo", "main"]
+            .into_iter()
+            .enumerate()
+            .map(|(value, identifier)| FunctionDefinition::Function {
+                name: identifier.to_string(),
+                instructions: vec![
+                    Instruction::Mov {
+                        src: Operand::Imm(value as i64),
+                        dst: Operand::Register(Reg::AX),
+                    },
+                    Instruction::Ret,
+                ],
+            })
+            .collect();
+        let ast_node = ProgramDefinition::Program(function_defns);
+        let asm_code = emit_program_definition(ast_node);
+        let expected_asm_code = vec![
+            "    .globl foo",
+            "foo:",
+            "    pushq %rbp",
+            "    movq %rsp, %rbp",
+            "    movl $0, %eax",
+            "    movq %rbp, %rsp",
+            "    popq %rbp",
+            "    ret",
+            "    .globl main",
+            "main:",
+            "    pushq %rbp",
+            "    movq %rsp, %rbp",
+            "    movl $1, %eax",
+            "    movq %rbp, %rsp",
+            "    popq %rbp",
+            "    ret",
+        ];
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_mov_instruction_with_smallest_int_imm_operand() {
+        let ast_node = Instruction::Mov {
+            src: Operand::Imm(-2147483648),
+            dst: Operand::Register(Reg::AX),
+        };
+        let asm_code = emit_instruction(ast_node, "main");
+        let expected_asm_code = vec!["    movl $-2147483648, %eax"];
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_cmp_instruction() {
+        let ast_node = Instruction::Cmp {
+            left: Operand::Imm(0),
+            right: Operand::Stack(-4),
+        };
+        let asm_code = emit_instruction(ast_node, "main");
+        assert_eq!(asm_code, vec!["    cmpl $0, -4(%rbp)".to_string()]);
+    }
+
+    #[test]
+    fn emit_jump_instructions_and_label_as_local_labels() {
+        let ast_nodes = vec![
+            Instruction::Jmp("and_end0".to_string()),
+            Instruction::JmpCC {
+                condition: CondCode::E,
+                target: "and_false0".to_string(),
+            },
+            Instruction::JmpCC {
+                condition: CondCode::NE,
+                target: "and_false0".to_string(),
+            },
+            Instruction::Label("and_end0".to_string()),
+        ];
+        let expected_asm_code = vec![
+            "    jmp .Lmain.and_end0".to_string(),
+            "    je .Lmain.and_false0".to_string(),
+            "    jne .Lmain.and_false0".to_string(),
+            ".Lmain.and_end0:".to_string(),
+        ];
+        let asm_code: Vec<String> = ast_nodes
+            .into_iter()
+            .flat_map(|ast_node| emit_instruction(ast_node, "main"))
+            .collect();
+        assert_eq!(asm_code, expected_asm_code);
+    }
+
+    #[test]
+    fn emit_set_instruction_with_stack_addr_operand() {
+        let ast_node = Instruction::SetCC {
+            condition: CondCode::E,
+            dst: Operand::Stack(-4),
+        };
+        let asm_code = emit_instruction(ast_node, "main");
+        assert_eq!(asm_code, vec!["    sete -4(%rbp)".to_string()]);
+    }
+
+    #[test]
+    fn emit_set_instruction_with_one_byte_register_operand() {
+        let ast_node = Instruction::SetCC {
+            condition: CondCode::NE,
+            dst: Operand::Register(Reg::AX),
+        };
+        let asm_code = emit_instruction(ast_node, "main");
+        assert_eq!(asm_code, vec!["    setne %al".to_string()]);
+    }
+
+    #[test]
+    fn emit_comparison_cond_codes() {
+        let ast_nodes = [
+            (CondCode::L, "l"),
+            (CondCode::LE, "le"),
+            (CondCode::G, "g"),
+            (CondCode::GE, "ge"),
+        ];
+        for (ast_node, expected_asm_code) in ast_nodes {
+            let asm_code = emit_cond_code(ast_node);
+            assert_eq!(asm_code, expected_asm_code);
+        }
+    }
+
+    #[test]
+    fn emit_same_label_in_two_function_definitions_as_distinct_local_labels() {
+        let program_defn = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                instructions: vec![Instruction::Label("if_end0".to_string())],
+            },
+            FunctionDefinition::Function {
+                name: "main".to_string(),
+                instructions: vec![Instruction::Jmp("if_end0".to_string())],
+            },
+        ]);
+        let asm_code = emit_program_definition(program_defn);
+        assert!(asm_code.contains(&".Lfoo.if_end0:".to_string()));
+        assert!(asm_code.contains(&"    jmp .Lmain.if_end0".to_string()));
+    }
+
+    /// The 4-byte and 1-byte names of a register. The match is exhaustive, so adding a register
+    /// doesn't compile until its names are added here too.
+    fn expected_register_names(reg: &Reg) -> (&'static str, &'static str) {
+        match reg {
+            Reg::AX => ("%eax", "%al"),
+            Reg::CX => ("%ecx", "%cl"),
+            Reg::DX => ("%edx", "%dl"),
+            Reg::R10D => ("%r10d", "%r10b"),
+            Reg::R11D => ("%r11d", "%r11b"),
+        }
+    }
+
+    #[test]
+    fn emit_every_register_as_four_byte_and_one_byte_operand() {
+        for reg in [Reg::AX, Reg::CX, Reg::DX, Reg::R10D, Reg::R11D] {
+            let (expected_four_byte_name, expected_one_byte_name) = expected_register_names(&reg);
+            assert_eq!(
+                emit_operand(Operand::Register(reg.clone())),
+                expected_four_byte_name
+            );
+            assert_eq!(
+                emit_byte_operand(Operand::Register(reg)),
+                expected_one_byte_name
+            );
+        }
+    }
 }
@@ -1,9 +1,4 @@
-use regex::Regex;
-
-static INT_KEYWORD_LEN: usize = 3;
-static RETURN_KEYWORD_LEN: usize = 6;
-
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     IntKeyword,
     Identifier(String),
@@ -12,7 +7,23 @@ pub enum Token {
     OpenBrace,
     CloseBrace,
     ReturnKeyword,
-    NumericConstant(u8),
+    IfKeyword,
+    ElseKeyword,
+    WhileKeyword,
+    DoKeyword,
+    ForKeyword,
+    BreakKeyword,
+    ContinueKeyword,
+    SwitchKeyword,
+    CaseKeyword,
+    DefaultKeyword,
+    GotoKeyword,
+    LongKeyword,
+    UnsignedKeyword,
+    SignedKeyword,
+    CharKeyword,
+    VoidKeyword,
+    NumericConstant(i64),
     Semicolon,
     Minus,
     Tilde,
@@ -20,244 +31,508 @@ pub enum Token {
     Asterisk,
     ForwardSlash,
     Percent,
+    Exclamation,
+    ExclamationEquals,
+    DoubleAmpersand,
+    Ampersand,
+    DoublePipe,
+    Pipe,
+    DoubleEquals,
+    Equals,
+    PlusEquals,
+    MinusEquals,
+    AsteriskEquals,
+    ForwardSlashEquals,
+    PercentEquals,
+    AmpersandEquals,
+    PipeEquals,
+    CaretEquals,
+    LeftAngleBracket,
+    LeftAngleBracketEquals,
+    DoubleLeftAngleBracket,
+    DoubleLeftAngleBracketEquals,
+    RightAngleBracket,
+    RightAngleBracketEquals,
+    DoubleRightAngleBracket,
+    DoubleRightAngleBracketEquals,
+    DoublePlus,
+    DoubleMinus,
+    Caret,
+    QuestionMark,
+    Colon,
+    Comma,
 }
 
-pub fn lex(text: &str) -> Vec<Token> {
-    let int_keyword_regex = Regex::new(r"^int\b").unwrap();
-    let identifier_regex = Regex::new(r"^[a-zA-Z]\w*\b").unwrap();
-    let whitespace_regex = Regex::new(r"^\s+").unwrap();
-    let open_parenthesis_regex = Regex::new(r"^\(").unwrap();
-    let close_parenthesis_regex = Regex::new(r"^\)").unwrap();
-    let open_brace_regex = Regex::new(r"^\{").unwrap();
-    let close_brace_regex = Regex::new(r"^\}").unwrap();
-    let return_keyword_regex = Regex::new(r"^return\b").unwrap();
-    let numeric_constant_regex = Regex::new(r"^[0-9]+\b").unwrap();
-    let semicolon_regex = Regex::new(r"^;").unwrap();
-    let empty_line_regex = Regex::new(r"^$").unwrap();
-    let minus_regex = Regex::new(r"^-").unwrap();
-    let decrement_operator_regex = Regex::new(r"^--").unwrap();
-    let tilde_regex = Regex::new(r"^~").unwrap();
-    let plus_regex = Regex::new(r"^\+").unwrap();
-    let asterisk_regex = Regex::new(r"^\*").unwrap();
-    let forward_slash_regex = Regex::new(r"^/").unwrap();
-    let percent_regex = Regex::new(r"^%").unwrap();
-
-    let mut tokens: Vec<Token> = vec![];
-
-    for line in text.lines() {
-        let mut traversed_entire_line = false;
-        let mut idx = 0;
-
-        while !traversed_entire_line {
-            let res = whitespace_regex.find(&line[idx..]);
-            if let Some(mat) = res {
-                // Advance past the whitespace
-                idx += mat.end();
-                continue;
-            }
+/// Tokens are displayed as they would be spelled in C source code
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spelling = match self {
+            Token::Identifier(name) => return write!(f, "{}", name),
+            Token::NumericConstant(value) => return write!(f, "{}", value),
+            Token::IntKeyword => "int",
+            Token::ReturnKeyword => "return",
+            Token::IfKeyword => "if",
+            Token::ElseKeyword => "else",
+            Token::WhileKeyword => "while",
+            Token::DoKeyword => "do",
+            Token::ForKeyword => "for",
+            Token::BreakKeyword => "break",
+            Token::ContinueKeyword => "continue",
+            Token::SwitchKeyword => "switch",
+            Token::CaseKeyword => "case",
+            Token::DefaultKeyword => "default",
+            Token::GotoKeyword => "goto",
+            Token::LongKeyword => "long",
+            Token::UnsignedKeyword => "unsigned",
+            Token::SignedKeyword => "signed",
+            Token::CharKeyword => "char",
+            Token::VoidKeyword => "void",
+            Token::OpenParenthesis => "(",
+            Token::CloseParenthesis => ")",
+            Token::OpenBrace => "{",
+            Token::CloseBrace => "}",
+            Token::Semicolon => ";",
+            Token::Minus => "-",
+            Token::Tilde => "~",
+            Token::Plus => "+",
+            Token::Asterisk => "*",
+            Token::ForwardSlash => "/",
+            Token::Percent => "%",
+            Token::Exclamation => "!",
+            Token::ExclamationEquals => "!=",
+            Token::DoubleAmpersand => "&&",
+            Token::Ampersand => "&",
+            Token::DoublePipe => "||",
+            Token::Pipe => "|",
+            Token::DoubleEquals => "==",
+            Token::Equals => "=",
+            Token::PlusEquals => "+=",
+            Token::MinusEquals => "-=",
+            Token::AsteriskEquals => "*=",
+            Token::ForwardSlashEquals => "/=",
+            Token::PercentEquals => "%=",
+            Token::AmpersandEquals => "&=",
+            Token::PipeEquals => "|=",
+            Token::CaretEquals => "^=",
+            Token::LeftAngleBracket => "<",
+            Token::LeftAngleBracketEquals => "<=",
+            Token::DoubleLeftAngleBracket => "<<",
+            Token::DoubleLeftAngleBracketEquals => "<<=",
+            Token::RightAngleBracket => ">",
+            Token::RightAngleBracketEquals => ">=",
+            Token::DoubleRightAngleBracket => ">>",
+            Token::DoubleRightAngleBracketEquals => ">>=",
+            Token::DoublePlus => "++",
+            Token::DoubleMinus => "--",
+            Token::Caret => "^",
+            Token::QuestionMark => "?",
+            Token::Colon => ":",
+            Token::Comma => ",",
+        };
+        write!(f, "{}", spelling)
+    }
+}
 
-            let res = empty_line_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                // The removal of a newline character by the str.lines()` method means that a line
-                // with only a newline character will have an empty string. In such a case, move to
-                // the next line.
-                traversed_entire_line = true;
-                continue;
-            }
+/// Position of the first character of a token in the C source code, as 1-based line and column
+/// numbers
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
 
-            let res = int_keyword_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::IntKeyword;
-                tokens.push(token);
+/// A token along with the position in the C source code that it was created from
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
 
-                // Advance past the substring that a match was found for the `int` keyword
-                idx += INT_KEYWORD_LEN;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+/// Errors that can occur when converting C source code into tokens. Positions are 1-based line
+/// and column numbers.
+#[derive(Debug, PartialEq)]
+pub enum LexError {
+    /// No token could be matched at the start of the given substring
+    NoMatch {
+        text: String,
+        line: usize,
+        column: usize,
+    },
+    /// A numeric constant has a value that is too large to be represented
+    ConstantTooLarge {
+        text: String,
+        line: usize,
+        column: usize,
+    },
+    /// An identifier starts with a digit
+    InvalidIdentifier {
+        text: String,
+        line: usize,
+        column: usize,
+    },
+    /// A construct that needs to be closed (such as a block comment) was never closed. The
+    /// position is where the construct started.
+    Unterminated {
+        kind: UnterminatedKind,
+        start_line: usize,
+        start_column: usize,
+    },
+}
 
-            let res = return_keyword_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::ReturnKeyword;
-                tokens.push(token);
-                idx += RETURN_KEYWORD_LEN;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+/// Constructs that can be left unterminated in C source code
+#[derive(Debug, PartialEq)]
+pub enum UnterminatedKind {
+    BlockComment,
+    CharacterLiteral,
+    StringLiteral,
+}
 
-            let res = identifier_regex.find(&line[idx..]);
-            if let Some(mat) = res {
-                let token = Token::Identifier(mat.as_str().to_string());
-                tokens.push(token);
-                idx += mat.end();
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+impl std::fmt::Display for UnterminatedKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnterminatedKind::BlockComment => write!(f, "block comment"),
+            UnterminatedKind::CharacterLiteral => write!(f, "character literal"),
+            UnterminatedKind::StringLiteral => write!(f, "string literal"),
+        }
+    }
+}
 
-            let res = open_parenthesis_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::OpenParenthesis;
-                tokens.push(token);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+impl std::fmt::Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LexError::NoMatch { text, line, column } => write!(
+                f,
+                "{}:{}: No match found for the following substring: {}",
+                line, column, text
+            ),
+            LexError::ConstantTooLarge { text, line, column } => write!(
+                f,
+                "{}:{}: Numeric constant is too large: {}",
+                line, column, text
+            ),
+            LexError::InvalidIdentifier { text, line, column } => write!(
+                f,
+                "{}:{}: Invalid identifier starting with digit: {}",
+                line, column, text
+            ),
+            LexError::Unterminated {
+                kind,
+                start_line,
+                start_column,
+            } => write!(f, "{}:{}: Unterminated {}", start_line, start_column, kind),
+        }
+    }
+}
 
-            let res = close_parenthesis_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::CloseParenthesis;
-                tokens.push(token);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+impl std::error::Error for LexError {}
 
-            let res = open_brace_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::OpenBrace;
-                tokens.push(token);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+/// Tokens that consist only of punctuation characters, along with their spelling in C source code.
+///
+/// The order of the entries doesn't matter, see `find_longest_match()`.
+pub(crate) static PUNCTUATORS: [(&str, Token); 41] = [
+    ("(", Token::OpenParenthesis),
+    (")", Token::CloseParenthesis),
+    ("{", Token::OpenBrace),
+    ("}", Token::CloseBrace),
+    (";", Token::Semicolon),
+    ("~", Token::Tilde),
+    ("?", Token::QuestionMark),
+    (":", Token::Colon),
+    (",", Token::Comma),
+    ("+", Token::Plus),
+    ("++", Token::DoublePlus),
+    ("+=", Token::PlusEquals),
+    ("-", Token::Minus),
+    ("--", Token::DoubleMinus),
+    ("-=", Token::MinusEquals),
+    ("*", Token::Asterisk),
+    ("*=", Token::AsteriskEquals),
+    ("/", Token::ForwardSlash),
+    ("/=", Token::ForwardSlashEquals),
+    ("%", Token::Percent),
+    ("%=", Token::PercentEquals),
+    ("!", Token::Exclamation),
+    ("!=", Token::ExclamationEquals),
+    ("=", Token::Equals),
+    ("==", Token::DoubleEquals),
+    ("&", Token::Ampersand),
+    ("&&", Token::DoubleAmpersand),
+    ("&=", Token::AmpersandEquals),
+    ("|", Token::Pipe),
+    ("||", Token::DoublePipe),
+    ("|=", Token::PipeEquals),
+    ("^", Token::Caret),
+    ("^=", Token::CaretEquals),
+    ("<", Token::LeftAngleBracket),
+    ("<=", Token::LeftAngleBracketEquals),
+    ("<<", Token::DoubleLeftAngleBracket),
+    ("<<=", Token::DoubleLeftAngleBracketEquals),
+    (">", Token::RightAngleBracket),
+    (">=", Token::RightAngleBracketEquals),
+    (">>", Token::DoubleRightAngleBracket),
+    (">>=", Token::DoubleRightAngleBracketEquals),
+];
 
-            let res = close_brace_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::CloseBrace;
-                tokens.push(token);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+/// Keywords are lexed as identifiers, and then distinguished from identifiers by looking up the
+/// identifier's text
+fn keyword(lexeme: &str) -> Option<Token> {
+    match lexeme {
+        "int" => Some(Token::IntKeyword),
+        "return" => Some(Token::ReturnKeyword),
+        "if" => Some(Token::IfKeyword),
+        "else" => Some(Token::ElseKeyword),
+        "while" => Some(Token::WhileKeyword),
+        "do" => Some(Token::DoKeyword),
+        "for" => Some(Token::ForKeyword),
+        "break" => Some(Token::BreakKeyword),
+        "continue" => Some(Token::ContinueKeyword),
+        "switch" => Some(Token::SwitchKeyword),
+        "case" => Some(Token::CaseKeyword),
+        "default" => Some(Token::DefaultKeyword),
+        "goto" => Some(Token::GotoKeyword),
+        "long" => Some(Token::LongKeyword),
+        "unsigned" => Some(Token::UnsignedKeyword),
+        "signed" => Some(Token::SignedKeyword),
+        "char" => Some(Token::CharKeyword),
+        "void" => Some(Token::VoidKeyword),
+        _ => None,
+    }
+}
 
-            let res = numeric_constant_regex.find(&line[idx..]);
-            if let Some(mat) = res {
-                let value = mat
-                    .as_str()
-                    .parse::<u8>()
-                    .expect("Match from regex should remove all whitespace");
-                let token = Token::NumericConstant(value);
-                tokens.push(token);
-                idx += mat.end();
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c')
+}
 
-            let res = semicolon_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::Semicolon;
-                tokens.push(token);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+fn is_word_character(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
 
-            let res = decrement_operator_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                panic!("Decrement operator is not supported yet");
-            }
+/// Count the number of bytes at the start of the given bytes that satisfy the given predicate
+fn count_leading(bytes: &[u8], predicate: fn(u8) -> bool) -> usize {
+    bytes
+        .iter()
+        .position(|byte| !predicate(*byte))
+        .unwrap_or(bytes.len())
+}
 
-            let res = minus_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::Minus;
-                tokens.push(token);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
-                continue;
-            }
+/// Find the punctuator that matches the longest prefix of the given text (ie, maximal munch), along
+/// with the length of the match.
+///
+/// If multiple punctuators match a prefix of the same length, the punctuator that appears first
+/// wins.
+fn find_longest_match<'p>(
+    punctuators: &'p [(&str, Token)],
+    text: &str,
+) -> Option<(usize, &'p Token)> {
+    punctuators
+        .iter()
+        .filter(|(spelling, _)| text.starts_with(spelling))
+        .fold(None, |longest, (spelling, token)| match longest {
+            Some((longest_len, _)) if longest_len >= spelling.len() => longest,
+            _ => Some((spelling.len(), token)),
+        })
+}
 
-            let res = tilde_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                let token = Token::Tilde;
-                tokens.push(token);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
+/// Check if the character or string literal at the start of the given text is closed by a matching
+/// quote before the end of the line, skipping over any escaped characters
+fn has_closing_quote(text: &str) -> bool {
+    let mut bytes = text.bytes();
+    let quote = bytes.next();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'\\' => _ = bytes.next(),
+            b'\n' => return false,
+            _ if Some(byte) == quote => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+/// Lexer that converts C source code into tokens on demand, one token per call to `next()`.
+///
+/// Iteration stops after the first error is produced.
+pub struct Lexer<'a> {
+    text: &'a str,
+    /// Absolute byte offset into the text of the next character to lex
+    idx: usize,
+    /// 1-based number of the line that the next character to lex is on
+    line: usize,
+    /// Absolute byte offset into the text of the start of the current line
+    line_start_idx: usize,
+    encountered_error: bool,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(text: &'a str) -> Lexer<'a> {
+        Lexer {
+            text,
+            idx: 0,
+            line: 1,
+            line_start_idx: 0,
+            encountered_error: false,
+        }
+    }
+
+    /// Advance past the given number of bytes of the text.
+    ///
+    /// Newlines are treated as any other character, other than needing to keep track of where
+    /// lines start in order to compute columns.
+    fn advance(&mut self, len: usize) {
+        for (newline_idx, _) in self.text[self.idx..self.idx + len].match_indices('\n') {
+            self.line += 1;
+            self.line_start_idx = self.idx + newline_idx + 1;
+        }
+        self.idx += len;
+    }
+
+    /// Convert the absolute byte offset of the next character to lex into a line and column
+    fn current_span(&self) -> Span {
+        Span {
+            line: self.line,
+            column: self.idx - self.line_start_idx + 1,
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Result<SpannedToken, LexError>> {
+        loop {
+            let remaining = &self.text[self.idx..];
+            let bytes = remaining.as_bytes();
+            let first_byte = *bytes.first()?;
+
+            if is_whitespace(first_byte) {
+                // Advance past the whitespace
+                self.advance(count_leading(bytes, is_whitespace));
                 continue;
             }
 
-            let res = plus_regex.find(&line[idx..]);
-            if let Some(_) = res {
-                tokens.push(Token::Plus);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
+            let span = self.current_span();
+
+            if remaining.starts_with("//") {
+                let comment_len = remaining.find('\n').unwrap_or(remaining.len());
+                self.advance(comment_len);
                 continue;
             }
 
-            if let Some(_) = asterisk_regex.find(&line[idx..]) {
-                tokens.push(Token::Asterisk);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
-                }
+            if let Some(comment_body) = remaining.strip_prefix("/*") {
+                let Some(comment_body_len) = comment_body.find("*/") else {
+                    return Some(Err(LexError::Unterminated {
+                        kind: UnterminatedKind::BlockComment,
+                        start_line: span.line,
+                        start_column: span.column,
+                    }));
+                };
+                self.advance("/*".len() + comment_body_len + "*/".len());
                 continue;
             }
 
-            if let Some(_) = forward_slash_regex.find(&line[idx..]) {
-                tokens.push(Token::ForwardSlash);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
+            let token = match first_byte {
+                b'a'..=b'z' | b'A'..=b'Z' => {
+                    let lexeme = &remaining[..count_leading(bytes, is_word_character)];
+                    self.advance(lexeme.len());
+                    let token =
+                        keyword(lexeme).unwrap_or_else(|| Token::Identifier(lexeme.to_string()));
+                    Ok(token)
                 }
-                continue;
-            }
+                b'0'..=b'9' => {
+                    let digits_len = count_leading(bytes, |byte| byte.is_ascii_digit());
+                    let lexeme_len = count_leading(bytes, is_word_character);
+                    let lexeme = &remaining[..lexeme_len];
+                    self.advance(lexeme_len);
 
-            if let Some(_) = percent_regex.find(&line[idx..]) {
-                tokens.push(Token::Percent);
-                idx += 1;
-                if idx == line.len() {
-                    traversed_entire_line = true;
+                    if lexeme_len > digits_len {
+                        Err(LexError::InvalidIdentifier {
+                            text: lexeme.to_string(),
+                            line: span.line,
+                            column: span.column,
+                        })
+                    } else {
+                        // The lexeme only contains digits, so the only way parsing can fail is if
+                        // the value is too large to be represented
+                        lexeme
+                            .parse::<i64>()
+                            .map(Token::NumericConstant)
+                            .map_err(|_| LexError::ConstantTooLarge {
+                                text: lexeme.to_string(),
+                                line: span.line,
+                                column: span.column,
+                            })
+                    }
                 }
-                continue;
-            }
+                b'\'' | b'"' if !has_closing_quote(remaining) => {
+                    let kind = if first_byte == b'\'' {
+                        UnterminatedKind::CharacterLiteral
+                    } else {
+                        UnterminatedKind::StringLiteral
+                    };
+                    Err(LexError::Unterminated {
+                        kind,
+                        start_line: span.line,
+                        start_column: span.column,
+                    })
+                }
+                _ => match find_longest_match(&PUNCTUATORS, remaining) {
+                    Some((match_len, token)) => {
+                        self.advance(match_len);
+                        Ok(token.clone())
+                    }
+                    // No match was found, so the string contains either:
+                    // - valid C code, but not yet supported
+                    // - invalid C code
+                    //
+                    // These cases should be handled differently, but for now, report the same
+                    // error for both
+                    None => Err(LexError::NoMatch {
+                        text: remaining.lines().next().unwrap_or_default().to_string(),
+                        line: span.line,
+                        column: span.column,
+                    }),
+                },
+            };
+            return Some(token.map(|token| SpannedToken { token, span }));
+        }
+    }
+}
 
-            // No match was found, so the string contains either:
-            // - valid C code, but not yet supported
-            // - invalid C code
-            //
-            // These cases should be handled differently, but for now, panic for both
-            panic!(
-                "No match found for the following substring: {}",
-                &line[idx..]
-            )
+impl Iterator for Lexer<'_> {
+    type Item = Result<SpannedToken, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.encountered_error {
+            return None;
+        }
+
+        let res = self.next_token();
+        if let Some(Err(_)) = res {
+            self.encountered_error = true;
         }
+        res
     }
+}
 
-    tokens
+pub fn lex(text: &str) -> Result<Vec<SpannedToken>, LexError> {
+    Lexer::new(text).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Lex the given C source code and discard the positions of the tokens
+    fn lex_tokens(text: &str) -> Vec<Token> {
+        lex(text)
+            .unwrap()
+            .into_iter()
+            .map(|spanned_token| spanned_token.token)
+            .collect()
+    }
+
     #[test]
     fn create_int_keyword_token_when_found_at_start_of_string() {
         let source_code_string = "int";
         let expected_tokens = vec![Token::IntKeyword];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 
@@ -265,22 +540,39 @@ mod tests {
     fn create_int_keyword_and_main_identifier_tokens() {
         let source_code_string = "int main";
         let expected_tokens = vec![Token::IntKeyword, Token::Identifier("main".to_string())];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 
     #[test]
-    #[should_panic(expected = "No match found for the following substring: ?")]
-    fn panic_if_no_match_found_for_substring() {
-        let source_code_string = "?";
-        lex(source_code_string);
+    fn error_if_no_match_found_for_substring() {
+        let source_code_string = "@";
+        let expected_error = LexError::NoMatch {
+            text: "@".to_string(),
+            line: 1,
+            column: 1,
+        };
+        let res = lex(source_code_string);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn no_match_error_reports_position_of_substring() {
+        let source_code_string = "int main() {\n    return @2;\n}";
+        let expected_error = LexError::NoMatch {
+            text: "@2;".to_string(),
+            line: 2,
+            column: 12,
+        };
+        let res = lex(source_code_string);
+        assert_eq!(res, Err(expected_error));
     }
 
     #[test]
     fn open_parenthesis_token_is_created() {
         let source_code_string = "int main(";
         let expected_last_token = Token::OpenParenthesis;
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens[tokens.len() - 1], expected_last_token);
     }
 
@@ -288,7 +580,7 @@ mod tests {
     fn close_parenthesis_token_is_created() {
         let source_code_string = "int main()";
         let expected_last_token = Token::CloseParenthesis;
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens[tokens.len() - 1], expected_last_token);
     }
 
@@ -296,7 +588,7 @@ mod tests {
     fn open_brace_token_is_created() {
         let source_code_string = "int main() {";
         let expected_last_token = Token::OpenBrace;
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens[tokens.len() - 1], expected_last_token);
     }
 
@@ -304,7 +596,7 @@ mod tests {
     fn close_brace_token_is_created() {
         let source_code_string = "int main() {}";
         let expected_last_token = Token::CloseBrace;
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens[tokens.len() - 1], expected_last_token);
     }
 
@@ -312,7 +604,7 @@ mod tests {
     fn return_keyword_token_is_created() {
         let source_code_string = "int main() {return";
         let expected_last_token = Token::ReturnKeyword;
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens[tokens.len() - 1], expected_last_token);
     }
 
@@ -320,7 +612,7 @@ mod tests {
     fn numeric_constant_token_is_created_with_correct_value() {
         let source_code_string = "int main() {return 2";
         let expected_last_token = Token::NumericConstant(2);
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens[tokens.len() - 1], expected_last_token);
     }
 
@@ -328,7 +620,7 @@ mod tests {
     fn semicolon_token_is_created() {
         let source_code_string = "int main() {return 2;";
         let expected_last_token = Token::Semicolon;
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens[tokens.len() - 1], expected_last_token);
     }
 
@@ -351,7 +643,7 @@ int main() {
             Token::Semicolon,
             Token::CloseBrace,
         ];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 
@@ -370,15 +662,27 @@ int main() {
             Token::Semicolon,
             Token::CloseBrace,
         ];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 
     #[test]
-    #[should_panic(expected = "Decrement operator is not supported yet")]
-    fn panic_if_decrement_operator_detected() {
+    fn double_minus_token_is_created_before_numeric_constant() {
         let source_code_string = "int main() {return --2;}";
-        _ = lex(source_code_string);
+        let expected_tokens = vec![
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::DoubleMinus,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
     }
 
     #[test]
@@ -399,7 +703,7 @@ int main() {
             Token::Semicolon,
             Token::CloseBrace,
         ];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 
@@ -416,7 +720,7 @@ int main() {
             Token::NumericConstant(2),
             Token::Plus,
         ];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 
@@ -433,7 +737,7 @@ int main() {
             Token::NumericConstant(2),
             Token::Asterisk,
         ];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 
@@ -450,7 +754,7 @@ int main() {
             Token::NumericConstant(2),
             Token::ForwardSlash,
         ];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 
@@ -467,7 +771,953 @@ int main() {
             Token::NumericConstant(2),
             Token::Percent,
         ];
-        let tokens = lex(source_code_string);
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn exclamation_token_is_created_before_numeric_constant() {
+        let source_code_string = "return !0;";
+        let expected_tokens = vec![
+            Token::ReturnKeyword,
+            Token::Exclamation,
+            Token::NumericConstant(0),
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn exclamation_token_is_created_before_identifier() {
+        let source_code_string = "return !a;";
+        let expected_tokens = vec![
+            Token::ReturnKeyword,
+            Token::Exclamation,
+            Token::Identifier("a".to_string()),
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn exclamation_token_is_created_before_open_parenthesis() {
+        let source_code_string = "return !(2);";
+        let expected_tokens = vec![
+            Token::ReturnKeyword,
+            Token::Exclamation,
+            Token::OpenParenthesis,
+            Token::NumericConstant(2),
+            Token::CloseParenthesis,
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn exclamation_equals_token_is_created_instead_of_exclamation_token() {
+        let source_code_string = "1 != 2";
+        let expected_tokens = vec![
+            Token::NumericConstant(1),
+            Token::ExclamationEquals,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn double_ampersand_token_is_created_at_end_of_line() {
+        let source_code_string = "return 1 &&";
+        let expected_tokens = vec![
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::DoubleAmpersand,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn double_ampersand_token_is_created_in_middle_of_expression() {
+        let source_code_string = "1&&2";
+        let expected_tokens = vec![
+            Token::NumericConstant(1),
+            Token::DoubleAmpersand,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn whitespace_separated_ampersands_create_two_ampersand_tokens() {
+        let source_code_string = "1 & &2";
+        let expected_tokens = vec![
+            Token::NumericConstant(1),
+            Token::Ampersand,
+            Token::Ampersand,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn double_pipe_token_is_created_between_constants() {
+        let source_code_string = "1||2";
+        let expected_tokens = vec![
+            Token::NumericConstant(1),
+            Token::DoublePipe,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn pipe_token_is_created_between_constants() {
+        let source_code_string = "1|2";
+        let expected_tokens = vec![
+            Token::NumericConstant(1),
+            Token::Pipe,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn equals_token_is_created_in_variable_declaration() {
+        let source_code_string = "int a = 2;";
+        let expected_tokens = vec![
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn double_equals_token_is_created_instead_of_two_equals_tokens() {
+        let source_code_string = "a==2";
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::DoubleEquals,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn compound_assignment_tokens_are_created_adjacent_to_identifiers() {
+        let cases = [
+            ("a+=3", Token::PlusEquals),
+            ("a-=3", Token::MinusEquals),
+            ("a*=3", Token::AsteriskEquals),
+            ("a/=3", Token::ForwardSlashEquals),
+            ("a%=3", Token::PercentEquals),
+            ("a<<=3", Token::DoubleLeftAngleBracketEquals),
+            ("a>>=3", Token::DoubleRightAngleBracketEquals),
+            ("a&=3", Token::AmpersandEquals),
+            ("a|=3", Token::PipeEquals),
+            ("a^=3", Token::CaretEquals),
+        ];
+        for (source_code_string, operator_token) in cases {
+            let expected_tokens = vec![
+                Token::Identifier("a".to_string()),
+                operator_token,
+                Token::NumericConstant(3),
+            ];
+            let tokens = lex_tokens(source_code_string);
+            assert_eq!(tokens, expected_tokens);
+        }
+    }
+
+    #[test]
+    fn angle_bracket_tokens_are_created_with_longest_match() {
+        let source_code_string = "a<b<=c<<d>e>=f>>g";
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::LeftAngleBracket,
+            Token::Identifier("b".to_string()),
+            Token::LeftAngleBracketEquals,
+            Token::Identifier("c".to_string()),
+            Token::DoubleLeftAngleBracket,
+            Token::Identifier("d".to_string()),
+            Token::RightAngleBracket,
+            Token::Identifier("e".to_string()),
+            Token::RightAngleBracketEquals,
+            Token::Identifier("f".to_string()),
+            Token::DoubleRightAngleBracket,
+            Token::Identifier("g".to_string()),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn double_plus_token_is_created_after_identifier() {
+        let source_code_string = "a++";
+        let expected_tokens = vec![Token::Identifier("a".to_string()), Token::DoublePlus];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn double_minus_token_is_created_before_identifier() {
+        let source_code_string = "--a";
+        let expected_tokens = vec![Token::DoubleMinus, Token::Identifier("a".to_string())];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn whitespace_separated_minus_characters_create_two_minus_tokens() {
+        let source_code_string = "- -a";
+        let expected_tokens = vec![
+            Token::Minus,
+            Token::Minus,
+            Token::Identifier("a".to_string()),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn subtraction_of_negated_identifier_creates_two_minus_tokens() {
+        let source_code_string = "a - -b";
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::Minus,
+            Token::Minus,
+            Token::Identifier("b".to_string()),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn caret_token_is_created_at_end_of_line() {
+        let source_code_string = "return 2^";
+        let expected_tokens = vec![
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Caret,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn caret_token_is_created_between_constants() {
+        let source_code_string = "2^3";
+        let expected_tokens = vec![
+            Token::NumericConstant(2),
+            Token::Caret,
+            Token::NumericConstant(3),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn caret_token_is_created_adjacent_to_parentheses() {
+        let source_code_string = "(2)^(3)";
+        let expected_tokens = vec![
+            Token::OpenParenthesis,
+            Token::NumericConstant(2),
+            Token::CloseParenthesis,
+            Token::Caret,
+            Token::OpenParenthesis,
+            Token::NumericConstant(3),
+            Token::CloseParenthesis,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn question_mark_and_colon_tokens_are_created_with_whitespace() {
+        let source_code_string = "a ? 1 : 2";
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::QuestionMark,
+            Token::NumericConstant(1),
+            Token::Colon,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn question_mark_and_colon_tokens_are_created_without_whitespace() {
+        let source_code_string = "a?1:2";
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::QuestionMark,
+            Token::NumericConstant(1),
+            Token::Colon,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn loop_keyword_tokens_are_created() {
+        let cases = [
+            ("while", Token::WhileKeyword),
+            ("do", Token::DoKeyword),
+            ("for", Token::ForKeyword),
+            ("break", Token::BreakKeyword),
+            ("continue", Token::ContinueKeyword),
+        ];
+        for (source_code_string, keyword_token) in cases {
+            let tokens = lex_tokens(source_code_string);
+            assert_eq!(tokens, vec![keyword_token]);
+        }
+    }
+
+    #[test]
+    fn identifiers_prefixed_by_loop_keywords_are_not_keyword_tokens() {
+        for source_code_string in ["dormant", "format", "whilelse", "breaker", "continued"] {
+            let expected_tokens = vec![Token::Identifier(source_code_string.to_string())];
+            let tokens = lex_tokens(source_code_string);
+            assert_eq!(tokens, expected_tokens);
+        }
+    }
+
+    #[test]
+    fn switch_keyword_tokens_are_created() {
+        let source_code_string = "switch (a) { case 1: default: }";
+        let expected_tokens = vec![
+            Token::SwitchKeyword,
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::CaseKeyword,
+            Token::NumericConstant(1),
+            Token::Colon,
+            Token::DefaultKeyword,
+            Token::Colon,
+            Token::CloseBrace,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn if_else_statement_tokens_are_created() {
+        let source_code_string = "if (a) return 1; else return 2;";
+        let expected_tokens = vec![
+            Token::IfKeyword,
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::ElseKeyword,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn goto_statement_tokens_are_created() {
+        let source_code_string = "goto end;";
+        let expected_tokens = vec![
+            Token::GotoKeyword,
+            Token::Identifier("end".to_string()),
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn labeled_statement_tokens_are_created() {
+        let source_code_string = "end: return 2;";
+        let expected_tokens = vec![
+            Token::Identifier("end".to_string()),
+            Token::Colon,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn identifier_prefixed_by_goto_keyword_is_not_keyword_token() {
+        let source_code_string = "gotoend";
+        let expected_tokens = vec![Token::Identifier("gotoend".to_string())];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn type_keyword_tokens_are_created() {
+        let cases = [
+            ("long", Token::LongKeyword),
+            ("unsigned", Token::UnsignedKeyword),
+            ("signed", Token::SignedKeyword),
+            ("char", Token::CharKeyword),
+        ];
+        for (source_code_string, keyword_token) in cases {
+            let tokens = lex_tokens(source_code_string);
+            assert_eq!(tokens, vec![keyword_token]);
+        }
+    }
+
+    #[test]
+    fn identifiers_prefixed_by_type_keywords_are_not_keyword_tokens() {
+        for source_code_string in ["longest", "unsignedness", "signedness", "charge"] {
+            let expected_tokens = vec![Token::Identifier(source_code_string.to_string())];
+            let tokens = lex_tokens(source_code_string);
+            assert_eq!(tokens, expected_tokens);
+        }
+    }
+
+    #[test]
+    fn multi_keyword_type_specifier_tokens_are_created() {
+        let source_code_string = "unsigned long a";
+        let expected_tokens = vec![
+            Token::UnsignedKeyword,
+            Token::LongKeyword,
+            Token::Identifier("a".to_string()),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn numeric_constant_token_is_created_for_value_larger_than_one_byte() {
+        let source_code_string = "return 300;";
+        let expected_tokens = vec![
+            Token::ReturnKeyword,
+            Token::NumericConstant(300),
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn error_if_numeric_constant_is_too_large() {
+        let source_code_string = "return 99999999999999999999;";
+        let expected_error = LexError::ConstantTooLarge {
+            text: "99999999999999999999".to_string(),
+            line: 1,
+            column: 8,
+        };
+        let res = lex(source_code_string);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn spans_are_created_for_tokens_on_first_line() {
+        let source_code_string = "int main";
+        let expected_spanned_tokens = vec![
+            SpannedToken {
+                token: Token::IntKeyword,
+                span: Span { line: 1, column: 1 },
+            },
+            SpannedToken {
+                token: Token::Identifier("main".to_string()),
+                span: Span { line: 1, column: 5 },
+            },
+        ];
+        let spanned_tokens = lex(source_code_string).unwrap();
+        assert_eq!(spanned_tokens, expected_spanned_tokens);
+    }
+
+    #[test]
+    fn spans_are_created_for_tokens_after_leading_whitespace() {
+        let source_code_string = "    return 2;";
+        let expected_spanned_tokens = vec![
+            SpannedToken {
+                token: Token::ReturnKeyword,
+                span: Span { line: 1, column: 5 },
+            },
+            SpannedToken {
+                token: Token::NumericConstant(2),
+                span: Span {
+                    line: 1,
+                    column: 12,
+                },
+            },
+            SpannedToken {
+                token: Token::Semicolon,
+                span: Span {
+                    line: 1,
+                    column: 13,
+                },
+            },
+        ];
+        let spanned_tokens = lex(source_code_string).unwrap();
+        assert_eq!(spanned_tokens, expected_spanned_tokens);
+    }
+
+    #[test]
+    fn spans_are_created_for_tokens_on_later_lines() {
+        let source_code_string = "int main() {\n\n    return 2;\n}";
+        let spanned_tokens = lex(source_code_string).unwrap();
+        let return_keyword_span = spanned_tokens
+            .iter()
+            .find(|spanned_token| spanned_token.token == Token::ReturnKeyword)
+            .map(|spanned_token| spanned_token.span);
+        let close_brace_span = spanned_tokens
+            .last()
+            .map(|spanned_token| spanned_token.span);
+        assert_eq!(return_keyword_span, Some(Span { line: 3, column: 5 }));
+        assert_eq!(close_brace_span, Some(Span { line: 4, column: 1 }));
+    }
+
+    #[test]
+    fn lexer_iterator_yields_same_tokens_as_lex() {
+        let source_code_strings = [
+            "int main() {return ~(-2);}",
+            "
+int main() {
+    return 2;
+
+}
+",
+            "a<b<=c<<d>e>=f>>g",
+            "a<<=3",
+        ];
+        for source_code_string in source_code_strings {
+            let iterated_tokens = Lexer::new(source_code_string)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let tokens = lex(source_code_string).unwrap();
+            assert_eq!(iterated_tokens, tokens);
+        }
+    }
+
+    #[test]
+    fn lexer_iterator_produces_tokens_before_error() {
+        let source_code_string = "return 2 @ 3;";
+        let mut lexer = Lexer::new(source_code_string);
+        let first_token = lexer.next().unwrap().unwrap();
+        let second_token = lexer.next().unwrap().unwrap();
+        assert_eq!(first_token.token, Token::ReturnKeyword);
+        assert_eq!(second_token.token, Token::NumericConstant(2));
+        assert!(matches!(lexer.next(), Some(Err(LexError::NoMatch { .. }))));
+    }
+
+    #[test]
+    fn lexer_iterator_stops_at_first_error() {
+        let source_code_string = "@ 2;";
+        let mut lexer = Lexer::new(source_code_string);
+        assert!(matches!(lexer.next(), Some(Err(LexError::NoMatch { .. }))));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn longest_match_wins_regardless_of_punctuator_order() {
+        let punctuators = [
+            ("<", Token::LeftAngleBracket),
+            ("-", Token::Minus),
+            ("<<", Token::DoubleLeftAngleBracket),
+            ("--", Token::DoubleMinus),
+        ];
+        let longest_match = find_longest_match(&punctuators, "<<2");
+        assert_eq!(longest_match, Some((2, &Token::DoubleLeftAngleBracket)));
+        let longest_match = find_longest_match(&punctuators, "--2");
+        assert_eq!(longest_match, Some((2, &Token::DoubleMinus)));
+    }
+
+    #[test]
+    fn first_punctuator_wins_when_matches_have_same_length() {
+        let punctuators = [
+            ("<", Token::LeftAngleBracket),
+            ("<", Token::RightAngleBracket),
+        ];
+        let longest_match = find_longest_match(&punctuators, "<2");
+        assert_eq!(longest_match, Some((1, &Token::LeftAngleBracket)));
+    }
+
+    #[test]
+    fn double_left_angle_bracket_and_double_minus_tokens_are_created_by_maximal_munch() {
+        let source_code_string = "1<<2--";
+        let expected_tokens = vec![
+            Token::NumericConstant(1),
+            Token::DoubleLeftAngleBracket,
+            Token::NumericConstant(2),
+            Token::DoubleMinus,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn error_if_identifier_starts_with_digit() {
+        let source_code_string = "int 1foo = 2;";
+        let expected_error = LexError::InvalidIdentifier {
+            text: "1foo".to_string(),
+            line: 1,
+            column: 5,
+        };
+        let res = lex(source_code_string);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_digit_is_followed_by_letters_without_hex_support() {
+        let source_code_string = "return 0xff;";
+        let expected_error = LexError::InvalidIdentifier {
+            text: "0xff".to_string(),
+            line: 1,
+            column: 8,
+        };
+        let res = lex(source_code_string);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn identifier_ending_with_digit_token_is_created() {
+        let source_code_string = "int foo1 = 2;";
+        let expected_tokens = vec![
+            Token::IntKeyword,
+            Token::Identifier("foo1".to_string()),
+            Token::Equals,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn operators_followed_by_more_tokens_on_same_line_are_lexed_once() {
+        let cases = [
+            ("1 >> 2;", Token::DoubleRightAngleBracket),
+            ("1 & 2;", Token::Ampersand),
+            ("1 | 2;", Token::Pipe),
+        ];
+        for (source_code_string, operator_token) in cases {
+            let expected_tokens = vec![
+                Token::NumericConstant(1),
+                operator_token,
+                Token::NumericConstant(2),
+                Token::Semicolon,
+            ];
+            let tokens = lex_tokens(source_code_string);
+            assert_eq!(tokens, expected_tokens);
+        }
+    }
+
+    #[test]
+    fn ampersand_token_is_created_between_constants() {
+        let source_code_string = "1&2";
+        let expected_tokens = vec![
+            Token::NumericConstant(1),
+            Token::Ampersand,
+            Token::NumericConstant(2),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn ampersand_and_pipe_tokens_are_created_between_identifiers() {
+        let source_code_string = "a & b | c";
+        let expected_tokens = vec![
+            Token::Identifier("a".to_string()),
+            Token::Ampersand,
+            Token::Identifier("b".to_string()),
+            Token::Pipe,
+            Token::Identifier("c".to_string()),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn repeated_lexing_produces_identical_tokens() {
+        let source_code_string = "int main() {return ~(-2) + 3 << 1;}";
+        let first_tokens = lex(source_code_string).unwrap();
+        let second_tokens = lex(source_code_string).unwrap();
+        assert_eq!(first_tokens, second_tokens);
+    }
+
+    #[test]
+    fn crlf_line_endings_are_lexed_identically_to_lf_line_endings() {
+        let lf_source_code_string = "int main() {\n    return 2;\n}\n";
+        let crlf_source_code_string = "int main() {\r\n    return 2;\r\n}\r\n";
+        let lf_spanned_tokens = lex(lf_source_code_string).unwrap();
+        let crlf_spanned_tokens = lex(crlf_source_code_string).unwrap();
+        assert_eq!(lf_spanned_tokens, crlf_spanned_tokens);
+    }
+
+    #[test]
+    fn token_is_created_for_last_character_of_file_without_trailing_newline() {
+        let source_code_string = "int main() {\n    return 2;\n}";
+        let spanned_tokens = lex(source_code_string).unwrap();
+        let expected_last_spanned_token = SpannedToken {
+            token: Token::CloseBrace,
+            span: Span { line: 3, column: 1 },
+        };
+        assert_eq!(spanned_tokens.last(), Some(&expected_last_spanned_token));
+    }
+
+    #[test]
+    fn identifier_token_is_created_at_end_of_file() {
+        let source_code_string = "return\na";
+        let expected_tokens = vec![Token::ReturnKeyword, Token::Identifier("a".to_string())];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn double_minus_token_is_created_between_constants_by_maximal_munch() {
+        let source_code_string = "2--1";
+        let expected_tokens = vec![
+            Token::NumericConstant(2),
+            Token::DoubleMinus,
+            Token::NumericConstant(1),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn double_plus_token_is_created_between_constants_by_maximal_munch() {
+        let source_code_string = "2++1";
+        let expected_tokens = vec![
+            Token::NumericConstant(2),
+            Token::DoublePlus,
+            Token::NumericConstant(1),
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn keyword_tokens_are_created_by_looking_up_identifier_matches() {
+        let source_code_string = "int main() { return 2; }";
+        let expected_tokens = vec![
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn identifiers_containing_keywords_are_not_keyword_tokens() {
+        for source_code_string in ["integer", "returned", "print", "my_return"] {
+            let expected_tokens = vec![Token::Identifier(source_code_string.to_string())];
+            let tokens = lex_tokens(source_code_string);
+            assert_eq!(tokens, expected_tokens);
+        }
+    }
+
+    #[test]
+    fn keyword_tokens_are_created_at_end_of_line() {
+        let source_code_string = "int\nmain() {return\n2;}";
+        let expected_tokens = vec![
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn comments_are_skipped() {
+        let source_code_string = "int /* a\nblock comment */ main // a line comment\n()";
+        let expected_tokens = vec![
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn spans_account_for_newlines_inside_block_comments() {
+        let source_code_string = "/*\n\n*/  return";
+        let expected_spanned_tokens = vec![SpannedToken {
+            token: Token::ReturnKeyword,
+            span: Span { line: 3, column: 5 },
+        }];
+        let spanned_tokens = lex(source_code_string).unwrap();
+        assert_eq!(spanned_tokens, expected_spanned_tokens);
+    }
+
+    #[test]
+    fn error_reports_start_of_unterminated_block_comment() {
+        let source_code_string =
+            "int main() {\n    int a = 1;\n    /* start of comment\n\n    return a;\n\n\n\n}\n";
+        let expected_error = LexError::Unterminated {
+            kind: UnterminatedKind::BlockComment,
+            start_line: 3,
+            start_column: 5,
+        };
+        let res = lex(source_code_string);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_reports_start_of_unterminated_string_literal_at_end_of_last_line() {
+        let source_code_string = "int main() {\n    return 2;\n} \"";
+        let expected_error = LexError::Unterminated {
+            kind: UnterminatedKind::StringLiteral,
+            start_line: 3,
+            start_column: 3,
+        };
+        let res = lex(source_code_string);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_reports_start_of_unterminated_character_literal() {
+        let source_code_string = "return 'a;\n";
+        let expected_error = LexError::Unterminated {
+            kind: UnterminatedKind::CharacterLiteral,
+            start_line: 1,
+            start_column: 8,
+        };
+        let res = lex(source_code_string);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn lex_large_generated_source() {
+        let statement_count = 80_000;
+        let source_code_string = format!(
+            "int main() {{\n{}}}\n",
+            "    return 2;\n".repeat(statement_count)
+        );
+        assert!(source_code_string.len() > 1_000_000);
+        let tokens = lex_tokens(&source_code_string);
+        assert_eq!(tokens.len(), 5 + 3 * statement_count + 1);
+        assert_eq!(
+            tokens[5..8],
+            [
+                Token::ReturnKeyword,
+                Token::NumericConstant(2),
+                Token::Semicolon,
+            ]
+        );
+        assert_eq!(tokens.last(), Some(&Token::CloseBrace));
+    }
+
+    #[test]
+    fn display_keyword_tokens_as_source_spelling() {
+        let tokens = [
+            (Token::IntKeyword, "int"),
+            (Token::ReturnKeyword, "return"),
+            (Token::IfKeyword, "if"),
+            (Token::ElseKeyword, "else"),
+            (Token::WhileKeyword, "while"),
+            (Token::DoKeyword, "do"),
+            (Token::ForKeyword, "for"),
+            (Token::BreakKeyword, "break"),
+            (Token::ContinueKeyword, "continue"),
+            (Token::SwitchKeyword, "switch"),
+            (Token::CaseKeyword, "case"),
+            (Token::DefaultKeyword, "default"),
+            (Token::GotoKeyword, "goto"),
+            (Token::LongKeyword, "long"),
+            (Token::UnsignedKeyword, "unsigned"),
+            (Token::SignedKeyword, "signed"),
+            (Token::CharKeyword, "char"),
+            (Token::VoidKeyword, "void"),
+        ];
+        for (token, expected_spelling) in tokens {
+            assert_eq!(token.to_string(), expected_spelling);
+        }
+    }
+
+    #[test]
+    fn display_identifier_token_as_name() {
+        let token = Token::Identifier("main".to_string());
+        assert_eq!(token.to_string(), "main");
+    }
+
+    #[test]
+    fn display_numeric_constant_token_as_value() {
+        let token = Token::NumericConstant(2147483648);
+        assert_eq!(token.to_string(), "2147483648");
+    }
+
+    #[test]
+    fn display_punctuator_tokens_as_source_spelling() {
+        for (spelling, token) in PUNCTUATORS.iter() {
+            assert_eq!(token.to_string(), *spelling);
+        }
+    }
+
+    #[test]
+    fn displayed_tokens_lex_back_to_same_tokens() {
+        let source_code_string = "int main() { return ~(-2) % 3 << 1; }";
+        let tokens = lex_tokens(source_code_string);
+        let displayed = tokens
+            .iter()
+            .map(|token| token.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(lex_tokens(&displayed), tokens);
+    }
+
+    #[test]
+    fn void_keyword_token_is_created() {
+        let source_code_string = "int main(void)";
+        let expected_tokens = vec![
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::VoidKeyword,
+            Token::CloseParenthesis,
+        ];
+        let tokens = lex_tokens(source_code_string);
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn comma_token_is_created() {
+        let source_code_string = "int a,int b";
+        let expected_tokens = vec![
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Comma,
+            Token::IntKeyword,
+            Token::Identifier("b".to_string()),
+        ];
+        let tokens = lex_tokens(source_code_string);
         assert_eq!(tokens, expected_tokens);
     }
 }
@@ -2,16 +2,64 @@ use std::{
     collections::VecDeque,
     env::args,
     path::{Path, PathBuf},
+    process::exit,
 };
 
-use c_compiler::{emit, lex, parse};
+use c_compiler::{emit, lex, parse, parse::ir::pass_manager::PassManager};
 
 static ASM_FILE_EXTENSION: &str = "s";
+static EMIT_AST_FLAG: &str = "--emit-ast";
+static OPTIMISE_FLAG: &str = "-O";
+static SELECT_PASSES_FLAG: &str = "--opt=";
+static NO_OPTIMISE_FLAG: &str = "--no-opt";
+static EMIT_IR_FLAG: &str = "--emit-ir=";
+
+/// Whether the argument is one of the flags that the compiler accepts
+fn is_known_flag(arg: &str) -> bool {
+    [EMIT_AST_FLAG, OPTIMISE_FLAG, NO_OPTIMISE_FLAG].contains(&arg)
+        || arg.starts_with(SELECT_PASSES_FLAG)
+        || arg.starts_with(EMIT_IR_FLAG)
+}
 
 fn main() {
-    let args: Vec<String> = args().collect();
+    let args: Vec<String> = args().skip(1).collect();
+    // A misspelt or removed flag would otherwise be silently ignored
+    if let Some(arg) = args
+        .iter()
+        .find(|arg| arg.starts_with('-') && !is_known_flag(arg))
+    {
+        eprintln!("Unknown flag `{}`", arg);
+        exit(1);
+    }
+    // Print the C AST as JSON instead of compiling the C source code to assembly
+    let emit_ast = args.iter().any(|arg| arg == EMIT_AST_FLAG);
+    // Run the default optimisation passes on the IR, or only the given comma-separated passes.
+    // Disabling optimisations takes precedence over either of them.
+    let optimise = args.iter().any(|arg| arg == OPTIMISE_FLAG);
+    let selected_passes = args
+        .iter()
+        .find_map(|arg| arg.strip_prefix(SELECT_PASSES_FLAG));
+    let no_optimise = args.iter().any(|arg| arg == NO_OPTIMISE_FLAG);
+    let pass_manager = match (no_optimise, selected_passes, optimise) {
+        (true, _, _) => PassManager::new(),
+        (false, Some(names), _) => match PassManager::from_pass_names(names) {
+            Ok(pass_manager) => pass_manager,
+            Err(err) => {
+                eprintln!("{}", err);
+                exit(1);
+            }
+        },
+        (false, None, true) => PassManager::default_pipeline(),
+        (false, None, false) => PassManager::new(),
+    };
+    // Print the IR in the given format instead of generating assembly from it
+    let emit_ir_format = args.iter().find_map(|arg| arg.strip_prefix(EMIT_IR_FLAG));
 
-    let input_filepath = Path::new(&args[1]);
+    let input_filepath = Path::new(
+        args.iter()
+            .find(|arg| !arg.starts_with('-'))
+            .expect("Expected path to input C source file"),
+    );
     let asm_file_stem = input_filepath
         .file_stem()
         .expect("Expected filename for input C source file");
@@ -21,10 +69,104 @@ fn main() {
 
     let c_source_code =
         std::fs::read_to_string(input_filepath).expect("Unable to read C source code file");
-    let tokens = lex::lex(&c_source_code);
+    let tokens = match lex::lex(&c_source_code) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            eprintln!("Lexing error: {}", err);
+            exit(1);
+        }
+    };
     let mut token_queue = VecDeque::from(tokens);
-    let c_ast = parse::c::parse_program_definition(&mut token_queue);
-    let ir_ast = parse::ir::parse_program_definition(c_ast);
-    let asm_ast = parse::asm::parse_program_definition(ir_ast);
+    let c_ast = match parse::c::parse_program_definition(&mut token_queue) {
+        Ok(c_ast) => c_ast,
+        Err(err) => {
+            eprintln!("Parsing error: {}", err);
+            exit(1);
+        }
+    };
+
+    if emit_ast {
+        emit_c_ast(&c_ast);
+        return;
+    }
+
+    let c_ast = match parse::semantics::analyse_program_definition(c_ast) {
+        Ok((c_ast, warnings)) => {
+            for warning in warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            c_ast
+        }
+        Err(err) => {
+            eprintln!("Semantic error: {}", err);
+            exit(1);
+        }
+    };
+    let ir_ast = match parse::ir::parse_program_definition(c_ast) {
+        Ok(ir_ast) => ir_ast,
+        Err(err) => {
+            // The C source code is valid, but the compiler doesn't support compiling it yet
+            eprintln!("Internal error: {}", err);
+            exit(1);
+        }
+    };
+    let ir_ast = pass_manager.run_program_definition(ir_ast);
+    // A broken invariant in the IR is a bug in the compiler rather than in the C source code, so is
+    // reported as such rather than going on to generate broken assembly
+    if let Err(errors) = parse::ir::validation::validate_program_definition(&ir_ast) {
+        for err in errors {
+            eprintln!("Internal error: {}", err);
+        }
+        exit(1);
+    }
+    // Finding the unreachable instructions relies on every jump targeting a label in the same
+    // function, so is only done once the IR is known to be valid
+    let ir_ast = parse::ir::optimize::remove_unreachable_instructions_program_definition(ir_ast);
+    if let Some(format) = emit_ir_format {
+        emit_ir(&ir_ast, format);
+        return;
+    }
+    let asm_ast = match parse::asm::parse_program_definition(ir_ast) {
+        Ok(asm_ast) => asm_ast,
+        Err(err) => {
+            // As with lowering to IR, the C source code is valid but can't be compiled yet
+            eprintln!("Internal error: {}", err);
+            exit(1);
+        }
+    };
     emit::emit(&output_filepath, asm_ast).unwrap();
 }
+
+#[cfg(feature = "ast-json")]
+fn emit_c_ast(c_ast: &parse::c::ProgramDefinition) {
+    println!("{}", parse::c::ast_to_json(c_ast));
+}
+
+#[cfg(not(feature = "ast-json"))]
+fn emit_c_ast(_: &parse::c::ProgramDefinition) {
+    eprintln!(
+        "The {} flag requires the compiler to be built with the `ast-json` feature",
+        EMIT_AST_FLAG
+    );
+    exit(1);
+}
+
+#[cfg(feature = "ast-json")]
+fn emit_ir(ir_ast: &parse::ir::ProgramDefinition, format: &str) {
+    match format {
+        "json" => println!("{}", parse::ir::ir_to_json(ir_ast)),
+        _ => {
+            eprintln!("Unsupported IR output format `{}`", format);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(not(feature = "ast-json"))]
+fn emit_ir(_: &parse::ir::ProgramDefinition, _: &str) {
+    eprintln!(
+        "The {} flag requires the compiler to be built with the `ast-json` feature",
+        EMIT_IR_FLAG
+    );
+    exit(1);
+}
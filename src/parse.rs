@@ -1,5 +1,6 @@
 pub mod asm;
 pub mod c;
 pub mod ir;
+pub mod semantics;
 
 type Identifier = String;
@@ -10,29 +10,81 @@ const TMP_VAR_BYTE_LEN: usize = 4;
 #[derive(Debug, PartialEq, Clone)]
 pub enum Reg {
     AX,
+    CX,
+    DX,
     R10D,
+    R11D,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum UnaryOperator {
     Not,
     Neg,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mult,
+    And,
+    Or,
+    Xor,
+    Sal,
+    Sar,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum CondCode {
+    E,
+    NE,
+    L,
+    LE,
+    G,
+    GE,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Operand {
-    Imm(u8),
+    Imm(i64),
     Register(Reg),
     PseudoRegister(crate::parse::Identifier),
-    Stack(i8),
+    Stack(i32),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Instruction {
-    Mov { src: Operand, dst: Operand },
+    Mov {
+        src: Operand,
+        dst: Operand,
+    },
     Ret,
-    Unary { op: UnaryOperator, dst: Operand },
-    AllocateStack(u8),
+    Unary {
+        op: UnaryOperator,
+        dst: Operand,
+    },
+    Binary {
+        op: BinaryOperator,
+        src: Operand,
+        dst: Operand,
+    },
+    Idiv(Operand),
+    Cdq,
+    Cmp {
+        left: Operand,
+        right: Operand,
+    },
+    Jmp(crate::parse::Identifier),
+    JmpCC {
+        condition: CondCode,
+        target: crate::parse::Identifier,
+    },
+    Label(crate::parse::Identifier),
+    SetCC {
+        condition: CondCode,
+        dst: Operand,
+    },
+    AllocateStack(u32),
 }
 
 #[derive(Debug, PartialEq)]
@@ -45,11 +97,164 @@ pub enum FunctionDefinition {
 
 #[derive(Debug, PartialEq)]
 pub enum ProgramDefinition {
-    Program(FunctionDefinition),
+    Program(Vec<FunctionDefinition>),
+}
+
+/// An IR instruction that can't be translated to asm yet. The IR doesn't keep the positions of
+/// constructs in the source code, so the location is the name of the function that the instruction
+/// is in.
+#[derive(Debug, PartialEq)]
+pub struct TranslationError {
+    pub construct: String,
+    pub location: crate::parse::Identifier,
+}
+
+impl std::fmt::Display for TranslationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "translation to asm for {} in function `{}` not implemented",
+            self.construct, self.location
+        )
+    }
 }
 
-pub fn parse_program_definition(ir_ast: ir::ProgramDefinition) -> ProgramDefinition {
-    let asm_ast = first_pass::parse_program_definition(ir_ast);
-    let (asm_ast, stack_offset) = second_pass::parse_program_definition(asm_ast);
-    third_pass::parse_program_definition(asm_ast, stack_offset)
+/// Generate asm from the IR of a program. Instructions that don't have a translation to asm yet are
+/// reported as an error rather than causing a panic.
+pub fn parse_program_definition(
+    ir_ast: ir::ProgramDefinition,
+) -> Result<ProgramDefinition, TranslationError> {
+    let asm_ast = first_pass::parse_program_definition(ir_ast)?;
+    let (asm_ast, stack_offsets) = second_pass::parse_program_definition(asm_ast);
+    Ok(third_pass::parse_program_definition(asm_ast, stack_offsets))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, VecDeque};
+
+    use super::*;
+    use crate::lex::lex;
+    use crate::parse::{c, semantics};
+
+    #[test]
+    fn copy_between_two_pseudo_registers_uses_intermediate_register() {
+        let ir_ast = ir::ProgramDefinition::Program(vec![ir::FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                ir::Instruction::Copy {
+                    src: ir::Value::Constant(5),
+                    dst: ir::Value::Var("a.0".to_string()),
+                },
+                ir::Instruction::Copy {
+                    src: ir::Value::Var("a.0".to_string()),
+                    dst: ir::Value::Var("b.1".to_string()),
+                },
+            ],
+            locations: BTreeMap::new(),
+        }]);
+        let expected_asm_ast = ProgramDefinition::Program(vec![FunctionDefinition::Function {
+            name: "main".to_string(),
+            instructions: vec![
+                Instruction::AllocateStack(2 * TMP_VAR_BYTE_LEN as u32),
+                Instruction::Mov {
+                    src: Operand::Imm(5),
+                    dst: Operand::Stack(-4),
+                },
+                Instruction::Mov {
+                    src: Operand::Stack(-4),
+                    dst: Operand::Register(Reg::R10D),
+                },
+                Instruction::Mov {
+                    src: Operand::Register(Reg::R10D),
+                    dst: Operand::Stack(-8),
+                },
+            ],
+        }]);
+        let asm_ast = parse_program_definition(ir_ast).unwrap();
+        assert_eq!(asm_ast, expected_asm_ast);
+    }
+
+    #[test]
+    fn variable_named_like_temporary_variable_gets_its_own_stack_address() {
+        let source_code = "int main(void) { int tmp0 = 5; return tmp0 + 1; }";
+        let mut tokens = VecDeque::from(lex(source_code).unwrap());
+        let c_ast = c::parse_program_definition(&mut tokens).unwrap();
+        let (c_ast, _) = semantics::analyse_program_definition(c_ast).unwrap();
+        let ir_ast = ir::parse_program_definition(c_ast).unwrap();
+        let asm_ast = first_pass::parse_program_definition(ir_ast).unwrap();
+        let (asm_ast, _) = second_pass::parse_program_definition(asm_ast);
+        let expected_asm_ast = ProgramDefinition::Program(vec![FunctionDefinition::Function {
+            name: "main".to_string(),
+            instructions: vec![
+                // The variable `tmp0`
+                Instruction::Mov {
+                    src: Operand::Imm(5),
+                    dst: Operand::Stack(-4),
+                },
+                // The temporary variable holding the result of the addition
+                Instruction::Mov {
+                    src: Operand::Stack(-4),
+                    dst: Operand::Stack(-8),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    src: Operand::Imm(1),
+                    dst: Operand::Stack(-8),
+                },
+                Instruction::Mov {
+                    src: Operand::Stack(-8),
+                    dst: Operand::Register(Reg::AX),
+                },
+                Instruction::Ret,
+            ],
+        }]);
+        assert_eq!(asm_ast, expected_asm_ast);
+    }
+
+    #[test]
+    fn modulo_of_constants_uses_remainder_in_dx_and_divisor_in_register() {
+        // return 7 % 3;
+        let ir_ast = ir::ProgramDefinition::Program(vec![ir::FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                ir::Instruction::Binary {
+                    op: ir::BinaryOperator::Modulo,
+                    left: ir::Value::Constant(7),
+                    right: ir::Value::Constant(3),
+                    dst: ir::Value::Var("tmp0".to_string()),
+                },
+                ir::Instruction::Return(ir::Value::Var("tmp0".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        }]);
+        let expected_asm_ast = ProgramDefinition::Program(vec![FunctionDefinition::Function {
+            name: "main".to_string(),
+            instructions: vec![
+                Instruction::AllocateStack(TMP_VAR_BYTE_LEN as u32),
+                Instruction::Mov {
+                    src: Operand::Imm(7),
+                    dst: Operand::Register(Reg::AX),
+                },
+                Instruction::Cdq,
+                // `idiv` can't take an immediate divisor
+                Instruction::Mov {
+                    src: Operand::Imm(3),
+                    dst: Operand::Register(Reg::R10D),
+                },
+                Instruction::Idiv(Operand::Register(Reg::R10D)),
+                Instruction::Mov {
+                    src: Operand::Register(Reg::DX),
+                    dst: Operand::Stack(-4),
+                },
+                Instruction::Mov {
+                    src: Operand::Stack(-4),
+                    dst: Operand::Register(Reg::AX),
+                },
+                Instruction::Ret,
+            ],
+        }]);
+        let asm_ast = parse_program_definition(ir_ast).unwrap();
+        assert_eq!(asm_ast, expected_asm_ast);
+    }
 }
@@ -1,12 +1,58 @@
 use crate::parse::asm::{
-    FunctionDefinition, Instruction, Operand, ProgramDefinition, Reg, UnaryOperator,
+    BinaryOperator, CondCode, FunctionDefinition, Instruction, Operand, ProgramDefinition, Reg,
+    TranslationError, UnaryOperator,
 };
 use crate::parse::ir;
 
+/// An IR instruction without a translation to asm yet. It's turned into a [`TranslationError`] once
+/// it reaches the function that it's in.
+#[derive(Debug, PartialEq)]
+pub struct Unsupported(pub &'static str);
+
 pub fn parse_unary_operator(node: ir::UnaryOperator) -> UnaryOperator {
     match node {
         ir::UnaryOperator::BitwiseComplement => UnaryOperator::Not,
         ir::UnaryOperator::Negation => UnaryOperator::Neg,
+        ir::UnaryOperator::Not => {
+            panic!("Logical NOT isn't represented by a unary instruction in asm")
+        }
+    }
+}
+
+pub fn parse_binary_operator(node: ir::BinaryOperator) -> BinaryOperator {
+    match node {
+        ir::BinaryOperator::Add => BinaryOperator::Add,
+        ir::BinaryOperator::Subtract => BinaryOperator::Sub,
+        ir::BinaryOperator::Multiply => BinaryOperator::Mult,
+        ir::BinaryOperator::BitwiseAnd => BinaryOperator::And,
+        ir::BinaryOperator::BitwiseOr => BinaryOperator::Or,
+        ir::BinaryOperator::BitwiseXor => BinaryOperator::Xor,
+        ir::BinaryOperator::ShiftLeft => BinaryOperator::Sal,
+        ir::BinaryOperator::ShiftRight => BinaryOperator::Sar,
+        ir::BinaryOperator::Divide | ir::BinaryOperator::Modulo => {
+            panic!("Division and modulo aren't represented by a binary instruction in asm")
+        }
+        ir::BinaryOperator::Equal
+        | ir::BinaryOperator::NotEqual
+        | ir::BinaryOperator::LessThan
+        | ir::BinaryOperator::LessOrEqual
+        | ir::BinaryOperator::GreaterThan
+        | ir::BinaryOperator::GreaterOrEqual => {
+            panic!("Comparisons aren't represented by a binary instruction in asm")
+        }
+    }
+}
+
+/// The condition under which a comparison operator is true
+pub fn parse_cond_code(node: ir::BinaryOperator) -> CondCode {
+    match node {
+        ir::BinaryOperator::Equal => CondCode::E,
+        ir::BinaryOperator::NotEqual => CondCode::NE,
+        ir::BinaryOperator::LessThan => CondCode::L,
+        ir::BinaryOperator::LessOrEqual => CondCode::LE,
+        ir::BinaryOperator::GreaterThan => CondCode::G,
+        ir::BinaryOperator::GreaterOrEqual => CondCode::GE,
+        _ => panic!("Only comparison operators have a condition code"),
     }
 }
 
@@ -17,12 +63,35 @@ pub fn parse_operand(node: ir::Value) -> Operand {
     }
 }
 
-pub fn parse_instructions(node: ir::Instruction) -> Vec<Instruction> {
-    match node {
+pub fn parse_instructions(node: ir::Instruction) -> Result<Vec<Instruction>, Unsupported> {
+    let instructions = match node {
         ir::Instruction::Return(val) => {
             let src = parse_operand(val);
             let dst = Operand::Register(Reg::AX);
-            vec![Instruction::Mov { src: src, dst: dst }, Instruction::Ret]
+            vec![Instruction::Mov { src, dst }, Instruction::Ret]
+        }
+        ir::Instruction::Unary {
+            op: ir::UnaryOperator::Not,
+            src,
+            dst,
+        } => {
+            // Logical NOT is a comparison against zero, with the destination zeroed beforehand
+            // since `set<cc>` only writes the lowest byte of it
+            let dst = parse_operand(dst);
+            vec![
+                Instruction::Cmp {
+                    left: Operand::Imm(0),
+                    right: parse_operand(src),
+                },
+                Instruction::Mov {
+                    src: Operand::Imm(0),
+                    dst: dst.clone(),
+                },
+                Instruction::SetCC {
+                    condition: CondCode::E,
+                    dst,
+                },
+            ]
         }
         ir::Instruction::Unary { op, src, dst } => {
             let op = parse_unary_operator(op);
@@ -30,44 +99,161 @@ pub fn parse_instructions(node: ir::Instruction) -> Vec<Instruction> {
             let dst = parse_operand(dst);
             vec![
                 Instruction::Mov {
-                    src: src,
+                    src,
                     dst: dst.clone(),
                 },
-                Instruction::Unary { op: op, dst: dst },
+                Instruction::Unary { op, dst },
             ]
         }
-    }
+        ir::Instruction::Binary {
+            op: op @ (ir::BinaryOperator::Divide | ir::BinaryOperator::Modulo),
+            left,
+            right,
+            dst,
+        } => {
+            // `idiv` divides the value in EDX:EAX by its operand, and stores the quotient in EAX
+            // and the remainder in EDX
+            let result_reg = match op {
+                ir::BinaryOperator::Divide => Reg::AX,
+                _ => Reg::DX,
+            };
+            vec![
+                Instruction::Mov {
+                    src: parse_operand(left),
+                    dst: Operand::Register(Reg::AX),
+                },
+                Instruction::Cdq,
+                Instruction::Idiv(parse_operand(right)),
+                Instruction::Mov {
+                    src: Operand::Register(result_reg),
+                    dst: parse_operand(dst),
+                },
+            ]
+        }
+        ir::Instruction::Binary {
+            op:
+                op @ (ir::BinaryOperator::Equal
+                | ir::BinaryOperator::NotEqual
+                | ir::BinaryOperator::LessThan
+                | ir::BinaryOperator::LessOrEqual
+                | ir::BinaryOperator::GreaterThan
+                | ir::BinaryOperator::GreaterOrEqual),
+            left,
+            right,
+            dst,
+        } => {
+            // `cmp` sets the flags according to the result of subtracting its first operand from
+            // its second operand, so the operands are in the opposite order to the IR
+            let condition = parse_cond_code(op);
+            let dst = parse_operand(dst);
+            vec![
+                Instruction::Cmp {
+                    left: parse_operand(right),
+                    right: parse_operand(left),
+                },
+                Instruction::Mov {
+                    src: Operand::Imm(0),
+                    dst: dst.clone(),
+                },
+                Instruction::SetCC { condition, dst },
+            ]
+        }
+        ir::Instruction::Binary {
+            op,
+            left,
+            right,
+            dst,
+        } => {
+            let op = parse_binary_operator(op);
+            let dst = parse_operand(dst);
+            vec![
+                Instruction::Mov {
+                    src: parse_operand(left),
+                    dst: dst.clone(),
+                },
+                Instruction::Binary {
+                    op,
+                    src: parse_operand(right),
+                    dst,
+                },
+            ]
+        }
+        ir::Instruction::Copy { src, dst } => vec![Instruction::Mov {
+            src: parse_operand(src),
+            dst: parse_operand(dst),
+        }],
+        ir::Instruction::Jump(target) => vec![Instruction::Jmp(target)],
+        ir::Instruction::JumpIfZero { condition, target } => vec![
+            Instruction::Cmp {
+                left: Operand::Imm(0),
+                right: parse_operand(condition),
+            },
+            Instruction::JmpCC {
+                condition: CondCode::E,
+                target,
+            },
+        ],
+        ir::Instruction::JumpIfNotZero { condition, target } => vec![
+            Instruction::Cmp {
+                left: Operand::Imm(0),
+                right: parse_operand(condition),
+            },
+            Instruction::JmpCC {
+                condition: CondCode::NE,
+                target,
+            },
+        ],
+        ir::Instruction::Label(identifier) => vec![Instruction::Label(identifier)],
+        ir::Instruction::FunCall { .. } => return Err(Unsupported("function call")),
+    };
+    Ok(instructions)
 }
 
-pub fn parse_function_definition(node: ir::FunctionDefinition) -> FunctionDefinition {
+pub fn parse_function_definition(
+    node: ir::FunctionDefinition,
+) -> Result<FunctionDefinition, TranslationError> {
     match node {
-        ir::FunctionDefinition::Function { identifier, body } => {
+        ir::FunctionDefinition::Function {
+            identifier, body, ..
+        } => {
             let mut all_asm_instructions = Vec::new();
 
             for ir_instruction in body.into_iter() {
-                let mut asm_instructions = parse_instructions(ir_instruction);
+                let mut asm_instructions =
+                    parse_instructions(ir_instruction).map_err(|Unsupported(construct)| {
+                        TranslationError {
+                            construct: construct.to_string(),
+                            location: identifier.clone(),
+                        }
+                    })?;
                 all_asm_instructions.append(&mut asm_instructions);
             }
 
-            FunctionDefinition::Function {
+            Ok(FunctionDefinition::Function {
                 name: identifier,
                 instructions: all_asm_instructions,
-            }
+            })
         }
     }
 }
 
-pub fn parse_program_definition(node: ir::ProgramDefinition) -> ProgramDefinition {
+pub fn parse_program_definition(
+    node: ir::ProgramDefinition,
+) -> Result<ProgramDefinition, TranslationError> {
     match node {
-        ir::ProgramDefinition::Program(ir_func_defn) => {
-            let asm_function_definition = parse_function_definition(ir_func_defn);
-            ProgramDefinition::Program(asm_function_definition)
+        ir::ProgramDefinition::Program(ir_func_defns) => {
+            let asm_function_definitions = ir_func_defns
+                .into_iter()
+                .map(parse_function_definition)
+                .collect::<Result<Vec<FunctionDefinition>, TranslationError>>()?;
+            Ok(ProgramDefinition::Program(asm_function_definitions))
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
 
     use super::*;
 
@@ -117,7 +303,7 @@ mod tests {
             },
             Instruction::Ret,
         ];
-        let asm_ast_instruction_nodes = parse_instructions(ir_return_instruction_ast_node);
+        let asm_ast_instruction_nodes = parse_instructions(ir_return_instruction_ast_node).unwrap();
         assert_eq!(
             asm_ast_instruction_nodes,
             expected_asm_ast_instruction_nodes
@@ -146,7 +332,7 @@ mod tests {
                 dst: asm_instructions_same_dst,
             },
         ];
-        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node);
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
         assert_eq!(
             asm_instruction_ast_nodes,
             expected_asm_instruction_ast_nodes
@@ -162,6 +348,7 @@ mod tests {
         let ir_function_defn_ast_node = ir::FunctionDefinition::Function {
             identifier: identifier.to_string(),
             body: ir_return_instruction_ast_nodes,
+            locations: BTreeMap::new(),
         };
         let expected_asm_instructions = vec![
             Instruction::Mov {
@@ -174,7 +361,7 @@ mod tests {
             name: identifier.to_string(),
             instructions: expected_asm_instructions,
         };
-        let asm_ast_node = parse_function_definition(ir_function_defn_ast_node);
+        let asm_ast_node = parse_function_definition(ir_function_defn_ast_node).unwrap();
         assert_eq!(asm_ast_node, expected_asm_ast_node);
     }
 
@@ -187,8 +374,10 @@ mod tests {
         let ir_function_defn_ast_node = ir::FunctionDefinition::Function {
             identifier: identifier.to_string(),
             body: ir_return_instruction_ast_nodes,
+            locations: BTreeMap::new(),
         };
-        let ir_program_defn_ast_node = ir::ProgramDefinition::Program(ir_function_defn_ast_node);
+        let ir_program_defn_ast_node =
+            ir::ProgramDefinition::Program(vec![ir_function_defn_ast_node]);
         let asm_instructions = vec![
             Instruction::Mov {
                 src: Operand::Imm(value),
@@ -200,8 +389,356 @@ mod tests {
             name: identifier.to_string(),
             instructions: asm_instructions,
         };
-        let expected_asm_ast_node = ProgramDefinition::Program(asm_function_defn_ast_node);
-        let asm_ast_node = parse_program_definition(ir_program_defn_ast_node);
+        let expected_asm_ast_node = ProgramDefinition::Program(vec![asm_function_defn_ast_node]);
+        let asm_ast_node = parse_program_definition(ir_program_defn_ast_node).unwrap();
+        assert_eq!(asm_ast_node, expected_asm_ast_node);
+    }
+
+    #[test]
+    fn parse_ir_add_operator_to_asm_binary_operator() {
+        let ir_ast_node = ir::BinaryOperator::Add;
+        let expected_asm_ast_node = BinaryOperator::Add;
+        let asm_ast_node = parse_binary_operator(ir_ast_node);
+        assert_eq!(asm_ast_node, expected_asm_ast_node);
+    }
+
+    #[test]
+    fn parse_ir_add_instruction_to_asm_instructions() {
+        let tmp_var_identifier = "tmp0";
+        let ir_instruction_ast_node = ir::Instruction::Binary {
+            op: ir::BinaryOperator::Add,
+            left: ir::Value::Constant(1),
+            right: ir::Value::Constant(2),
+            dst: ir::Value::Var(tmp_var_identifier.to_string()),
+        };
+        let asm_instructions_same_dst = Operand::PseudoRegister(tmp_var_identifier.to_string());
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Imm(1),
+                dst: asm_instructions_same_dst.clone(),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::Add,
+                src: Operand::Imm(2),
+                dst: asm_instructions_same_dst,
+            },
+        ];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn parse_ir_divide_instruction_to_asm_instructions_using_quotient_in_ax() {
+        let tmp_var_identifier = "tmp0";
+        let ir_instruction_ast_node = ir::Instruction::Binary {
+            op: ir::BinaryOperator::Divide,
+            left: ir::Value::Constant(7),
+            right: ir::Value::Constant(3),
+            dst: ir::Value::Var(tmp_var_identifier.to_string()),
+        };
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Imm(7),
+                dst: Operand::Register(Reg::AX),
+            },
+            Instruction::Cdq,
+            Instruction::Idiv(Operand::Imm(3)),
+            Instruction::Mov {
+                src: Operand::Register(Reg::AX),
+                dst: Operand::PseudoRegister(tmp_var_identifier.to_string()),
+            },
+        ];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn parse_ir_modulo_instruction_to_asm_instructions_using_remainder_in_dx() {
+        let tmp_var_identifier = "tmp0";
+        let ir_instruction_ast_node = ir::Instruction::Binary {
+            op: ir::BinaryOperator::Modulo,
+            left: ir::Value::Constant(7),
+            right: ir::Value::Constant(3),
+            dst: ir::Value::Var(tmp_var_identifier.to_string()),
+        };
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Imm(7),
+                dst: Operand::Register(Reg::AX),
+            },
+            Instruction::Cdq,
+            Instruction::Idiv(Operand::Imm(3)),
+            Instruction::Mov {
+                src: Operand::Register(Reg::DX),
+                dst: Operand::PseudoRegister(tmp_var_identifier.to_string()),
+            },
+        ];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn parse_ir_program_defn_with_two_function_defns_to_asm_program_defn() {
+        let ir_program_defn_ast_node = ir::ProgramDefinition::Program(vec![
+            ir::FunctionDefinition::Function {
+                identifier: "foo".to_string(),
+                body: vec![ir::Instruction::Return(ir::Value::Constant(1))],
+                locations: BTreeMap::new(),
+            },
+            ir::FunctionDefinition::Function {
+                identifier: "main".to_string(),
+                body: vec![ir::Instruction::Return(ir::Value::Constant(2))],
+                locations: BTreeMap::new(),
+            },
+        ]);
+        let expected_asm_ast_node = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                instructions: vec![
+                    Instruction::Mov {
+                        src: Operand::Imm(1),
+                        dst: Operand::Register(Reg::AX),
+                    },
+                    Instruction::Ret,
+                ],
+            },
+            FunctionDefinition::Function {
+                name: "main".to_string(),
+                instructions: vec![
+                    Instruction::Mov {
+                        src: Operand::Imm(2),
+                        dst: Operand::Register(Reg::AX),
+                    },
+                    Instruction::Ret,
+                ],
+            },
+        ]);
+        let asm_ast_node = parse_program_definition(ir_program_defn_ast_node).unwrap();
         assert_eq!(asm_ast_node, expected_asm_ast_node);
     }
+
+    #[test]
+    fn parse_ir_copy_instruction_to_asm_mov_instruction() {
+        let ir_instruction_ast_node = ir::Instruction::Copy {
+            src: ir::Value::Constant(5),
+            dst: ir::Value::Var("a.0".to_string()),
+        };
+        let expected_asm_instruction_ast_nodes = vec![Instruction::Mov {
+            src: Operand::Imm(5),
+            dst: Operand::PseudoRegister("a.0".to_string()),
+        }];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn parse_ir_jump_if_zero_instruction_to_asm_compare_and_conditional_jump() {
+        let ir_instruction_ast_node = ir::Instruction::JumpIfZero {
+            condition: ir::Value::Var("tmp0".to_string()),
+            target: "and_false0".to_string(),
+        };
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Cmp {
+                left: Operand::Imm(0),
+                right: Operand::PseudoRegister("tmp0".to_string()),
+            },
+            Instruction::JmpCC {
+                condition: CondCode::E,
+                target: "and_false0".to_string(),
+            },
+        ];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn parse_ir_jump_and_label_instructions_to_asm_instructions() {
+        let asm_jump_ast_nodes =
+            parse_instructions(ir::Instruction::Jump("and_end0".to_string())).unwrap();
+        assert_eq!(
+            asm_jump_ast_nodes,
+            vec![Instruction::Jmp("and_end0".to_string())]
+        );
+        let asm_label_ast_nodes =
+            parse_instructions(ir::Instruction::Label("and_end0".to_string())).unwrap();
+        assert_eq!(
+            asm_label_ast_nodes,
+            vec![Instruction::Label("and_end0".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_ir_jump_if_not_zero_instruction_to_asm_compare_and_conditional_jump() {
+        let ir_instruction_ast_node = ir::Instruction::JumpIfNotZero {
+            condition: ir::Value::Constant(1),
+            target: "or_true0".to_string(),
+        };
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Cmp {
+                left: Operand::Imm(0),
+                right: Operand::Imm(1),
+            },
+            Instruction::JmpCC {
+                condition: CondCode::NE,
+                target: "or_true0".to_string(),
+            },
+        ];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn parse_ir_logical_not_instruction_to_asm_compare_and_set_instructions() {
+        let ir_instruction_ast_node = ir::Instruction::Unary {
+            op: ir::UnaryOperator::Not,
+            src: ir::Value::Constant(5),
+            dst: ir::Value::Var("tmp0".to_string()),
+        };
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Cmp {
+                left: Operand::Imm(0),
+                right: Operand::Imm(5),
+            },
+            Instruction::Mov {
+                src: Operand::Imm(0),
+                dst: Operand::PseudoRegister("tmp0".to_string()),
+            },
+            Instruction::SetCC {
+                condition: CondCode::E,
+                dst: Operand::PseudoRegister("tmp0".to_string()),
+            },
+        ];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn parse_ir_comparison_operators_to_asm_condition_codes() {
+        let operators = [
+            (ir::BinaryOperator::Equal, CondCode::E),
+            (ir::BinaryOperator::NotEqual, CondCode::NE),
+            (ir::BinaryOperator::LessThan, CondCode::L),
+            (ir::BinaryOperator::LessOrEqual, CondCode::LE),
+            (ir::BinaryOperator::GreaterThan, CondCode::G),
+            (ir::BinaryOperator::GreaterOrEqual, CondCode::GE),
+        ];
+        for (ir_ast_node, expected_asm_ast_node) in operators {
+            let asm_ast_node = parse_cond_code(ir_ast_node);
+            assert_eq!(asm_ast_node, expected_asm_ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_ir_less_than_instruction_to_asm_compare_and_set_instructions() {
+        let ir_instruction_ast_node = ir::Instruction::Binary {
+            op: ir::BinaryOperator::LessThan,
+            left: ir::Value::Constant(1),
+            right: ir::Value::Var("tmp0".to_string()),
+            dst: ir::Value::Var("tmp1".to_string()),
+        };
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Cmp {
+                left: Operand::PseudoRegister("tmp0".to_string()),
+                right: Operand::Imm(1),
+            },
+            Instruction::Mov {
+                src: Operand::Imm(0),
+                dst: Operand::PseudoRegister("tmp1".to_string()),
+            },
+            Instruction::SetCC {
+                condition: CondCode::L,
+                dst: Operand::PseudoRegister("tmp1".to_string()),
+            },
+        ];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn parse_ir_bitwise_operators_to_asm_binary_operators() {
+        let operators = [
+            (ir::BinaryOperator::BitwiseAnd, BinaryOperator::And),
+            (ir::BinaryOperator::BitwiseOr, BinaryOperator::Or),
+            (ir::BinaryOperator::BitwiseXor, BinaryOperator::Xor),
+        ];
+        for (ir_ast_node, expected_asm_ast_node) in operators {
+            let asm_ast_node = parse_binary_operator(ir_ast_node);
+            assert_eq!(asm_ast_node, expected_asm_ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_ir_bitwise_and_instruction_to_asm_instructions() {
+        let ir_instruction_ast_node = ir::Instruction::Binary {
+            op: ir::BinaryOperator::BitwiseAnd,
+            left: ir::Value::Constant(12),
+            right: ir::Value::Constant(10),
+            dst: ir::Value::Var("tmp0".to_string()),
+        };
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Imm(12),
+                dst: Operand::PseudoRegister("tmp0".to_string()),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::And,
+                src: Operand::Imm(10),
+                dst: Operand::PseudoRegister("tmp0".to_string()),
+            },
+        ];
+        let asm_instruction_ast_nodes = parse_instructions(ir_instruction_ast_node).unwrap();
+        assert_eq!(
+            asm_instruction_ast_nodes,
+            expected_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn error_if_function_defn_contains_function_call() {
+        let ir_program_defn_ast_node =
+            ir::ProgramDefinition::Program(vec![ir::FunctionDefinition::Function {
+                identifier: "main".to_string(),
+                body: vec![
+                    ir::Instruction::FunCall {
+                        name: "foo".to_string(),
+                        args: vec![],
+                        dst: ir::Value::Var("tmp0".to_string()),
+                    },
+                    ir::Instruction::Return(ir::Value::Var("tmp0".to_string())),
+                ],
+                locations: BTreeMap::new(),
+            }]);
+        let expected_err = TranslationError {
+            construct: "function call".to_string(),
+            location: "main".to_string(),
+        };
+        assert_eq!(
+            parse_program_definition(ir_program_defn_ast_node),
+            Err(expected_err)
+        );
+    }
 }
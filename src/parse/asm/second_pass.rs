@@ -4,12 +4,12 @@ use crate::parse::asm::{
 
 use std::collections::HashMap;
 
-pub fn parse_operand(node: Operand, map: &mut HashMap<String, i8>, offset: &mut i8) -> Operand {
+pub fn parse_operand(node: Operand, map: &mut HashMap<String, i32>, offset: &mut i32) -> Operand {
     match node {
         Operand::PseudoRegister(identifier) => match map.get(&identifier) {
             Some(value) => Operand::Stack(*value),
             None => {
-                *offset -= TMP_VAR_BYTE_LEN as i8;
+                *offset -= TMP_VAR_BYTE_LEN as i32;
                 (*map).insert(identifier.to_string(), *offset);
                 Operand::Stack(*offset)
             }
@@ -18,9 +18,9 @@ pub fn parse_operand(node: Operand, map: &mut HashMap<String, i8>, offset: &mut
     }
 }
 
-pub fn parse_instructions(nodes: Vec<Instruction>, stack_offset: &mut i8) -> Vec<Instruction> {
+pub fn parse_instructions(nodes: Vec<Instruction>, stack_offset: &mut i32) -> Vec<Instruction> {
     let mut instructions = Vec::new();
-    let mut map: HashMap<String, i8> = HashMap::new();
+    let mut map: HashMap<String, i32> = HashMap::new();
 
     for instruction in nodes.into_iter() {
         match instruction {
@@ -33,10 +33,32 @@ pub fn parse_instructions(nodes: Vec<Instruction>, stack_offset: &mut i8) -> Vec
                 let dst = parse_operand(dst, &mut map, stack_offset);
                 instructions.push(Instruction::Unary { op, dst });
             }
+            Instruction::Binary { op, src, dst } => {
+                let src = parse_operand(src, &mut map, stack_offset);
+                let dst = parse_operand(dst, &mut map, stack_offset);
+                instructions.push(Instruction::Binary { op, src, dst });
+            }
+            Instruction::Idiv(operand) => {
+                let operand = parse_operand(operand, &mut map, stack_offset);
+                instructions.push(Instruction::Idiv(operand));
+            }
+            Instruction::Cmp { left, right } => {
+                let left = parse_operand(left, &mut map, stack_offset);
+                let right = parse_operand(right, &mut map, stack_offset);
+                instructions.push(Instruction::Cmp { left, right });
+            }
+            Instruction::SetCC { condition, dst } => {
+                let dst = parse_operand(dst, &mut map, stack_offset);
+                instructions.push(Instruction::SetCC { condition, dst });
+            }
             Instruction::AllocateStack(_) => {
                 panic!("Stack allocation instruction shouldn't be present in second pass")
             }
-            Instruction::Ret => instructions.push(instruction),
+            Instruction::Ret
+            | Instruction::Cdq
+            | Instruction::Jmp(_)
+            | Instruction::JmpCC { .. }
+            | Instruction::Label(_) => instructions.push(instruction),
         }
     }
 
@@ -45,7 +67,7 @@ pub fn parse_instructions(nodes: Vec<Instruction>, stack_offset: &mut i8) -> Vec
 
 pub fn parse_function_definition(
     node: FunctionDefinition,
-    stack_offset: &mut i8,
+    stack_offset: &mut i32,
 ) -> FunctionDefinition {
     match node {
         FunctionDefinition::Function { name, instructions } => FunctionDefinition::Function {
@@ -55,14 +77,24 @@ pub fn parse_function_definition(
     }
 }
 
-pub fn parse_program_definition(node: ProgramDefinition) -> (ProgramDefinition, i8) {
-    let mut stack_offset = 0;
-
+/// Replace pseudo-registers with stack addresses in each function, returning the stack offset
+/// that each function needs alongside the transformed program
+pub fn parse_program_definition(node: ProgramDefinition) -> (ProgramDefinition, Vec<i32>) {
     match node {
-        ProgramDefinition::Program(func_defn) => {
-            let program_defn =
-                ProgramDefinition::Program(parse_function_definition(func_defn, &mut stack_offset));
-            (program_defn, stack_offset)
+        ProgramDefinition::Program(func_defns) => {
+            let mut stack_offsets = Vec::new();
+            let func_defns = func_defns
+                .into_iter()
+                .map(|func_defn| {
+                    // Each function has its own stack frame, so stack offsets start from zero
+                    // for every function
+                    let mut stack_offset = 0;
+                    let func_defn = parse_function_definition(func_defn, &mut stack_offset);
+                    stack_offsets.push(stack_offset);
+                    func_defn
+                })
+                .collect();
+            (ProgramDefinition::Program(func_defns), stack_offsets)
         }
     }
 }
@@ -70,31 +102,29 @@ pub fn parse_program_definition(node: ProgramDefinition) -> (ProgramDefinition,
 #[cfg(test)]
 mod tests {
 
-    use crate::parse::asm::UnaryOperator;
+    use crate::parse::asm::{CondCode, UnaryOperator};
 
     use super::*;
 
     #[test]
     fn convert_pseudo_register_to_stack_address_and_update_hash_table_and_offset() {
         let mut offset = 0;
-        let mut map: HashMap<String, i8> = HashMap::new();
+        let mut map: HashMap<String, i32> = HashMap::new();
         let identifier = "tmp0";
         let input_asm_ast_node = Operand::PseudoRegister(identifier.to_string());
-        let expected_output_asm_ast_node = Operand::Stack(-(TMP_VAR_BYTE_LEN as i8));
+        let expected_output_asm_ast_node = Operand::Stack(-(TMP_VAR_BYTE_LEN as i32));
         let transformed_asm_ast_node = parse_operand(input_asm_ast_node, &mut map, &mut offset);
-        assert_eq!(-(TMP_VAR_BYTE_LEN as i8), offset);
-        assert_eq!(
-            true,
-            map.get(identifier)
-                .is_some_and(|val| *val == -(TMP_VAR_BYTE_LEN as i8))
-        );
+        assert_eq!(-(TMP_VAR_BYTE_LEN as i32), offset);
+        assert!(map
+            .get(identifier)
+            .is_some_and(|val| *val == -(TMP_VAR_BYTE_LEN as i32)));
         assert_eq!(expected_output_asm_ast_node, transformed_asm_ast_node);
     }
 
     #[test]
     fn non_pseudo_register_operand_is_left_unchanged() {
         let mut offset = 0;
-        let mut map: HashMap<String, i8> = HashMap::new();
+        let mut map: HashMap<String, i32> = HashMap::new();
         let value = 2;
         let input_asm_ast_node = Operand::Imm(value);
         let output_asm_ast_node = parse_operand(input_asm_ast_node.clone(), &mut map, &mut offset);
@@ -119,7 +149,7 @@ mod tests {
             },
         ];
         let expected_asm_instructions_same_stack_addr_dst =
-            Operand::Stack(-(TMP_VAR_BYTE_LEN as i8));
+            Operand::Stack(-(TMP_VAR_BYTE_LEN as i32));
         let expected_asm_instruction_ast_nodes = vec![
             Instruction::Mov {
                 src: Operand::Imm(value),
@@ -139,12 +169,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn convert_pseudo_register_operands_of_cmp_instruction_to_stack_addresses() {
+        let input_asm_instruction_ast_nodes = vec![Instruction::Cmp {
+            left: Operand::PseudoRegister("tmp0".to_string()),
+            right: Operand::PseudoRegister("tmp1".to_string()),
+        }];
+        let expected_asm_instruction_ast_nodes = vec![Instruction::Cmp {
+            left: Operand::Stack(-(TMP_VAR_BYTE_LEN as i32)),
+            right: Operand::Stack(-2 * TMP_VAR_BYTE_LEN as i32),
+        }];
+        let mut stack_offset = 0;
+        let output_asm_instruction_ast_nodes =
+            parse_instructions(input_asm_instruction_ast_nodes, &mut stack_offset);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn convert_pseudo_register_dst_of_setcc_instruction_to_stack_address() {
+        let input_asm_instruction_ast_nodes = vec![Instruction::SetCC {
+            condition: CondCode::L,
+            dst: Operand::PseudoRegister("tmp0".to_string()),
+        }];
+        let expected_asm_instruction_ast_nodes = vec![Instruction::SetCC {
+            condition: CondCode::L,
+            dst: Operand::Stack(-(TMP_VAR_BYTE_LEN as i32)),
+        }];
+        let mut stack_offset = 0;
+        let output_asm_instruction_ast_nodes =
+            parse_instructions(input_asm_instruction_ast_nodes, &mut stack_offset);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn dont_transform_jump_and_label_instructions() {
+        let instructions = vec![
+            Instruction::JmpCC {
+                condition: CondCode::E,
+                target: "if_else0".to_string(),
+            },
+            Instruction::Jmp("if_end1".to_string()),
+            Instruction::Label("if_else0".to_string()),
+            Instruction::Label("if_end1".to_string()),
+        ];
+        let mut stack_offset = 0;
+        let output_asm_instruction_ast_nodes =
+            parse_instructions(instructions.clone(), &mut stack_offset);
+        assert_eq!(output_asm_instruction_ast_nodes, instructions);
+        assert_eq!(stack_offset, 0);
+    }
+
+    #[test]
+    fn stack_offsets_beyond_one_byte_are_not_truncated() {
+        let temporaries_count = 40;
+        let input_asm_instruction_ast_nodes: Vec<Instruction> = (0..temporaries_count)
+            .map(|i| Instruction::Mov {
+                src: Operand::Imm(i),
+                dst: Operand::PseudoRegister(format!("tmp{}", i)),
+            })
+            .collect();
+        let mut stack_offset = 0;
+        let output_asm_instruction_ast_nodes =
+            parse_instructions(input_asm_instruction_ast_nodes, &mut stack_offset);
+        assert_eq!(stack_offset, -160);
+        assert_eq!(
+            output_asm_instruction_ast_nodes.last(),
+            Some(&Instruction::Mov {
+                src: Operand::Imm(temporaries_count - 1),
+                dst: Operand::Stack(-160),
+            })
+        );
+    }
+
     #[test]
     #[should_panic(expected = "Stack allocation instruction shouldn't be present in second pass")]
     fn panic_if_allocate_stack_instruction_encountered() {
         let mut stack_offset = -4;
         let input_asm_instruction_ast_nodes =
-            vec![Instruction::AllocateStack(-(stack_offset) as u8)];
+            vec![Instruction::AllocateStack(-(stack_offset) as u32)];
         _ = parse_instructions(input_asm_instruction_ast_nodes, &mut stack_offset)
     }
 
@@ -180,7 +288,7 @@ mod tests {
         };
 
         let expected_asm_instructions_same_stack_addr_dst =
-            Operand::Stack(-(TMP_VAR_BYTE_LEN as i8));
+            Operand::Stack(-(TMP_VAR_BYTE_LEN as i32));
         let expected_asm_instruction_ast_nodes = vec![
             Instruction::Mov {
                 src: Operand::Imm(value),
@@ -226,10 +334,11 @@ mod tests {
             name: function_name_identifier.to_string(),
             instructions: asm_instruction_ast_nodes,
         };
-        let input_program_defn_ast_node = ProgramDefinition::Program(function_defn_asm_ast_node);
+        let input_program_defn_ast_node =
+            ProgramDefinition::Program(vec![function_defn_asm_ast_node]);
 
         let expected_asm_instructions_same_stack_addr_dst =
-            Operand::Stack(-(TMP_VAR_BYTE_LEN as i8));
+            Operand::Stack(-(TMP_VAR_BYTE_LEN as i32));
         let expected_asm_instruction_ast_nodes = vec![
             Instruction::Mov {
                 src: Operand::Imm(value),
@@ -245,15 +354,70 @@ mod tests {
             instructions: expected_asm_instruction_ast_nodes,
         };
         let expected_program_defn_asm_ast_node =
-            ProgramDefinition::Program(output_function_defn_asm_ast_node);
-        let expected_stack_offset = -(TMP_VAR_BYTE_LEN as i8);
+            ProgramDefinition::Program(vec![output_function_defn_asm_ast_node]);
+        let expected_stack_offsets = vec![-(TMP_VAR_BYTE_LEN as i32)];
 
-        let (output_program_defn_ast_node, output_stack_offset) =
+        let (output_program_defn_ast_node, output_stack_offsets) =
             parse_program_definition(input_program_defn_ast_node);
         assert_eq!(
             expected_program_defn_asm_ast_node,
             output_program_defn_ast_node
         );
-        assert_eq!(expected_stack_offset, output_stack_offset);
+        assert_eq!(expected_stack_offsets, output_stack_offsets);
+    }
+
+    #[test]
+    fn each_function_defn_gets_its_own_stack_offset() {
+        let function_defn_with_one_tmp_var = FunctionDefinition::Function {
+            name: "foo".to_string(),
+            instructions: vec![Instruction::Mov {
+                src: Operand::Imm(1),
+                dst: Operand::PseudoRegister("tmp0".to_string()),
+            }],
+        };
+        let function_defn_with_two_tmp_vars = FunctionDefinition::Function {
+            name: "main".to_string(),
+            instructions: vec![
+                Instruction::Mov {
+                    src: Operand::Imm(1),
+                    dst: Operand::PseudoRegister("tmp0".to_string()),
+                },
+                Instruction::Mov {
+                    src: Operand::Imm(2),
+                    dst: Operand::PseudoRegister("tmp1".to_string()),
+                },
+            ],
+        };
+        let input_program_defn_ast_node = ProgramDefinition::Program(vec![
+            function_defn_with_one_tmp_var,
+            function_defn_with_two_tmp_vars,
+        ]);
+        let expected_program_defn_ast_node = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                instructions: vec![Instruction::Mov {
+                    src: Operand::Imm(1),
+                    dst: Operand::Stack(-(TMP_VAR_BYTE_LEN as i32)),
+                }],
+            },
+            FunctionDefinition::Function {
+                name: "main".to_string(),
+                instructions: vec![
+                    Instruction::Mov {
+                        src: Operand::Imm(1),
+                        dst: Operand::Stack(-(TMP_VAR_BYTE_LEN as i32)),
+                    },
+                    Instruction::Mov {
+                        src: Operand::Imm(2),
+                        dst: Operand::Stack(-2 * TMP_VAR_BYTE_LEN as i32),
+                    },
+                ],
+            },
+        ]);
+        let expected_stack_offsets = vec![-(TMP_VAR_BYTE_LEN as i32), -2 * TMP_VAR_BYTE_LEN as i32];
+        let (output_program_defn_ast_node, output_stack_offsets) =
+            parse_program_definition(input_program_defn_ast_node);
+        assert_eq!(expected_program_defn_ast_node, output_program_defn_ast_node);
+        assert_eq!(expected_stack_offsets, output_stack_offsets);
     }
 }
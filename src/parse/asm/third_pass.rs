@@ -1,14 +1,28 @@
-use crate::parse::asm::{FunctionDefinition, Instruction, Operand, ProgramDefinition, Reg};
+use crate::parse::asm::{
+    BinaryOperator, FunctionDefinition, Instruction, Operand, ProgramDefinition, Reg,
+};
 
-pub fn parse_program_definition(node: ProgramDefinition, stack_offset: i8) -> ProgramDefinition {
+/// Fix up the instructions of each function, given the stack offset that each function needs (in
+/// the same order as the functions)
+pub fn parse_program_definition(
+    node: ProgramDefinition,
+    stack_offsets: Vec<i32>,
+) -> ProgramDefinition {
     match node {
-        ProgramDefinition::Program(func_defn) => {
-            ProgramDefinition::Program(parse_function_definition(func_defn, stack_offset))
-        }
+        ProgramDefinition::Program(func_defns) => ProgramDefinition::Program(
+            func_defns
+                .into_iter()
+                .zip(stack_offsets)
+                .map(|(func_defn, stack_offset)| parse_function_definition(func_defn, stack_offset))
+                .collect(),
+        ),
     }
 }
 
-pub fn parse_function_definition(node: FunctionDefinition, stack_offset: i8) -> FunctionDefinition {
+pub fn parse_function_definition(
+    node: FunctionDefinition,
+    stack_offset: i32,
+) -> FunctionDefinition {
     match node {
         FunctionDefinition::Function {
             name,
@@ -16,7 +30,7 @@ pub fn parse_function_definition(node: FunctionDefinition, stack_offset: i8) ->
         } => {
             // NOTE: Inserting at the front of a vector is the worst case scenario (all elements
             // need to be shifted), so might be worth rethinking this at some point.
-            instructions.insert(0, Instruction::AllocateStack(-(stack_offset) as u8));
+            instructions.insert(0, Instruction::AllocateStack(-(stack_offset) as u32));
             FunctionDefinition::Function {
                 name,
                 instructions: parse_instructions(instructions),
@@ -46,6 +60,115 @@ pub fn parse_instructions(nodes: Vec<Instruction>) -> Vec<Instruction> {
                 ];
                 transformed_instructions.append(&mut intermediate_register_instructions);
             }
+            Instruction::Binary {
+                op:
+                    op @ (BinaryOperator::Add
+                    | BinaryOperator::Sub
+                    | BinaryOperator::And
+                    | BinaryOperator::Or
+                    | BinaryOperator::Xor),
+                src: Operand::Stack(src_offset),
+                dst: Operand::Stack(dst_offset),
+            } => {
+                let mut intermediate_register_instructions = vec![
+                    Instruction::Mov {
+                        src: Operand::Stack(src_offset),
+                        dst: Operand::Register(Reg::R10D),
+                    },
+                    Instruction::Binary {
+                        op,
+                        src: Operand::Register(Reg::R10D),
+                        dst: Operand::Stack(dst_offset),
+                    },
+                ];
+                transformed_instructions.append(&mut intermediate_register_instructions);
+            }
+            Instruction::Binary {
+                op: op @ (BinaryOperator::Sal | BinaryOperator::Sar),
+                src: src @ (Operand::Register(_) | Operand::Stack(_)),
+                dst,
+            } => {
+                // A shift count that isn't an immediate value can only be given in `%cl`
+                let mut intermediate_register_instructions = vec![
+                    Instruction::Mov {
+                        src,
+                        dst: Operand::Register(Reg::CX),
+                    },
+                    Instruction::Binary {
+                        op,
+                        src: Operand::Register(Reg::CX),
+                        dst,
+                    },
+                ];
+                transformed_instructions.append(&mut intermediate_register_instructions);
+            }
+            Instruction::Binary {
+                op: BinaryOperator::Mult,
+                src,
+                dst: Operand::Stack(dst_offset),
+            } => {
+                // `imul` can't have a memory address as its destination
+                let mut intermediate_register_instructions = vec![
+                    Instruction::Mov {
+                        src: Operand::Stack(dst_offset),
+                        dst: Operand::Register(Reg::R11D),
+                    },
+                    Instruction::Binary {
+                        op: BinaryOperator::Mult,
+                        src,
+                        dst: Operand::Register(Reg::R11D),
+                    },
+                    Instruction::Mov {
+                        src: Operand::Register(Reg::R11D),
+                        dst: Operand::Stack(dst_offset),
+                    },
+                ];
+                transformed_instructions.append(&mut intermediate_register_instructions);
+            }
+            Instruction::Idiv(Operand::Imm(val)) => {
+                // `idiv` can't have an immediate value as its operand
+                let mut intermediate_register_instructions = vec![
+                    Instruction::Mov {
+                        src: Operand::Imm(val),
+                        dst: Operand::Register(Reg::R10D),
+                    },
+                    Instruction::Idiv(Operand::Register(Reg::R10D)),
+                ];
+                transformed_instructions.append(&mut intermediate_register_instructions);
+            }
+            Instruction::Cmp {
+                left: Operand::Stack(left_offset),
+                right: Operand::Stack(right_offset),
+            } => {
+                let mut intermediate_register_instructions = vec![
+                    Instruction::Mov {
+                        src: Operand::Stack(left_offset),
+                        dst: Operand::Register(Reg::R10D),
+                    },
+                    Instruction::Cmp {
+                        left: Operand::Register(Reg::R10D),
+                        right: Operand::Stack(right_offset),
+                    },
+                ];
+                transformed_instructions.append(&mut intermediate_register_instructions);
+            }
+            Instruction::Cmp {
+                left,
+                right: Operand::Imm(val),
+            } => {
+                // The second operand of `cmp` can't be an immediate value
+                let mut intermediate_register_instructions = vec![
+                    Instruction::Mov {
+                        src: Operand::Imm(val),
+                        dst: Operand::Register(Reg::R11D),
+                    },
+                    Instruction::Cmp {
+                        left,
+                        right: Operand::Register(Reg::R11D),
+                    },
+                ];
+                transformed_instructions.append(&mut intermediate_register_instructions);
+            }
             _ => transformed_instructions.push(node),
         }
     }
@@ -55,7 +178,7 @@ pub fn parse_instructions(nodes: Vec<Instruction>) -> Vec<Instruction> {
 
 #[cfg(test)]
 mod tests {
-    use crate::parse::asm::{Operand, UnaryOperator, TMP_VAR_BYTE_LEN};
+    use crate::parse::asm::{CondCode, Operand, UnaryOperator, TMP_VAR_BYTE_LEN};
 
     use super::*;
 
@@ -63,7 +186,7 @@ mod tests {
     fn insert_stack_frame_allocate_instruction_at_start_of_function_defn_instructions() {
         let value = 2;
         let function_name_identifier = "main";
-        let stack_offset = -(TMP_VAR_BYTE_LEN as i8);
+        let stack_offset = -(TMP_VAR_BYTE_LEN as i32);
 
         let asm_instructions_same_stack_addr_dst = Operand::Stack(stack_offset);
         let asm_instruction_ast_nodes = vec![
@@ -83,7 +206,7 @@ mod tests {
 
         let expected_asm_instructions_same_stack_addr_dst = Operand::Stack(stack_offset);
         let expected_asm_instruction_ast_nodes = vec![
-            Instruction::AllocateStack(-(stack_offset) as u8),
+            Instruction::AllocateStack(-(stack_offset) as u32),
             Instruction::Mov {
                 src: Operand::Imm(value),
                 dst: expected_asm_instructions_same_stack_addr_dst.clone(),
@@ -177,4 +300,258 @@ mod tests {
             output_asm_ast_instruction_ast_nodes
         );
     }
+
+    #[test]
+    fn convert_idiv_instruction_with_imm_operand_to_use_intermediate_register() {
+        let value = 3;
+        let input_asm_instruction_ast_nodes = vec![Instruction::Idiv(Operand::Imm(value))];
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Imm(value),
+                dst: Operand::Register(Reg::R10D),
+            },
+            Instruction::Idiv(Operand::Register(Reg::R10D)),
+        ];
+        let output_asm_instruction_ast_nodes = parse_instructions(input_asm_instruction_ast_nodes);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn convert_add_instruction_with_src_dst_stack_addrs_to_use_intermediate_register() {
+        let input_asm_instruction_ast_nodes = vec![Instruction::Binary {
+            op: BinaryOperator::Add,
+            src: Operand::Stack(-4),
+            dst: Operand::Stack(-8),
+        }];
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Stack(-4),
+                dst: Operand::Register(Reg::R10D),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::Add,
+                src: Operand::Register(Reg::R10D),
+                dst: Operand::Stack(-8),
+            },
+        ];
+        let output_asm_instruction_ast_nodes = parse_instructions(input_asm_instruction_ast_nodes);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn convert_mult_instruction_with_stack_addr_dst_to_use_intermediate_register() {
+        let value = 3;
+        let input_asm_instruction_ast_nodes = vec![Instruction::Binary {
+            op: BinaryOperator::Mult,
+            src: Operand::Imm(value),
+            dst: Operand::Stack(-4),
+        }];
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Stack(-4),
+                dst: Operand::Register(Reg::R11D),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::Mult,
+                src: Operand::Imm(value),
+                dst: Operand::Register(Reg::R11D),
+            },
+            Instruction::Mov {
+                src: Operand::Register(Reg::R11D),
+                dst: Operand::Stack(-4),
+            },
+        ];
+        let output_asm_instruction_ast_nodes = parse_instructions(input_asm_instruction_ast_nodes);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn insert_stack_frame_allocate_instruction_with_each_function_defns_stack_offset() {
+        let input_program_defn_ast_node = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                instructions: vec![Instruction::Ret],
+            },
+            FunctionDefinition::Function {
+                name: "main".to_string(),
+                instructions: vec![Instruction::Ret],
+            },
+        ]);
+        let expected_program_defn_ast_node = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                instructions: vec![Instruction::AllocateStack(4), Instruction::Ret],
+            },
+            FunctionDefinition::Function {
+                name: "main".to_string(),
+                instructions: vec![Instruction::AllocateStack(8), Instruction::Ret],
+            },
+        ]);
+        let output_program_defn_ast_node =
+            parse_program_definition(input_program_defn_ast_node, vec![-4, -8]);
+        assert_eq!(expected_program_defn_ast_node, output_program_defn_ast_node);
+    }
+
+    #[test]
+    fn convert_bitwise_instructions_with_src_dst_stack_addrs_to_use_intermediate_register() {
+        for op in [BinaryOperator::And, BinaryOperator::Or, BinaryOperator::Xor] {
+            let input_asm_instruction_ast_nodes = vec![Instruction::Binary {
+                op: op.clone(),
+                src: Operand::Stack(-4),
+                dst: Operand::Stack(-8),
+            }];
+            let expected_asm_instruction_ast_nodes = vec![
+                Instruction::Mov {
+                    src: Operand::Stack(-4),
+                    dst: Operand::Register(Reg::R10D),
+                },
+                Instruction::Binary {
+                    op,
+                    src: Operand::Register(Reg::R10D),
+                    dst: Operand::Stack(-8),
+                },
+            ];
+            let output_asm_instruction_ast_nodes =
+                parse_instructions(input_asm_instruction_ast_nodes);
+            assert_eq!(
+                expected_asm_instruction_ast_nodes,
+                output_asm_instruction_ast_nodes
+            );
+        }
+    }
+
+    #[test]
+    fn leave_shift_instruction_with_immediate_count_unchanged() {
+        let input_asm_instruction_ast_nodes = vec![Instruction::Binary {
+            op: BinaryOperator::Sal,
+            src: Operand::Imm(4),
+            dst: Operand::Stack(-4),
+        }];
+        let expected_asm_instruction_ast_nodes = vec![Instruction::Binary {
+            op: BinaryOperator::Sal,
+            src: Operand::Imm(4),
+            dst: Operand::Stack(-4),
+        }];
+        let output_asm_instruction_ast_nodes = parse_instructions(input_asm_instruction_ast_nodes);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn convert_shift_instructions_with_stack_addr_count_to_use_cx_register() {
+        for op in [BinaryOperator::Sal, BinaryOperator::Sar] {
+            let input_asm_instruction_ast_nodes = vec![Instruction::Binary {
+                op: op.clone(),
+                src: Operand::Stack(-4),
+                dst: Operand::Stack(-8),
+            }];
+            let expected_asm_instruction_ast_nodes = vec![
+                Instruction::Mov {
+                    src: Operand::Stack(-4),
+                    dst: Operand::Register(Reg::CX),
+                },
+                Instruction::Binary {
+                    op,
+                    src: Operand::Register(Reg::CX),
+                    dst: Operand::Stack(-8),
+                },
+            ];
+            let output_asm_instruction_ast_nodes =
+                parse_instructions(input_asm_instruction_ast_nodes);
+            assert_eq!(
+                expected_asm_instruction_ast_nodes,
+                output_asm_instruction_ast_nodes
+            );
+        }
+    }
+
+    #[test]
+    fn convert_cmp_instruction_with_both_stack_addr_operands_to_use_intermediate_register() {
+        let input_asm_instruction_ast_nodes = vec![Instruction::Cmp {
+            left: Operand::Stack(-4),
+            right: Operand::Stack(-8),
+        }];
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Stack(-4),
+                dst: Operand::Register(Reg::R10D),
+            },
+            Instruction::Cmp {
+                left: Operand::Register(Reg::R10D),
+                right: Operand::Stack(-8),
+            },
+        ];
+        let output_asm_instruction_ast_nodes = parse_instructions(input_asm_instruction_ast_nodes);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn convert_cmp_instruction_with_immediate_second_operand_to_use_intermediate_register() {
+        let value = 2;
+        let input_asm_instruction_ast_nodes = vec![Instruction::Cmp {
+            left: Operand::Stack(-4),
+            right: Operand::Imm(value),
+        }];
+        let expected_asm_instruction_ast_nodes = vec![
+            Instruction::Mov {
+                src: Operand::Imm(value),
+                dst: Operand::Register(Reg::R11D),
+            },
+            Instruction::Cmp {
+                left: Operand::Stack(-4),
+                right: Operand::Register(Reg::R11D),
+            },
+        ];
+        let output_asm_instruction_ast_nodes = parse_instructions(input_asm_instruction_ast_nodes);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn leave_cmp_instruction_with_immediate_first_operand_unchanged() {
+        let input_asm_instruction_ast_nodes = vec![Instruction::Cmp {
+            left: Operand::Imm(0),
+            right: Operand::Stack(-4),
+        }];
+        let expected_asm_instruction_ast_nodes = vec![Instruction::Cmp {
+            left: Operand::Imm(0),
+            right: Operand::Stack(-4),
+        }];
+        let output_asm_instruction_ast_nodes = parse_instructions(input_asm_instruction_ast_nodes);
+        assert_eq!(
+            expected_asm_instruction_ast_nodes,
+            output_asm_instruction_ast_nodes
+        );
+    }
+
+    #[test]
+    fn dont_transform_jump_and_label_instructions() {
+        let instructions = vec![
+            Instruction::JmpCC {
+                condition: CondCode::NE,
+                target: "or_true0".to_string(),
+            },
+            Instruction::Jmp("or_end1".to_string()),
+            Instruction::Label("or_true0".to_string()),
+            Instruction::Label("or_end1".to_string()),
+        ];
+        let output_asm_instruction_ast_nodes = parse_instructions(instructions.clone());
+        assert_eq!(output_asm_instruction_ast_nodes, instructions);
+    }
 }
@@ -1,343 +1,1356 @@
 use std::collections::VecDeque;
 
-use crate::lex::Token;
+use crate::lex::{Span, SpannedToken, Token};
 use crate::parse::Identifier;
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum UnaryOperator {
     BitwiseComplement,
     Negation,
+    Not,
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+pub enum IncrementOperator {
+    Increment,
+    Decrement,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum BinaryOperator {
     Add,
     Subtract,
     Multiply,
     Divide,
     Modulo,
+    ShiftLeft,
+    ShiftRight,
+    LessThan,
+    GreaterThan,
+    LessOrEqual,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+    BitwiseAnd,
+    BitwiseXor,
+    BitwiseOr,
+    And,
+    Or,
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum Expression {
-    NumericConstant(u8),
+    NumericConstant(i64),
+    Var(Identifier),
     Unary(UnaryOperator, Box<Expression>),
     Binary {
         op: BinaryOperator,
         left: Box<Expression>,
         right: Box<Expression>,
     },
+    Assignment {
+        lhs: Box<Expression>,
+        rhs: Box<Expression>,
+    },
+    /// Compound assignment (such as `a += 1`) is kept as its own node rather than being desugared
+    /// into an assignment of a binary expression (such as `a = a + 1`), so that the left operand
+    /// isn't duplicated
+    CompoundAssignment {
+        op: BinaryOperator,
+        lhs: Box<Expression>,
+        rhs: Box<Expression>,
+    },
+    /// Prefix increment or decrement (such as `++a`), which has the value of the operand after it's
+    /// been updated
+    Prefix {
+        op: IncrementOperator,
+        operand: Box<Expression>,
+    },
+    /// Postfix increment or decrement (such as `a++`), which has the value of the operand before
+    /// it's been updated
+    Postfix {
+        op: IncrementOperator,
+        operand: Box<Expression>,
+    },
+    Conditional {
+        condition: Box<Expression>,
+        then: Box<Expression>,
+        otherwise: Box<Expression>,
+    },
+    /// The comma operator, which evaluates the left operand and then the right operand, and has
+    /// the value of the right operand
+    Comma {
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    FunctionCall {
+        name: Identifier,
+        args: Vec<Expression>,
+    },
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum Statement {
     Return(Expression),
+    Expression(Expression),
+    If {
+        condition: Expression,
+        then: Box<Statement>,
+        otherwise: Option<Box<Statement>>,
+    },
+    /// The label of a loop (and of a `break` or `continue`) is `None` after parsing, and is filled
+    /// in by loop labeling during semantic analysis
+    While {
+        condition: Expression,
+        body: Box<Statement>,
+        label: Option<Identifier>,
+    },
+    DoWhile {
+        body: Box<Statement>,
+        condition: Expression,
+        label: Option<Identifier>,
+    },
+    For {
+        init: ForInit,
+        condition: Option<Expression>,
+        post: Option<Expression>,
+        body: Box<Statement>,
+        label: Option<Identifier>,
+    },
+    Break(Option<Identifier>),
+    Continue(Option<Identifier>),
+    /// The label and cases of a `switch` are filled in during semantic analysis. Each case is the
+    /// value of a `case` (or `None` for `default`), and the label of the statement it jumps to.
+    Switch {
+        condition: Expression,
+        body: Box<Statement>,
+        label: Option<Identifier>,
+        cases: Vec<(Option<i64>, Identifier)>,
+    },
+    Case {
+        value: Expression,
+        statement: Box<Statement>,
+        label: Option<Identifier>,
+    },
+    Default {
+        statement: Box<Statement>,
+        label: Option<Identifier>,
+    },
+    Goto(Identifier),
+    Null,
+    Labeled {
+        label: Identifier,
+        statement: Box<Statement>,
+    },
+    /// A block of block items wrapped in braces, which has its own scope
+    Compound(Vec<BlockItem>),
+}
+
+/// The initial clause of a `for` loop. A declaration in this clause is only in scope for the
+/// loop, so it's kept distinct from an expression rather than being represented as a block item.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+pub enum ForInit {
+    InitDecl(Declaration),
+    InitExp(Option<Expression>),
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+pub enum Declaration {
+    Declaration {
+        name: Identifier,
+        init: Option<Expression>,
+    },
+}
+
+/// Each block item keeps the position of its first token in the C source code, so that the code
+/// it's lowered to can be traced back to a line. The position is `None` for block items that
+/// weren't parsed from C source code.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+pub enum BlockItem {
+    Statement(Statement, Option<Span>),
+    Declaration(Declaration, Option<Span>),
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum FunctionDefinition {
-    Function { name: Identifier, body: Statement },
+    Function {
+        name: Identifier,
+        params: Vec<Identifier>,
+        /// Function declarations (ie, prototypes) have no body
+        body: Option<Vec<BlockItem>>,
+    },
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum ProgramDefinition {
-    Program(FunctionDefinition),
+    Program(Vec<FunctionDefinition>),
 }
 
-pub fn parse_unary_operator(tokens: &mut VecDeque<Token>) -> UnaryOperator {
-    let next_token = tokens
-        .pop_front()
-        .expect("Should have non-empty queue of tokens");
+/// Serialize the C AST to JSON, for inspecting the parse tree outside of the compiler
+#[cfg(feature = "ast-json")]
+pub fn ast_to_json(program: &ProgramDefinition) -> String {
+    serde_json::to_string_pretty(program).expect("C AST should always be serializable to JSON")
+}
 
-    match next_token {
-        Token::Tilde => UnaryOperator::BitwiseComplement,
-        Token::Minus => UnaryOperator::Negation,
-        _ => todo!(),
+/// Errors that can occur when parsing tokens into a C AST. Positions are 1-based line and column
+/// numbers.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// A token was found where a different construct was expected
+    UnexpectedToken {
+        expected: String,
+        found: Token,
+        line: usize,
+        column: usize,
+    },
+    /// The tokens ran out while a construct was still expected
+    UnexpectedEndOfInput { expected: String },
+    /// A numeric constant can't be represented as an `int`
+    ConstantOutOfRange {
+        text: String,
+        line: usize,
+        column: usize,
+    },
+    /// Expressions are nested inside each other more deeply than the parser supports. The
+    /// position is of the first token of the expression that exceeded the limit.
+    NestingTooDeep { line: usize, column: usize },
+    /// Statements are nested inside each other more deeply than the parser supports. The position
+    /// is of the first token of the statement that exceeded the limit.
+    StatementNestingTooDeep { line: usize, column: usize },
+    /// A token was left over that can't start another top-level construct of the program
+    TrailingToken {
+        found: Token,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl ParseError {
+    fn unexpected_token(spanned_token: &SpannedToken, expected: &str) -> ParseError {
+        ParseError::UnexpectedToken {
+            expected: expected.to_string(),
+            found: spanned_token.token.clone(),
+            line: spanned_token.span.line,
+            column: spanned_token.span.column,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedToken {
+                expected,
+                found,
+                line,
+                column,
+            } => write!(
+                f,
+                "{}:{}: Expected {}, found `{}`",
+                line, column, expected, found
+            ),
+            ParseError::UnexpectedEndOfInput { expected } => {
+                write!(f, "Unexpected end of input, expected {}", expected)
+            }
+            ParseError::ConstantOutOfRange { text, line, column } => write!(
+                f,
+                "{}:{}: Numeric constant is out of range for `int`: {}",
+                line, column, text
+            ),
+            ParseError::NestingTooDeep { line, column } => {
+                write!(f, "{}:{}: Expression nesting too deep", line, column)
+            }
+            ParseError::StatementNestingTooDeep { line, column } => {
+                write!(f, "{}:{}: Statement nesting too deep", line, column)
+            }
+            ParseError::TrailingToken {
+                found,
+                line,
+                column,
+            } => write!(
+                f,
+                "{}:{}: Unexpected token after end of program: `{}`",
+                line, column, found
+            ),
+        }
     }
 }
 
-pub fn parse_binary_operator(tokens: &mut VecDeque<Token>) -> BinaryOperator {
-    let next_token = tokens
+impl std::error::Error for ParseError {}
+
+/// Look at the next token without removing it from the queue, where `expected` describes what the
+/// caller is looking for in case the queue is empty
+fn peek_token<'a>(
+    tokens: &'a VecDeque<SpannedToken>,
+    expected: &str,
+) -> Result<&'a SpannedToken, ParseError> {
+    tokens
+        .front()
+        .ok_or_else(|| ParseError::UnexpectedEndOfInput {
+            expected: expected.to_string(),
+        })
+}
+
+/// Remove the next token from the queue, where `expected` describes what the caller is looking
+/// for in case the queue is empty
+fn pop_token(
+    tokens: &mut VecDeque<SpannedToken>,
+    expected: &str,
+) -> Result<SpannedToken, ParseError> {
+    tokens
         .pop_front()
-        .expect("Should have non-empty queue of tokens");
+        .ok_or_else(|| ParseError::UnexpectedEndOfInput {
+            expected: expected.to_string(),
+        })
+}
 
-    match next_token {
-        Token::Plus => BinaryOperator::Add,
-        Token::Minus => BinaryOperator::Subtract,
-        Token::Asterisk => BinaryOperator::Multiply,
-        Token::ForwardSlash => BinaryOperator::Divide,
-        Token::Percent => BinaryOperator::Modulo,
-        _ => todo!(),
+/// Remove the next token from the queue, checking that it's the given token
+fn expect_token(tokens: &mut VecDeque<SpannedToken>, expected: Token) -> Result<(), ParseError> {
+    let description = format!("`{}`", expected);
+    let next_token = pop_token(tokens, &description)?;
+    if next_token.token != expected {
+        return Err(ParseError::unexpected_token(&next_token, &description));
     }
+    Ok(())
 }
 
-pub fn parse_factor(tokens: &mut VecDeque<Token>) -> Expression {
-    // The queue of tokens shouldn't be empty if the queue has been handled correctly by others, so
-    // the panic shouldn't occur. Hence, the use of `expect()`.
-    let next_token = tokens
-        .front()
-        .expect("Should have non-empty queue of tokens");
+fn parse_unary_operator(tokens: &mut VecDeque<SpannedToken>) -> Result<UnaryOperator, ParseError> {
+    let next_token = pop_token(tokens, "unary operator")?;
 
-    match next_token {
+    match next_token.token {
+        Token::Tilde => Ok(UnaryOperator::BitwiseComplement),
+        Token::Minus => Ok(UnaryOperator::Negation),
+        Token::Exclamation => Ok(UnaryOperator::Not),
+        _ => Err(ParseError::unexpected_token(&next_token, "unary operator")),
+    }
+}
+
+/// The maximum number of expressions that can enclose another expression (such as through
+/// parentheses, unary operators, or chains of binary operators). Each level of nesting is parsed
+/// (and later walked) by a recursive call, so without a limit pathological input would overflow
+/// the stack.
+///
+/// Each level of nesting can use several KiB of stack in unoptimised builds, so the limit is kept
+/// low enough for parsing to fit comfortably in the 2MiB stack that spawned threads get by default.
+const MAX_EXPRESSION_NESTING_DEPTH: usize = 128;
+
+/// Check that an expression nested inside `depth` other expressions is within the nesting limit
+fn check_nesting_depth(tokens: &VecDeque<SpannedToken>, depth: usize) -> Result<(), ParseError> {
+    if depth <= MAX_EXPRESSION_NESTING_DEPTH {
+        return Ok(());
+    }
+
+    let next_token = peek_token(tokens, "expression")?;
+    Err(ParseError::NestingTooDeep {
+        line: next_token.span.line,
+        column: next_token.span.column,
+    })
+}
+
+/// Parse a factor that's nested inside `depth` other expressions
+fn parse_nested_factor(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Expression, ParseError> {
+    check_nesting_depth(tokens, depth)?;
+    let next_token = peek_token(tokens, "expression")?;
+
+    match &next_token.token {
         Token::NumericConstant(_) => {
-            // NOTE: Not able to use the value inside the token since that's an immutable reference
-            // to the value, and we also need to consume the token (via popping it off the queue).
-            //
-            // The borrow checker won't allow the use of the value inside the token reference if a
-            // pop happens before it (due to it involving a mutation of `tokens`). Instead have to
-            // ignore the value in the token reference and use the value in the popped token.
-            let token = tokens
+            let constant_ast_node = parse_constant(tokens, false)?;
+            parse_postfix_operators(tokens, constant_ast_node)
+        }
+        Token::Identifier(_) => {
+            let identifier_ast_node = parse_identifier_or_function_call(tokens, depth)?;
+            parse_postfix_operators(tokens, identifier_ast_node)
+        }
+        // The magnitude of the smallest `int` is too large to be an `int` itself, so a negated
+        // literal is parsed as a single negative constant rather than range-checking the literal
+        // by itself
+        Token::Minus
+            if tokens
+                .get(1)
+                .is_some_and(|t| matches!(t.token, Token::NumericConstant(_))) =>
+        {
+            _ = tokens
                 .pop_front()
                 .expect("Already confirmed at least one token in the queue");
-
-            // TODO: It's clunky to have to match against the variant again for the popped token,
-            // even though from being inside this match arm we know that the variant must be
-            // `Token::NumericConstant`.
-            //
-            // Find a nicer way to do this.
-            match token {
-                Token::NumericConstant(val) => Expression::NumericConstant(val),
-                _ => panic!(),
-            }
+            let constant_ast_node = parse_constant(tokens, true)?;
+            parse_postfix_operators(tokens, constant_ast_node)
         }
-        Token::Tilde | Token::Minus => {
-            let unary_operator_ast_node = parse_unary_operator(tokens);
-            let inner_expression_ast_node = parse_factor(tokens);
-            Expression::Unary(unary_operator_ast_node, Box::new(inner_expression_ast_node))
+        // Unary plus has no effect on an `int`, so the operand is used as-is
+        Token::Plus => {
+            _ = tokens
+                .pop_front()
+                .expect("Already confirmed at least one token in the queue");
+            parse_nested_factor(tokens, depth + 1)
         }
+        Token::Tilde | Token::Minus | Token::Exclamation => parse_unary_expression(tokens, depth),
+        Token::DoublePlus | Token::DoubleMinus => parse_prefix_expression(tokens, depth),
         Token::OpenParenthesis => {
             _ = tokens
                 .pop_front()
                 .expect("Already confirmed at least one token in the queue");
 
-            let expression_ast_node = parse_expression(tokens, 0);
+            let expression_ast_node = parse_nested_expression(tokens, 0, depth + 1)?;
+            expect_token(tokens, Token::CloseParenthesis)?;
+            parse_postfix_operators(tokens, expression_ast_node)
+        }
+        _ => Err(ParseError::unexpected_token(next_token, "expression")),
+    }
+}
+
+fn parse_increment_operator(
+    tokens: &mut VecDeque<SpannedToken>,
+) -> Result<IncrementOperator, ParseError> {
+    let next_token = pop_token(tokens, "increment or decrement operator")?;
 
-            let trailing_token = tokens
-                .pop_front()
-                .expect("Should be a close parenthesis token for valid syntax");
-            if let Token::CloseParenthesis = trailing_token {
-                return expression_ast_node;
-            }
+    match next_token.token {
+        Token::DoublePlus => Ok(IncrementOperator::Increment),
+        Token::DoubleMinus => Ok(IncrementOperator::Decrement),
+        _ => Err(ParseError::unexpected_token(
+            &next_token,
+            "increment or decrement operator",
+        )),
+    }
+}
+
+/// Parse any postfix increment or decrement operators that follow an already parsed expression.
+/// These bind more tightly than any prefix operator, so `-a++` is `-(a++)`.
+fn parse_postfix_operators(
+    tokens: &mut VecDeque<SpannedToken>,
+    mut expression_ast_node: Expression,
+) -> Result<Expression, ParseError> {
+    while let Some(Token::DoublePlus | Token::DoubleMinus) = tokens.front().map(|t| &t.token) {
+        let op = parse_increment_operator(tokens)?;
+        expression_ast_node = Expression::Postfix {
+            op,
+            operand: Box::new(expression_ast_node),
+        };
+    }
+    Ok(expression_ast_node)
+}
 
-            // If execution has reached here then the token after the open parenthesis + expression
-            // was not a close parenthesis token, which means that the C source code has invalid
-            // syntax.
-            panic!("Invalid syntax: expected closing parenthesis");
+/// Parse a unary operator and its operand, where the unary expression is nested inside `depth`
+/// other expressions
+fn parse_unary_expression(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Expression, ParseError> {
+    let unary_operator_ast_node = parse_unary_operator(tokens)?;
+    let inner_expression_ast_node = parse_nested_factor(tokens, depth + 1)?;
+
+    // Negating a constant (such as one wrapped in parentheses) is folded into the constant, so that
+    // lowering doesn't need to produce an instruction to negate it
+    match (unary_operator_ast_node, inner_expression_ast_node) {
+        (UnaryOperator::Negation, Expression::NumericConstant(val))
+            if i32::try_from(-val).is_ok() =>
+        {
+            Ok(Expression::NumericConstant(-val))
         }
-        _ => todo!(),
+        (op, inner) => Ok(Expression::Unary(op, Box::new(inner))),
     }
 }
 
-pub fn parse_expression(tokens: &mut VecDeque<Token>, min_precedence: u8) -> Expression {
-    let mut left = parse_factor(tokens);
+/// Parse a prefix increment or decrement and its operand, where the prefix expression is nested
+/// inside `depth` other expressions
+fn parse_prefix_expression(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Expression, ParseError> {
+    let op = parse_increment_operator(tokens)?;
+    let operand = parse_nested_factor(tokens, depth + 1)?;
+    Ok(Expression::Prefix {
+        op,
+        operand: Box::new(operand),
+    })
+}
 
-    let mut next_token = if let Some(token) = tokens.front() {
-        token
-    } else {
-        return left;
+/// Parse a numeric constant, which is negated if it's preceded by a minus sign (that has already
+/// been consumed)
+fn parse_constant(
+    tokens: &mut VecDeque<SpannedToken>,
+    negated: bool,
+) -> Result<Expression, ParseError> {
+    let token = pop_token(tokens, "numeric constant")?;
+    let val = match token.token {
+        Token::NumericConstant(val) if negated => -val,
+        Token::NumericConstant(val) => val,
+        _ => return Err(ParseError::unexpected_token(&token, "numeric constant")),
     };
 
-    match next_token {
-        Token::Plus | Token::Minus | Token::Asterisk | Token::ForwardSlash | Token::Percent => {
-            if get_operator_precedence(next_token) < min_precedence {
-                return left;
-            }
+    // Constants have type `int`, so must fit in 32 bits
+    if i32::try_from(val).is_err() {
+        return Err(ParseError::ConstantOutOfRange {
+            text: val.to_string(),
+            line: token.span.line,
+            column: token.span.column,
+        });
+    }
+
+    Ok(Expression::NumericConstant(val))
+}
+
+/// Parse an identifier, which is either a variable or the name of a function being called. The
+/// identifier is nested inside `depth` other expressions.
+fn parse_identifier_or_function_call(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Expression, ParseError> {
+    let token = pop_token(tokens, "identifier")?;
+    let identifier = match token.token {
+        Token::Identifier(identifier) => identifier,
+        _ => return Err(ParseError::unexpected_token(&token, "identifier")),
+    };
+
+    match tokens.front() {
+        Some(spanned_token) if spanned_token.token == Token::OpenParenthesis => {
+            let args = parse_argument_list(tokens, depth)?;
+            Ok(Expression::FunctionCall {
+                name: identifier,
+                args,
+            })
+        }
+        _ => Ok(Expression::Var(identifier)),
+    }
+}
+
+/// Parse the arguments of a function call, including the surrounding parentheses. The call is
+/// nested inside `depth` other expressions.
+fn parse_argument_list(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Vec<Expression>, ParseError> {
+    let mut args = Vec::new();
+    expect_token(tokens, Token::OpenParenthesis)?;
+
+    if peek_token(tokens, "argument or `)`")?.token == Token::CloseParenthesis {
+        _ = tokens
+            .pop_front()
+            .expect("Already confirmed at least one token in the queue");
+        return Ok(args);
+    }
+
+    loop {
+        // Commas separate the arguments, so an argument can't contain the comma operator unless
+        // it's wrapped in parentheses
+        args.push(parse_nested_expression(
+            tokens,
+            ASSIGNMENT_PRECEDENCE,
+            depth + 1,
+        )?);
+
+        let next_token = pop_token(tokens, "`,` or `)`")?;
+        match next_token.token {
+            Token::Comma => continue,
+            Token::CloseParenthesis => break Ok(args),
+            _ => return Err(ParseError::unexpected_token(&next_token, "`,` or `)`")),
         }
-        _ => return left,
     }
+}
+
+/// The minimum precedence to parse an expression with in order to exclude the comma operator,
+/// such as for an initializer or a function argument (called an "assignment expression" in the C
+/// standard)
+const ASSIGNMENT_PRECEDENCE: u8 = 1;
+
+fn parse_expression(
+    tokens: &mut VecDeque<SpannedToken>,
+    min_precedence: u8,
+) -> Result<Expression, ParseError> {
+    parse_nested_expression(tokens, min_precedence, 0)
+}
+
+/// Parse an expression that's nested inside `depth` other expressions
+fn parse_nested_expression(
+    tokens: &mut VecDeque<SpannedToken>,
+    min_precedence: u8,
+    mut depth: usize,
+) -> Result<Expression, ParseError> {
+    check_nesting_depth(tokens, depth)?;
+    let mut left = parse_nested_factor(tokens, depth)?;
 
     loop {
-        match next_token {
-            Token::Plus | Token::Minus | Token::Asterisk | Token::ForwardSlash | Token::Percent => {
-                let op_precedence = get_operator_precedence(next_token);
-                let op = parse_binary_operator(tokens);
-                let right = parse_expression(tokens, op_precedence + 1);
-                left = Expression::Binary {
+        let (op, op_precedence, associativity) = match tokens
+            .front()
+            .and_then(|spanned_token| binary_operator_info(&spanned_token.token))
+        {
+            Some(info) => info,
+            None => break Ok(left),
+        };
+
+        // An operator with lower precedence than the minimum belongs to an enclosing expression,
+        // so needs to be left in the queue for that expression to parse
+        if op_precedence < min_precedence {
+            break Ok(left);
+        }
+
+        _ = tokens
+            .pop_front()
+            .expect("Already confirmed at least one token in the queue");
+
+        // Each operator folded into the left operand nests the expressions parsed so far one level
+        // deeper, so a long chain of left-associative operators is as deep as the same number of
+        // nested parentheses
+        depth += 1;
+        check_nesting_depth(tokens, depth)?;
+
+        // The right operand of a right-associative operator is parsed with the same minimum
+        // precedence as the operator itself (rather than one higher) in order to allow it to
+        // contain another operator of the same kind
+        let right_min_precedence = match associativity {
+            Associativity::Left => op_precedence + 1,
+            Associativity::Right => op_precedence,
+        };
+
+        left = match op {
+            InfixOperator::Conditional => {
+                // The middle operand is delimited by `?` and `:`, so it can be any expression,
+                // much like an expression wrapped in parentheses
+                let then = parse_nested_expression(tokens, 0, depth + 1)?;
+                expect_token(tokens, Token::Colon)?;
+                let otherwise = parse_nested_expression(tokens, right_min_precedence, depth + 1)?;
+                Expression::Conditional {
+                    condition: Box::new(left),
+                    then: Box::new(then),
+                    otherwise: Box::new(otherwise),
+                }
+            }
+            InfixOperator::Assignment => {
+                let right = parse_nested_expression(tokens, right_min_precedence, depth + 1)?;
+                Expression::Assignment {
+                    lhs: Box::new(left),
+                    rhs: Box::new(right),
+                }
+            }
+            InfixOperator::CompoundAssignment(op) => {
+                let right = parse_nested_expression(tokens, right_min_precedence, depth + 1)?;
+                Expression::CompoundAssignment {
                     op,
+                    lhs: Box::new(left),
+                    rhs: Box::new(right),
+                }
+            }
+            InfixOperator::Comma => {
+                let right = parse_nested_expression(tokens, right_min_precedence, depth + 1)?;
+                Expression::Comma {
                     left: Box::new(left),
                     right: Box::new(right),
-                };
-
-                if let Some(token) = tokens.front() {
-                    next_token = token;
-                } else {
-                    break left;
                 }
             }
-            _ => break left,
-        }
+            InfixOperator::Binary(op) => {
+                let right = parse_nested_expression(tokens, right_min_precedence, depth + 1)?;
+                Expression::Binary {
+                    op,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            }
+        };
     }
 }
 
-fn get_operator_precedence(token: &Token) -> u8 {
-    match token {
-        Token::Asterisk => 50,
-        Token::ForwardSlash => 50,
-        Token::Percent => 50,
-        Token::Plus => 45,
-        Token::Minus => 45,
-        _ => todo!(),
-    }
+/// Operators that appear between two operands. Assignment, the conditional operator, and the comma
+/// operator aren't binary operators in the AST, but they're parsed in the same way as binary
+/// operators.
+#[derive(Debug, PartialEq)]
+enum InfixOperator {
+    Binary(BinaryOperator),
+    Assignment,
+    /// A compound assignment operator, along with the binary operator that it applies
+    CompoundAssignment(BinaryOperator),
+    Conditional,
+    Comma,
 }
 
-pub fn parse_statement(tokens: &mut VecDeque<Token>) -> Statement {
-    // The queue of tokens shouldn't be empty if the queue has been handled correctly by others, so
-    // the panic shouldn't occur. Hence, the use of `expect()`.
-    let first_token = tokens
-        .pop_front()
-        .expect("Should have non-empty queue of tokens");
-    if first_token != Token::ReturnKeyword {
-        todo!()
-    }
+/// How a sequence of operators with the same precedence is grouped, such as `a - b - c` being
+/// `(a - b) - c` and `a = b = c` being `a = (b = c)`
+#[derive(Debug, PartialEq)]
+enum Associativity {
+    Left,
+    Right,
+}
 
-    let expression_ast_node = parse_factor(tokens);
+/// Get the operator represented by the token, along with its precedence and associativity. Tokens
+/// that can't appear between two operands give `None`.
+fn binary_operator_info(token: &Token) -> Option<(InfixOperator, u8, Associativity)> {
+    let (op, precedence) = match token {
+        Token::Asterisk => (InfixOperator::Binary(BinaryOperator::Multiply), 50),
+        Token::ForwardSlash => (InfixOperator::Binary(BinaryOperator::Divide), 50),
+        Token::Percent => (InfixOperator::Binary(BinaryOperator::Modulo), 50),
+        Token::Plus => (InfixOperator::Binary(BinaryOperator::Add), 45),
+        Token::Minus => (InfixOperator::Binary(BinaryOperator::Subtract), 45),
+        Token::DoubleLeftAngleBracket => (InfixOperator::Binary(BinaryOperator::ShiftLeft), 40),
+        Token::DoubleRightAngleBracket => (InfixOperator::Binary(BinaryOperator::ShiftRight), 40),
+        Token::LeftAngleBracket => (InfixOperator::Binary(BinaryOperator::LessThan), 35),
+        Token::RightAngleBracket => (InfixOperator::Binary(BinaryOperator::GreaterThan), 35),
+        Token::LeftAngleBracketEquals => (InfixOperator::Binary(BinaryOperator::LessOrEqual), 35),
+        Token::RightAngleBracketEquals => {
+            (InfixOperator::Binary(BinaryOperator::GreaterOrEqual), 35)
+        }
+        Token::DoubleEquals => (InfixOperator::Binary(BinaryOperator::Equal), 30),
+        Token::ExclamationEquals => (InfixOperator::Binary(BinaryOperator::NotEqual), 30),
+        Token::Ampersand => (InfixOperator::Binary(BinaryOperator::BitwiseAnd), 25),
+        Token::Caret => (InfixOperator::Binary(BinaryOperator::BitwiseXor), 20),
+        Token::Pipe => (InfixOperator::Binary(BinaryOperator::BitwiseOr), 15),
+        Token::DoubleAmpersand => (InfixOperator::Binary(BinaryOperator::And), 10),
+        Token::DoublePipe => (InfixOperator::Binary(BinaryOperator::Or), 5),
+        Token::QuestionMark => (InfixOperator::Conditional, 3),
+        Token::Equals => (InfixOperator::Assignment, 1),
+        Token::PlusEquals => (InfixOperator::CompoundAssignment(BinaryOperator::Add), 1),
+        Token::MinusEquals => (
+            InfixOperator::CompoundAssignment(BinaryOperator::Subtract),
+            1,
+        ),
+        Token::AsteriskEquals => (
+            InfixOperator::CompoundAssignment(BinaryOperator::Multiply),
+            1,
+        ),
+        Token::ForwardSlashEquals => (InfixOperator::CompoundAssignment(BinaryOperator::Divide), 1),
+        Token::PercentEquals => (InfixOperator::CompoundAssignment(BinaryOperator::Modulo), 1),
+        Token::AmpersandEquals => (
+            InfixOperator::CompoundAssignment(BinaryOperator::BitwiseAnd),
+            1,
+        ),
+        Token::PipeEquals => (
+            InfixOperator::CompoundAssignment(BinaryOperator::BitwiseOr),
+            1,
+        ),
+        Token::CaretEquals => (
+            InfixOperator::CompoundAssignment(BinaryOperator::BitwiseXor),
+            1,
+        ),
+        Token::DoubleLeftAngleBracketEquals => (
+            InfixOperator::CompoundAssignment(BinaryOperator::ShiftLeft),
+            1,
+        ),
+        Token::DoubleRightAngleBracketEquals => (
+            InfixOperator::CompoundAssignment(BinaryOperator::ShiftRight),
+            1,
+        ),
+        Token::Comma => (InfixOperator::Comma, 0),
+        _ => return None,
+    };
 
-    let third_token = tokens
-        .pop_front()
-        .expect("Should have non-empty queue of tokens");
-    if third_token != Token::Semicolon {
-        todo!()
+    // Assignment operators and the conditional operator are right-associative, whereas all binary
+    // operators and the comma operator are left-associative
+    let associativity = match op {
+        InfixOperator::Binary(_) | InfixOperator::Comma => Associativity::Left,
+        _ => Associativity::Right,
+    };
+    Some((op, precedence, associativity))
+}
+
+/// The maximum number of statements that can enclose another statement (such as through blocks,
+/// or the bodies of `if` statements and loops). As with [`MAX_EXPRESSION_NESTING_DEPTH`], each level
+/// of nesting is parsed (and later walked) by a recursive call, so the limit keeps pathological
+/// input from overflowing the stack.
+const MAX_STATEMENT_NESTING_DEPTH: usize = 128;
+
+/// Parse a statement that's nested inside `depth` other statements
+fn parse_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    let next_token = peek_token(tokens, "statement")?;
+    if depth > MAX_STATEMENT_NESTING_DEPTH {
+        return Err(ParseError::StatementNestingTooDeep {
+            line: next_token.span.line,
+            column: next_token.span.column,
+        });
     }
+    let first_token = &next_token.token;
+
+    let statement = match first_token {
+        Token::IfKeyword => return parse_if_statement(tokens, depth),
+        Token::WhileKeyword => return parse_while_statement(tokens, depth),
+        Token::DoKeyword => parse_do_while_statement(tokens, depth)?,
+        Token::ForKeyword => return parse_for_statement(tokens, depth),
+        Token::SwitchKeyword => return parse_switch_statement(tokens, depth),
+        Token::CaseKeyword => return parse_case_statement(tokens, depth),
+        Token::DefaultKeyword => return parse_default_statement(tokens, depth),
+        Token::OpenBrace => return Ok(Statement::Compound(parse_block(tokens, depth + 1)?)),
+        // An identifier followed by a colon is a label rather than the start of an expression
+        Token::Identifier(_) if tokens.get(1).is_some_and(|t| t.token == Token::Colon) => {
+            return parse_labeled_statement(tokens, depth)
+        }
+        Token::ReturnKeyword => {
+            _ = tokens
+                .pop_front()
+                .expect("Already confirmed at least one token in the queue");
+            Statement::Return(parse_expression(tokens, 0)?)
+        }
+        // The semicolon is consumed below in the same way as for any other statement
+        Token::Semicolon => Statement::Null,
+        Token::BreakKeyword => {
+            _ = tokens
+                .pop_front()
+                .expect("Already confirmed at least one token in the queue");
+            Statement::Break(None)
+        }
+        Token::ContinueKeyword => {
+            _ = tokens
+                .pop_front()
+                .expect("Already confirmed at least one token in the queue");
+            Statement::Continue(None)
+        }
+        Token::GotoKeyword => {
+            _ = tokens
+                .pop_front()
+                .expect("Already confirmed at least one token in the queue");
+            let next_token = pop_token(tokens, "label")?;
+            match next_token.token {
+                Token::Identifier(label) => Statement::Goto(label),
+                _ => return Err(ParseError::unexpected_token(&next_token, "label")),
+            }
+        }
+        _ => Statement::Expression(parse_expression(tokens, 0)?),
+    };
 
-    Statement::Return(expression_ast_node)
+    expect_token(tokens, Token::Semicolon)?;
+    Ok(statement)
 }
 
-pub fn parse_function_definition(tokens: &mut VecDeque<Token>) -> FunctionDefinition {
-    let next_token = tokens
-        .pop_front()
-        .expect("Should have non-empty queue of tokens");
-    if next_token != Token::IntKeyword {
-        todo!()
+fn parse_labeled_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    let token = pop_token(tokens, "label")?;
+    let label = match token.token {
+        Token::Identifier(label) => label,
+        _ => return Err(ParseError::unexpected_token(&token, "label")),
+    };
+    expect_token(tokens, Token::Colon)?;
+
+    Ok(Statement::Labeled {
+        label,
+        statement: Box::new(parse_statement_after_label(tokens, depth + 1)?),
+    })
+}
+
+/// Parse the statement that follows a label (including `case` and `default` labels)
+fn parse_statement_after_label(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    // A label must be followed by a statement, so a label at the end of a block isn't valid
+    let next_token = peek_token(tokens, "statement after label")?;
+    if next_token.token == Token::CloseBrace {
+        return Err(ParseError::unexpected_token(
+            next_token,
+            "statement after label",
+        ));
     }
 
-    let next_token = tokens
-        .pop_front()
-        .expect("Should have non-empty queue of tokens");
-    let identifier = match next_token {
+    parse_statement(tokens, depth)
+}
+
+fn parse_switch_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    expect_token(tokens, Token::SwitchKeyword)?;
+    expect_token(tokens, Token::OpenParenthesis)?;
+    let condition = parse_expression(tokens, 0)?;
+    expect_token(tokens, Token::CloseParenthesis)?;
+    let body = parse_statement(tokens, depth + 1)?;
+
+    Ok(Statement::Switch {
+        condition,
+        body: Box::new(body),
+        label: None,
+        cases: Vec::new(),
+    })
+}
+
+fn parse_case_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    expect_token(tokens, Token::CaseKeyword)?;
+    let value = parse_expression(tokens, 0)?;
+    expect_token(tokens, Token::Colon)?;
+
+    Ok(Statement::Case {
+        value,
+        statement: Box::new(parse_statement_after_label(tokens, depth + 1)?),
+        label: None,
+    })
+}
+
+fn parse_default_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    expect_token(tokens, Token::DefaultKeyword)?;
+    expect_token(tokens, Token::Colon)?;
+
+    Ok(Statement::Default {
+        statement: Box::new(parse_statement_after_label(tokens, depth + 1)?),
+        label: None,
+    })
+}
+
+fn parse_if_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    expect_token(tokens, Token::IfKeyword)?;
+    expect_token(tokens, Token::OpenParenthesis)?;
+    let condition = parse_expression(tokens, 0)?;
+    expect_token(tokens, Token::CloseParenthesis)?;
+    let then = parse_statement(tokens, depth + 1)?;
+
+    // An `else` belongs to the innermost `if` that it follows
+    let otherwise = match tokens.front() {
+        Some(spanned_token) if spanned_token.token == Token::ElseKeyword => {
+            _ = tokens
+                .pop_front()
+                .expect("Already confirmed at least one token in the queue");
+            Some(Box::new(parse_statement(tokens, depth + 1)?))
+        }
+        _ => None,
+    };
+
+    Ok(Statement::If {
+        condition,
+        then: Box::new(then),
+        otherwise,
+    })
+}
+
+fn parse_while_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    expect_token(tokens, Token::WhileKeyword)?;
+    expect_token(tokens, Token::OpenParenthesis)?;
+    let condition = parse_expression(tokens, 0)?;
+    expect_token(tokens, Token::CloseParenthesis)?;
+    let body = parse_statement(tokens, depth + 1)?;
+
+    Ok(Statement::While {
+        condition,
+        body: Box::new(body),
+        label: None,
+    })
+}
+
+/// Parse a `do` loop up to (but not including) the semicolon that ends it
+fn parse_do_while_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    expect_token(tokens, Token::DoKeyword)?;
+    let body = parse_statement(tokens, depth + 1)?;
+    expect_token(tokens, Token::WhileKeyword)?;
+    expect_token(tokens, Token::OpenParenthesis)?;
+    let condition = parse_expression(tokens, 0)?;
+    expect_token(tokens, Token::CloseParenthesis)?;
+
+    Ok(Statement::DoWhile {
+        body: Box::new(body),
+        condition,
+        label: None,
+    })
+}
+
+fn parse_for_statement(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Statement, ParseError> {
+    expect_token(tokens, Token::ForKeyword)?;
+    expect_token(tokens, Token::OpenParenthesis)?;
+
+    let next_token = &peek_token(tokens, "initial clause of `for` loop")?.token;
+    // A declaration consumes its own trailing semicolon
+    let init = match next_token {
+        Token::IntKeyword => ForInit::InitDecl(parse_declaration(tokens)?),
+        _ => ForInit::InitExp(parse_optional_expression(tokens, Token::Semicolon)?),
+    };
+    let condition = parse_optional_expression(tokens, Token::Semicolon)?;
+    let post = parse_optional_expression(tokens, Token::CloseParenthesis)?;
+    let body = parse_statement(tokens, depth + 1)?;
+
+    Ok(Statement::For {
+        init,
+        condition,
+        post,
+        body: Box::new(body),
+        label: None,
+    })
+}
+
+/// Parse an expression that may be omitted, followed by the given delimiter token (which is
+/// consumed)
+fn parse_optional_expression(
+    tokens: &mut VecDeque<SpannedToken>,
+    delimiter: Token,
+) -> Result<Option<Expression>, ParseError> {
+    let next_token = &peek_token(tokens, &format!("expression or `{}`", delimiter))?.token;
+    let expression = if *next_token == delimiter {
+        None
+    } else {
+        Some(parse_expression(tokens, 0)?)
+    };
+
+    expect_token(tokens, delimiter)?;
+    Ok(expression)
+}
+
+fn parse_declaration(tokens: &mut VecDeque<SpannedToken>) -> Result<Declaration, ParseError> {
+    expect_token(tokens, Token::IntKeyword)?;
+
+    let next_token = pop_token(tokens, "variable name")?;
+    let name = match next_token.token {
         Token::Identifier(identifier) => identifier,
-        _ => todo!(),
+        _ => return Err(ParseError::unexpected_token(&next_token, "variable name")),
     };
 
-    let next_token = tokens
-        .pop_front()
-        .expect("Should have non-empty queue of tokens");
-    if next_token != Token::OpenParenthesis {
-        todo!()
+    let next_token = pop_token(tokens, "`=` or `;`")?;
+    let init = match next_token.token {
+        Token::Semicolon => None,
+        Token::Equals => {
+            let init = parse_expression(tokens, ASSIGNMENT_PRECEDENCE)?;
+            expect_token(tokens, Token::Semicolon)?;
+            Some(init)
+        }
+        _ => return Err(ParseError::unexpected_token(&next_token, "`=` or `;`")),
+    };
+
+    Ok(Declaration::Declaration { name, init })
+}
+
+/// Parse a block item that's nested inside `depth` statements
+fn parse_block_item(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<BlockItem, ParseError> {
+    let next_token = peek_token(tokens, "declaration or statement")?;
+    let span = Some(next_token.span);
+
+    match next_token.token {
+        Token::IntKeyword => Ok(BlockItem::Declaration(parse_declaration(tokens)?, span)),
+        _ => Ok(BlockItem::Statement(parse_statement(tokens, depth)?, span)),
     }
+}
 
-    let next_token = tokens
-        .pop_front()
-        .expect("Should have non-emtyp queue of tokens");
-    if next_token != Token::CloseParenthesis {
-        todo!()
+/// Parse the block items between a pair of braces, including the braces, where the block items are
+/// nested inside `depth` statements
+fn parse_block(
+    tokens: &mut VecDeque<SpannedToken>,
+    depth: usize,
+) -> Result<Vec<BlockItem>, ParseError> {
+    expect_token(tokens, Token::OpenBrace)?;
+
+    let mut block_item_ast_nodes = Vec::new();
+    while peek_token(tokens, "`}`")?.token != Token::CloseBrace {
+        block_item_ast_nodes.push(parse_block_item(tokens, depth)?);
     }
 
-    let next_token = tokens
+    _ = tokens
         .pop_front()
-        .expect("Should have non-empty queue of tokens");
-    if next_token != Token::OpenBrace {
-        todo!()
-    }
+        .expect("Already confirmed at least one token in the queue");
+
+    Ok(block_item_ast_nodes)
+}
 
-    let statement_ast_node = parse_statement(tokens);
+/// Parse the parameters of a function up to and including the closing parenthesis. Both `()` and
+/// `(void)` are parsed as an empty parameter list.
+fn parse_parameter_list(
+    tokens: &mut VecDeque<SpannedToken>,
+) -> Result<Vec<Identifier>, ParseError> {
+    let mut params = Vec::new();
 
-    let next_token = tokens
-        .pop_front()
-        .expect("Should have non-empty queue of tokens");
-    if next_token != Token::CloseBrace {
-        todo!()
+    let next_token = &peek_token(tokens, "parameter list")?.token;
+    match next_token {
+        Token::CloseParenthesis => {
+            _ = tokens
+                .pop_front()
+                .expect("Already confirmed at least one token in the queue");
+            return Ok(params);
+        }
+        Token::VoidKeyword
+            if tokens
+                .get(1)
+                .is_some_and(|spanned_token| spanned_token.token == Token::CloseParenthesis) =>
+        {
+            _ = tokens.drain(..2);
+            return Ok(params);
+        }
+        _ => (),
     }
 
-    FunctionDefinition::Function {
-        name: identifier.to_string(),
-        body: statement_ast_node,
+    loop {
+        let next_token = pop_token(tokens, "type of function parameter")?;
+        if next_token.token != Token::IntKeyword {
+            return Err(ParseError::unexpected_token(
+                &next_token,
+                "type of function parameter",
+            ));
+        }
+
+        let next_token = pop_token(tokens, "parameter name")?;
+        match next_token.token {
+            Token::Identifier(identifier) => params.push(identifier),
+            _ => return Err(ParseError::unexpected_token(&next_token, "parameter name")),
+        }
+
+        let next_token = pop_token(tokens, "`,` or `)`")?;
+        match next_token.token {
+            Token::Comma => continue,
+            Token::CloseParenthesis => break Ok(params),
+            _ => return Err(ParseError::unexpected_token(&next_token, "`,` or `)`")),
+        }
     }
 }
 
-pub fn parse_program_definition(tokens: &mut VecDeque<Token>) -> ProgramDefinition {
-    let function_defn_ast_node = parse_function_definition(tokens);
-    ProgramDefinition::Program(function_defn_ast_node)
+fn parse_function_definition(
+    tokens: &mut VecDeque<SpannedToken>,
+) -> Result<FunctionDefinition, ParseError> {
+    expect_token(tokens, Token::IntKeyword)?;
+
+    let next_token = pop_token(tokens, "function name")?;
+    let identifier = match next_token.token {
+        Token::Identifier(identifier) => identifier,
+        _ => return Err(ParseError::unexpected_token(&next_token, "function name")),
+    };
+
+    expect_token(tokens, Token::OpenParenthesis)?;
+    let params = parse_parameter_list(tokens)?;
+
+    let next_token = peek_token(tokens, "function body or `;`")?;
+    let body = match next_token.token {
+        Token::Semicolon => {
+            _ = tokens
+                .pop_front()
+                .expect("Already confirmed at least one token in the queue");
+            None
+        }
+        Token::OpenBrace => Some(parse_block(tokens, 0)?),
+        _ => {
+            return Err(ParseError::unexpected_token(
+                next_token,
+                "function body or `;`",
+            ))
+        }
+    };
+
+    Ok(FunctionDefinition::Function {
+        name: identifier,
+        params,
+        body,
+    })
+}
+
+pub fn parse_program_definition(
+    tokens: &mut VecDeque<SpannedToken>,
+) -> Result<ProgramDefinition, ParseError> {
+    let mut function_defn_ast_nodes = Vec::new();
+    while let Some(next_token) = tokens.front() {
+        // Every function definition starts with its return type, so any other token must be left
+        // over after the end of the program
+        if next_token.token != Token::IntKeyword {
+            return Err(ParseError::TrailingToken {
+                found: next_token.token.clone(),
+                line: next_token.span.line,
+                column: next_token.span.column,
+            });
+        }
+        function_defn_ast_nodes.push(parse_function_definition(tokens)?);
+    }
+    Ok(ProgramDefinition::Program(function_defn_ast_nodes))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lex::{lex, Span, PUNCTUATORS};
 
-    #[test]
-    fn parse_expression_containing_numeric_constant() {
-        let value = 2;
-        let mut tokens = VecDeque::from([Token::NumericConstant(value)]);
-        let expected_ast_node = Expression::NumericConstant(value);
-        let ast_node = parse_factor(&mut tokens);
-        assert_eq!(0, tokens.len());
+    /// Create a queue of tokens that are all given the same arbitrary position in the C source code
+    fn spanned_tokens<const N: usize>(tokens: [Token; N]) -> VecDeque<SpannedToken> {
+        tokens
+            .into_iter()
+            .map(|token| SpannedToken {
+                token,
+                span: Span { line: 1, column: 1 },
+            })
+            .collect()
+    }
+
+    /// Wrap the tokens in a function body of the form `int main(void) { <tokens> }`, and parse
+    /// the program to get the block items in the body
+    fn parse_block_items_in_program(
+        tokens: VecDeque<SpannedToken>,
+    ) -> Result<Vec<BlockItem>, ParseError> {
+        let mut program_tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::VoidKeyword,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+        ]);
+        program_tokens.extend(tokens);
+        program_tokens.extend(spanned_tokens([Token::CloseBrace]));
+        match parse_function_defn_in_program(program_tokens)? {
+            FunctionDefinition::Function {
+                body: Some(body), ..
+            } => Ok(body),
+            function_defn => panic!("Expected function with body, got {:?}", function_defn),
+        }
+    }
+
+    /// Parse a program consisting of a single statement in the body of `main()`, and get the
+    /// statement
+    fn parse_statement_in_program(tokens: VecDeque<SpannedToken>) -> Result<Statement, ParseError> {
+        let mut block_items = parse_block_items_in_program(tokens)?;
+        match block_items.pop() {
+            Some(BlockItem::Statement(statement, _)) if block_items.is_empty() => Ok(statement),
+            block_item => panic!("Expected a single statement, got {:?}", block_item),
+        }
+    }
+
+    /// Parse a program consisting of a single declaration in the body of `main()`, and get the
+    /// declaration
+    fn parse_declaration_in_program(
+        tokens: VecDeque<SpannedToken>,
+    ) -> Result<Declaration, ParseError> {
+        let mut block_items = parse_block_items_in_program(tokens)?;
+        match block_items.pop() {
+            Some(BlockItem::Declaration(declaration, _)) if block_items.is_empty() => {
+                Ok(declaration)
+            }
+            block_item => panic!("Expected a single declaration, got {:?}", block_item),
+        }
+    }
+
+    /// Parse a program of the form `int main(void) { return <tokens>; }`, and get the returned
+    /// expression
+    fn parse_expression_in_program(
+        tokens: VecDeque<SpannedToken>,
+    ) -> Result<Expression, ParseError> {
+        let mut statement_tokens = spanned_tokens([Token::ReturnKeyword]);
+        statement_tokens.extend(tokens);
+        statement_tokens.extend(spanned_tokens([Token::Semicolon]));
+        match parse_statement_in_program(statement_tokens)? {
+            Statement::Return(expression) => Ok(expression),
+            statement => panic!("Expected return statement, got {:?}", statement),
+        }
+    }
+
+    /// Parse a program consisting of a single function, and get the function
+    fn parse_function_defn_in_program(
+        mut tokens: VecDeque<SpannedToken>,
+    ) -> Result<FunctionDefinition, ParseError> {
+        let ProgramDefinition::Program(mut function_defns) = parse_program_definition(&mut tokens)?;
+        match function_defns.pop() {
+            Some(function_defn) if function_defns.is_empty() => Ok(function_defn),
+            function_defn => panic!("Expected a single function, got {:?}", function_defn),
+        }
+    }
+
+    /// Parse a program consisting of a function declaration of the form `int foo(<tokens>;`, and
+    /// get the parameters of the function
+    fn parse_parameter_list_in_program(
+        tokens: VecDeque<SpannedToken>,
+    ) -> Result<Vec<Identifier>, ParseError> {
+        let mut program_tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("foo".to_string()),
+            Token::OpenParenthesis,
+        ]);
+        program_tokens.extend(tokens);
+        program_tokens.extend(spanned_tokens([Token::Semicolon]));
+        let FunctionDefinition::Function { params, .. } =
+            parse_function_defn_in_program(program_tokens)?;
+        Ok(params)
+    }
+
+    #[test]
+    fn parse_expression_containing_numeric_constant() {
+        let value = 2;
+        let tokens = spanned_tokens([Token::NumericConstant(value)]);
+        let expected_ast_node = Expression::NumericConstant(value);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(ast_node, expected_ast_node);
     }
 
     #[test]
     fn parse_expression_containing_bitwise_complement_operator() {
         let value = 2;
-        let mut tokens = VecDeque::from([Token::Tilde, Token::NumericConstant(value)]);
+        let tokens = spanned_tokens([Token::Tilde, Token::NumericConstant(value)]);
         let boxed_expression_ast_node = Box::new(Expression::NumericConstant(value));
         let expected_ast_node =
             Expression::Unary(UnaryOperator::BitwiseComplement, boxed_expression_ast_node);
-        let ast_node = parse_factor(&mut tokens);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(ast_node, expected_ast_node);
     }
 
     #[test]
     fn parse_expression_containing_negation_operator() {
-        let value = 2;
-        let mut tokens = VecDeque::from([Token::Minus, Token::NumericConstant(value)]);
-        let boxed_expression_ast_node = Box::new(Expression::NumericConstant(value));
+        let tokens = spanned_tokens([Token::Minus, Token::Identifier("a".to_string())]);
+        let boxed_expression_ast_node = Box::new(Expression::Var("a".to_string()));
         let expected_ast_node =
             Expression::Unary(UnaryOperator::Negation, boxed_expression_ast_node);
-        let ast_node = parse_factor(&mut tokens);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(ast_node, expected_ast_node);
     }
 
     #[test]
     fn parse_expression_containing_expression_wrapped_in_parentheses() {
-        let value = 2;
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::OpenParenthesis,
             Token::Minus,
-            Token::NumericConstant(value),
+            Token::Identifier("a".to_string()),
             Token::CloseParenthesis,
         ]);
-        let boxed_expression_ast_node = Box::new(Expression::NumericConstant(value));
+        let boxed_expression_ast_node = Box::new(Expression::Var("a".to_string()));
         let expected_ast_node =
             Expression::Unary(UnaryOperator::Negation, boxed_expression_ast_node);
-        let ast_node = parse_factor(&mut tokens);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(ast_node, expected_ast_node);
     }
 
     #[test]
-    #[should_panic(expected = "Invalid syntax: expected closing parenthesis")]
-    fn panic_if_open_parenthesis_before_expression_but_no_close_parenthesis_after() {
+    fn error_if_open_parenthesis_before_expression_but_no_close_parenthesis_after() {
         let value = 2;
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::OpenParenthesis,
             Token::Minus,
             Token::NumericConstant(value),
             Token::CloseBrace,
         ]);
-        _ = parse_factor(&mut tokens);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "`)`".to_string(),
+            found: Token::CloseBrace,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_expression_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
     }
 
     #[test]
     fn parse_statement_with_return_identifier_and_numeric_expression() {
         let value = 2;
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::ReturnKeyword,
             Token::NumericConstant(value),
             Token::Semicolon,
         ]);
         let expected_ast_node = Statement::Return(Expression::NumericConstant(value));
-        let ast_node = parse_statement(&mut tokens);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_statement_in_program(tokens).unwrap();
         assert_eq!(ast_node, expected_ast_node);
     }
 
@@ -345,7 +1358,7 @@ mod tests {
     fn parse_function_defn_with_int_return_and_statement_as_body() {
         let value = 2;
         let identifier = "main";
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::IntKeyword,
             Token::Identifier(identifier.to_string()),
             Token::OpenParenthesis,
@@ -360,10 +1373,13 @@ mod tests {
         let statement_ast_node = Statement::Return(expression_ast_node);
         let expected_ast_node = FunctionDefinition::Function {
             name: identifier.to_string(),
-            body: statement_ast_node,
+            params: vec![],
+            body: Some(vec![BlockItem::Statement(
+                statement_ast_node,
+                Some(Span { line: 1, column: 1 }),
+            )]),
         };
-        let ast_node = parse_function_definition(&mut tokens);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_function_defn_in_program(tokens).unwrap();
         assert_eq!(ast_node, expected_ast_node);
     }
 
@@ -371,7 +1387,7 @@ mod tests {
     fn parse_program_defn_consisting_of_single_function_defn() {
         let value = 2;
         let identifier = "main";
-        let mut tokens = VecDeque::from([
+        let mut tokens = spanned_tokens([
             Token::IntKeyword,
             Token::Identifier(identifier.to_string()),
             Token::OpenParenthesis,
@@ -386,46 +1402,52 @@ mod tests {
         let statement_ast_node = Statement::Return(expression_ast_node);
         let function_defn_ast_node = FunctionDefinition::Function {
             name: identifier.to_string(),
-            body: statement_ast_node,
+            params: vec![],
+            body: Some(vec![BlockItem::Statement(
+                statement_ast_node,
+                Some(Span { line: 1, column: 1 }),
+            )]),
         };
-        let expected_ast_node = ProgramDefinition::Program(function_defn_ast_node);
-        let ast_node = parse_program_definition(&mut tokens);
+        let expected_ast_node = ProgramDefinition::Program(vec![function_defn_ast_node]);
+        let ast_node = parse_program_definition(&mut tokens).unwrap();
         assert_eq!(0, tokens.len());
         assert_eq!(ast_node, expected_ast_node);
     }
 
     #[test]
     fn parse_bitwise_complement_operator() {
-        let mut tokens = VecDeque::from([Token::Tilde]);
-        let expected_ast_node = UnaryOperator::BitwiseComplement;
-        let ast_node = parse_unary_operator(&mut tokens);
-        assert_eq!(0, tokens.len());
+        let tokens = spanned_tokens([Token::Tilde, Token::NumericConstant(1)]);
+        let expected_ast_node = Expression::Unary(
+            UnaryOperator::BitwiseComplement,
+            Box::new(Expression::NumericConstant(1)),
+        );
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(expected_ast_node, ast_node);
     }
 
     #[test]
     fn parse_negation_operator() {
-        let mut tokens = VecDeque::from([Token::Minus]);
-        let expected_ast_node = UnaryOperator::Negation;
-        let ast_node = parse_unary_operator(&mut tokens);
-        assert_eq!(0, tokens.len());
+        let tokens = spanned_tokens([Token::Minus, Token::Identifier("a".to_string())]);
+        let expected_ast_node = Expression::Unary(
+            UnaryOperator::Negation,
+            Box::new(Expression::Var("a".to_string())),
+        );
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(expected_ast_node, ast_node);
     }
 
     #[test]
     fn parse_addition_operator() {
-        let mut tokens = VecDeque::from([Token::Plus]);
-        let expected_ast_node = BinaryOperator::Add;
-        let ast_node = parse_binary_operator(&mut tokens);
-        assert_eq!(0, tokens.len());
-        assert_eq!(expected_ast_node, ast_node);
+        let (op, _, associativity) = binary_operator_info(&Token::Plus).unwrap();
+        assert_eq!(op, InfixOperator::Binary(BinaryOperator::Add));
+        assert_eq!(associativity, Associativity::Left);
     }
 
     #[test]
     fn parse_single_addition_operator_expression() {
         let left_operand = 1;
         let right_operand = 2;
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::NumericConstant(left_operand),
             Token::Plus,
             Token::NumericConstant(right_operand),
@@ -437,8 +1459,7 @@ mod tests {
             left: boxed_left,
             right: boxed_right,
         };
-        let ast_node = parse_expression(&mut tokens, 0);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(expected_ast_node, ast_node);
     }
 
@@ -447,7 +1468,7 @@ mod tests {
         let inner_left_operand = 1;
         let inner_right_operand = 2;
         let outer_right_operand = 3;
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::NumericConstant(inner_left_operand),
             Token::Plus,
             Token::NumericConstant(inner_right_operand),
@@ -463,8 +1484,7 @@ mod tests {
             }),
             right: Box::new(Expression::NumericConstant(outer_right_operand)),
         };
-        let ast_node = parse_expression(&mut tokens, 0);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(expected_ast_node, ast_node);
     }
 
@@ -473,7 +1493,7 @@ mod tests {
         let outer_left_operand = 1;
         let inner_left_operand = 2;
         let inner_right_operand = 3;
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::NumericConstant(outer_left_operand),
             Token::Plus,
             Token::NumericConstant(inner_left_operand),
@@ -489,8 +1509,7 @@ mod tests {
                 right: Box::new(Expression::NumericConstant(3)),
             }),
         };
-        let ast_node = parse_expression(&mut tokens, 0);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(expected_ast_node, ast_node);
     }
 
@@ -498,7 +1517,7 @@ mod tests {
     fn parse_expression_with_division_operator() {
         let left_operand = 1;
         let right_operand = 2;
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::NumericConstant(left_operand),
             Token::ForwardSlash,
             Token::NumericConstant(right_operand),
@@ -508,8 +1527,7 @@ mod tests {
             left: Box::new(Expression::NumericConstant(left_operand)),
             right: Box::new(Expression::NumericConstant(right_operand)),
         };
-        let ast_node = parse_expression(&mut tokens, 0);
-        assert_eq!(0, tokens.len());
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(expected_ast_node, ast_node);
     }
 
@@ -517,7 +1535,7 @@ mod tests {
     fn parse_expression_with_modulo_operator() {
         let left_operand = 10;
         let right_operand = 3;
-        let mut tokens = VecDeque::from([
+        let tokens = spanned_tokens([
             Token::NumericConstant(left_operand),
             Token::Percent,
             Token::NumericConstant(right_operand),
@@ -527,8 +1545,2723 @@ mod tests {
             left: Box::new(Expression::NumericConstant(left_operand)),
             right: Box::new(Expression::NumericConstant(right_operand)),
         };
-        let ast_node = parse_expression(&mut tokens, 0);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_statement_returning_addition_expression() {
+        let tokens = spanned_tokens([
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::Return(Expression::Binary {
+            op: BinaryOperator::Add,
+            left: Box::new(Expression::NumericConstant(1)),
+            right: Box::new(Expression::NumericConstant(2)),
+        });
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_statement_returning_expression_with_two_different_precedence_binary_operators() {
+        let tokens = spanned_tokens([
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Asterisk,
+            Token::NumericConstant(3),
+            Token::Plus,
+            Token::NumericConstant(4),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::Return(Expression::Binary {
+            op: BinaryOperator::Add,
+            left: Box::new(Expression::Binary {
+                op: BinaryOperator::Multiply,
+                left: Box::new(Expression::NumericConstant(2)),
+                right: Box::new(Expression::NumericConstant(3)),
+            }),
+            right: Box::new(Expression::NumericConstant(4)),
+        });
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_statement_returning_parenthesized_binary_expression() {
+        let tokens = spanned_tokens([
+            Token::ReturnKeyword,
+            Token::OpenParenthesis,
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(2),
+            Token::CloseParenthesis,
+            Token::Asterisk,
+            Token::NumericConstant(3),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::Return(Expression::Binary {
+            op: BinaryOperator::Multiply,
+            left: Box::new(Expression::Binary {
+                op: BinaryOperator::Add,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::NumericConstant(2)),
+            }),
+            right: Box::new(Expression::NumericConstant(3)),
+        });
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_program_defn_with_function_returning_binary_expression() {
+        let identifier = "main";
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expression_ast_node = Expression::Binary {
+            op: BinaryOperator::Add,
+            left: Box::new(Expression::NumericConstant(1)),
+            right: Box::new(Expression::NumericConstant(2)),
+        };
+        let function_defn_ast_node = FunctionDefinition::Function {
+            name: identifier.to_string(),
+            params: vec![],
+            body: Some(vec![BlockItem::Statement(
+                Statement::Return(expression_ast_node),
+                Some(Span { line: 1, column: 1 }),
+            )]),
+        };
+        let expected_ast_node = ProgramDefinition::Program(vec![function_defn_ast_node]);
+        let ast_node = parse_program_definition(&mut tokens).unwrap();
+        assert_eq!(0, tokens.len());
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_statement_returning_modulo_expression() {
+        let tokens = spanned_tokens([
+            Token::ReturnKeyword,
+            Token::NumericConstant(7),
+            Token::Percent,
+            Token::NumericConstant(3),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::Return(Expression::Binary {
+            op: BinaryOperator::Modulo,
+            left: Box::new(Expression::NumericConstant(7)),
+            right: Box::new(Expression::NumericConstant(3)),
+        });
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_program_defn_with_function_returning_modulo_expression() {
+        let identifier = "main";
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(7),
+            Token::Percent,
+            Token::NumericConstant(3),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expression_ast_node = Expression::Binary {
+            op: BinaryOperator::Modulo,
+            left: Box::new(Expression::NumericConstant(7)),
+            right: Box::new(Expression::NumericConstant(3)),
+        };
+        let function_defn_ast_node = FunctionDefinition::Function {
+            name: identifier.to_string(),
+            params: vec![],
+            body: Some(vec![BlockItem::Statement(
+                Statement::Return(expression_ast_node),
+                Some(Span { line: 1, column: 1 }),
+            )]),
+        };
+        let expected_ast_node = ProgramDefinition::Program(vec![function_defn_ast_node]);
+        let ast_node = parse_program_definition(&mut tokens).unwrap();
         assert_eq!(0, tokens.len());
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_shift_left_operator() {
+        let (op, _, associativity) = binary_operator_info(&Token::DoubleLeftAngleBracket).unwrap();
+        assert_eq!(op, InfixOperator::Binary(BinaryOperator::ShiftLeft));
+        assert_eq!(associativity, Associativity::Left);
+    }
+
+    #[test]
+    fn parse_shift_right_operator() {
+        let (op, _, associativity) = binary_operator_info(&Token::DoubleRightAngleBracket).unwrap();
+        assert_eq!(op, InfixOperator::Binary(BinaryOperator::ShiftRight));
+        assert_eq!(associativity, Associativity::Left);
+    }
+
+    #[test]
+    fn parse_expression_with_shift_operator_having_lower_precedence_than_addition() {
+        // 1 << 2 + 3 => 1 << (2 + 3)
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::DoubleLeftAngleBracket,
+            Token::NumericConstant(2),
+            Token::Plus,
+            Token::NumericConstant(3),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::ShiftLeft,
+            left: Box::new(Expression::NumericConstant(1)),
+            right: Box::new(Expression::Binary {
+                op: BinaryOperator::Add,
+                left: Box::new(Expression::NumericConstant(2)),
+                right: Box::new(Expression::NumericConstant(3)),
+            }),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_with_shift_operators_is_left_associative() {
+        // 1 << 2 >> 3 => (1 << 2) >> 3
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::DoubleLeftAngleBracket,
+            Token::NumericConstant(2),
+            Token::DoubleRightAngleBracket,
+            Token::NumericConstant(3),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::ShiftRight,
+            left: Box::new(Expression::Binary {
+                op: BinaryOperator::ShiftLeft,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::NumericConstant(2)),
+            }),
+            right: Box::new(Expression::NumericConstant(3)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_with_lower_precedence_operator_after_higher_precedence_right_operand() {
+        // 1 << 2 * 3 << 4 => (1 << (2 * 3)) << 4
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::DoubleLeftAngleBracket,
+            Token::NumericConstant(2),
+            Token::Asterisk,
+            Token::NumericConstant(3),
+            Token::DoubleLeftAngleBracket,
+            Token::NumericConstant(4),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::ShiftLeft,
+            left: Box::new(Expression::Binary {
+                op: BinaryOperator::ShiftLeft,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: Box::new(Expression::NumericConstant(2)),
+                    right: Box::new(Expression::NumericConstant(3)),
+                }),
+            }),
+            right: Box::new(Expression::NumericConstant(4)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_not_operator() {
+        let tokens = spanned_tokens([Token::Exclamation, Token::NumericConstant(1)]);
+        let expected_ast_node =
+            Expression::Unary(UnaryOperator::Not, Box::new(Expression::NumericConstant(1)));
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_containing_two_not_operators() {
+        let value = 2;
+        let tokens = spanned_tokens([
+            Token::Exclamation,
+            Token::Exclamation,
+            Token::NumericConstant(value),
+        ]);
+        let expected_ast_node = Expression::Unary(
+            UnaryOperator::Not,
+            Box::new(Expression::Unary(
+                UnaryOperator::Not,
+                Box::new(Expression::NumericConstant(value)),
+            )),
+        );
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_expression_containing_not_operator_applied_to_negation() {
+        let tokens = spanned_tokens([
+            Token::Exclamation,
+            Token::Minus,
+            Token::Identifier("a".to_string()),
+        ]);
+        let expected_ast_node = Expression::Unary(
+            UnaryOperator::Not,
+            Box::new(Expression::Unary(
+                UnaryOperator::Negation,
+                Box::new(Expression::Var("a".to_string())),
+            )),
+        );
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_expression_containing_not_operator_applied_to_parenthesized_binary_expression() {
+        let tokens = spanned_tokens([
+            Token::Exclamation,
+            Token::OpenParenthesis,
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(2),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::Unary(
+            UnaryOperator::Not,
+            Box::new(Expression::Binary {
+                op: BinaryOperator::Add,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::NumericConstant(2)),
+            }),
+        );
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_relational_operators() {
+        let operators = [
+            (Token::LeftAngleBracket, BinaryOperator::LessThan),
+            (Token::RightAngleBracket, BinaryOperator::GreaterThan),
+            (Token::LeftAngleBracketEquals, BinaryOperator::LessOrEqual),
+            (
+                Token::RightAngleBracketEquals,
+                BinaryOperator::GreaterOrEqual,
+            ),
+        ];
+        for (token, expected_ast_node) in operators {
+            let (op, _, associativity) = binary_operator_info(&token).unwrap();
+            assert_eq!(op, InfixOperator::Binary(expected_ast_node));
+            assert_eq!(associativity, Associativity::Left);
+        }
+    }
+
+    #[test]
+    fn parse_expression_with_relational_operator_having_lower_precedence_than_arithmetic() {
+        // 1 + 2 < 3 * 4 => (1 + 2) < (3 * 4)
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(2),
+            Token::LeftAngleBracket,
+            Token::NumericConstant(3),
+            Token::Asterisk,
+            Token::NumericConstant(4),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::LessThan,
+            left: Box::new(Expression::Binary {
+                op: BinaryOperator::Add,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::NumericConstant(2)),
+            }),
+            right: Box::new(Expression::Binary {
+                op: BinaryOperator::Multiply,
+                left: Box::new(Expression::NumericConstant(3)),
+                right: Box::new(Expression::NumericConstant(4)),
+            }),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_with_relational_operator_having_lower_precedence_than_shift() {
+        // 1 << 2 >= 3 => (1 << 2) >= 3
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::DoubleLeftAngleBracket,
+            Token::NumericConstant(2),
+            Token::RightAngleBracketEquals,
+            Token::NumericConstant(3),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::GreaterOrEqual,
+            left: Box::new(Expression::Binary {
+                op: BinaryOperator::ShiftLeft,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::NumericConstant(2)),
+            }),
+            right: Box::new(Expression::NumericConstant(3)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_with_chained_relational_operators_is_left_associative() {
+        // 1 < 2 < 3 => (1 < 2) < 3
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::LeftAngleBracket,
+            Token::NumericConstant(2),
+            Token::LeftAngleBracket,
+            Token::NumericConstant(3),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::LessThan,
+            left: Box::new(Expression::Binary {
+                op: BinaryOperator::LessThan,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::NumericConstant(2)),
+            }),
+            right: Box::new(Expression::NumericConstant(3)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
         assert_eq!(expected_ast_node, ast_node);
     }
+
+    #[test]
+    fn parse_expression_containing_variable() {
+        let identifier = "a";
+        let tokens = spanned_tokens([Token::Identifier(identifier.to_string())]);
+        let expected_ast_node = Expression::Var(identifier.to_string());
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_compound_assignment_operators() {
+        let operators = [
+            (Token::PlusEquals, BinaryOperator::Add),
+            (Token::MinusEquals, BinaryOperator::Subtract),
+            (Token::AsteriskEquals, BinaryOperator::Multiply),
+            (Token::ForwardSlashEquals, BinaryOperator::Divide),
+            (Token::PercentEquals, BinaryOperator::Modulo),
+            (Token::AmpersandEquals, BinaryOperator::BitwiseAnd),
+            (Token::PipeEquals, BinaryOperator::BitwiseOr),
+            (Token::CaretEquals, BinaryOperator::BitwiseXor),
+            (
+                Token::DoubleLeftAngleBracketEquals,
+                BinaryOperator::ShiftLeft,
+            ),
+            (
+                Token::DoubleRightAngleBracketEquals,
+                BinaryOperator::ShiftRight,
+            ),
+        ];
+        for (token, expected_ast_node) in operators {
+            let (op, _, associativity) = binary_operator_info(&token).unwrap();
+            assert_eq!(op, InfixOperator::CompoundAssignment(expected_ast_node));
+            assert_eq!(associativity, Associativity::Right);
+        }
+    }
+
+    #[test]
+    fn parse_expression_with_each_compound_assignment_operator() {
+        let operators = [
+            (Token::PlusEquals, BinaryOperator::Add),
+            (Token::MinusEquals, BinaryOperator::Subtract),
+            (Token::AsteriskEquals, BinaryOperator::Multiply),
+            (Token::ForwardSlashEquals, BinaryOperator::Divide),
+            (Token::PercentEquals, BinaryOperator::Modulo),
+            (Token::AmpersandEquals, BinaryOperator::BitwiseAnd),
+            (Token::PipeEquals, BinaryOperator::BitwiseOr),
+            (Token::CaretEquals, BinaryOperator::BitwiseXor),
+            (
+                Token::DoubleLeftAngleBracketEquals,
+                BinaryOperator::ShiftLeft,
+            ),
+            (
+                Token::DoubleRightAngleBracketEquals,
+                BinaryOperator::ShiftRight,
+            ),
+        ];
+        for (token, op) in operators {
+            let tokens = spanned_tokens([
+                Token::Identifier("a".to_string()),
+                token,
+                Token::NumericConstant(3),
+            ]);
+            let expected_ast_node = Expression::CompoundAssignment {
+                op,
+                lhs: Box::new(Expression::Var("a".to_string())),
+                rhs: Box::new(Expression::NumericConstant(3)),
+            };
+            let ast_node = parse_expression_in_program(tokens).unwrap();
+            assert_eq!(expected_ast_node, ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_expression_with_chained_assignments_is_right_associative() {
+        // a = b = 5 => a = (b = 5)
+        let tokens = spanned_tokens([
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::Identifier("b".to_string()),
+            Token::Equals,
+            Token::NumericConstant(5),
+        ]);
+        let expected_ast_node = Expression::Assignment {
+            lhs: Box::new(Expression::Var("a".to_string())),
+            rhs: Box::new(Expression::Assignment {
+                lhs: Box::new(Expression::Var("b".to_string())),
+                rhs: Box::new(Expression::NumericConstant(5)),
+            }),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_with_chained_compound_assignments_is_right_associative() {
+        // a += b -= 2 => a += (b -= 2)
+        let tokens = spanned_tokens([
+            Token::Identifier("a".to_string()),
+            Token::PlusEquals,
+            Token::Identifier("b".to_string()),
+            Token::MinusEquals,
+            Token::NumericConstant(2),
+        ]);
+        let expected_ast_node = Expression::CompoundAssignment {
+            op: BinaryOperator::Add,
+            lhs: Box::new(Expression::Var("a".to_string())),
+            rhs: Box::new(Expression::CompoundAssignment {
+                op: BinaryOperator::Subtract,
+                lhs: Box::new(Expression::Var("b".to_string())),
+                rhs: Box::new(Expression::NumericConstant(2)),
+            }),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_with_compound_assignment_having_lower_precedence_than_arithmetic() {
+        // a *= 1 + 2 * 3 => a *= (1 + (2 * 3))
+        let tokens = spanned_tokens([
+            Token::Identifier("a".to_string()),
+            Token::AsteriskEquals,
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(2),
+            Token::Asterisk,
+            Token::NumericConstant(3),
+        ]);
+        let expected_ast_node = Expression::CompoundAssignment {
+            op: BinaryOperator::Multiply,
+            lhs: Box::new(Expression::Var("a".to_string())),
+            rhs: Box::new(Expression::Binary {
+                op: BinaryOperator::Add,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: Box::new(Expression::NumericConstant(2)),
+                    right: Box::new(Expression::NumericConstant(3)),
+                }),
+            }),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_with_bitwise_operators_in_precedence_order() {
+        // 1 | 2 ^ 3 & 4 => 1 | (2 ^ (3 & 4))
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::Pipe,
+            Token::NumericConstant(2),
+            Token::Caret,
+            Token::NumericConstant(3),
+            Token::Ampersand,
+            Token::NumericConstant(4),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::BitwiseOr,
+            left: Box::new(Expression::NumericConstant(1)),
+            right: Box::new(Expression::Binary {
+                op: BinaryOperator::BitwiseXor,
+                left: Box::new(Expression::NumericConstant(2)),
+                right: Box::new(Expression::Binary {
+                    op: BinaryOperator::BitwiseAnd,
+                    left: Box::new(Expression::NumericConstant(3)),
+                    right: Box::new(Expression::NumericConstant(4)),
+                }),
+            }),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_expression_statement() {
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::Expression(Expression::Binary {
+            op: BinaryOperator::Add,
+            left: Box::new(Expression::NumericConstant(1)),
+            right: Box::new(Expression::NumericConstant(1)),
+        });
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_declaration_without_initializer() {
+        let identifier = "a";
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Declaration::Declaration {
+            name: identifier.to_string(),
+            init: None,
+        };
+        let ast_node = parse_declaration_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_declaration_with_initializer() {
+        let identifier = "a";
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::Equals,
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Declaration::Declaration {
+            name: identifier.to_string(),
+            init: Some(Expression::Binary {
+                op: BinaryOperator::Add,
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::NumericConstant(2)),
+            }),
+        };
+        let ast_node = parse_declaration_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_function_defn_with_two_return_statements() {
+        let identifier = "main";
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = FunctionDefinition::Function {
+            name: identifier.to_string(),
+            params: vec![],
+            body: Some(vec![
+                BlockItem::Statement(
+                    Statement::Return(Expression::NumericConstant(1)),
+                    Some(Span { line: 1, column: 1 }),
+                ),
+                BlockItem::Statement(
+                    Statement::Return(Expression::NumericConstant(2)),
+                    Some(Span { line: 1, column: 1 }),
+                ),
+            ]),
+        };
+        let ast_node = parse_function_defn_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_function_defn_with_empty_body() {
+        let identifier = "main";
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = FunctionDefinition::Function {
+            name: identifier.to_string(),
+            params: vec![],
+            body: Some(vec![]),
+        };
+        let ast_node = parse_function_defn_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_function_defn_with_body_mixing_declarations_and_statements() {
+        // int main() { int a = 1; a = a + 1; int b; return a; }
+        let identifier = "main";
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::Identifier("a".to_string()),
+            Token::Plus,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::IntKeyword,
+            Token::Identifier("b".to_string()),
+            Token::Semicolon,
+            Token::ReturnKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = FunctionDefinition::Function {
+            name: identifier.to_string(),
+            params: vec![],
+            body: Some(vec![
+                BlockItem::Declaration(
+                    Declaration::Declaration {
+                        name: "a".to_string(),
+                        init: Some(Expression::NumericConstant(1)),
+                    },
+                    Some(Span { line: 1, column: 1 }),
+                ),
+                BlockItem::Statement(
+                    Statement::Expression(Expression::Assignment {
+                        lhs: Box::new(Expression::Var("a".to_string())),
+                        rhs: Box::new(Expression::Binary {
+                            op: BinaryOperator::Add,
+                            left: Box::new(Expression::Var("a".to_string())),
+                            right: Box::new(Expression::NumericConstant(1)),
+                        }),
+                    }),
+                    Some(Span { line: 1, column: 1 }),
+                ),
+                BlockItem::Declaration(
+                    Declaration::Declaration {
+                        name: "b".to_string(),
+                        init: None,
+                    },
+                    Some(Span { line: 1, column: 1 }),
+                ),
+                BlockItem::Statement(
+                    Statement::Return(Expression::Var("a".to_string())),
+                    Some(Span { line: 1, column: 1 }),
+                ),
+            ]),
+        };
+        let ast_node = parse_function_defn_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_equality_and_logical_operators() {
+        let operators = [
+            (Token::DoubleEquals, BinaryOperator::Equal),
+            (Token::ExclamationEquals, BinaryOperator::NotEqual),
+            (Token::DoubleAmpersand, BinaryOperator::And),
+            (Token::DoublePipe, BinaryOperator::Or),
+        ];
+        for (token, expected_ast_node) in operators {
+            let (op, _, associativity) = binary_operator_info(&token).unwrap();
+            assert_eq!(op, InfixOperator::Binary(expected_ast_node));
+            assert_eq!(associativity, Associativity::Left);
+        }
+    }
+
+    #[test]
+    fn parse_expression_with_logical_operators_having_lower_precedence_than_equality() {
+        // 1 == 2 && 3 != 4 || 5 => ((1 == 2) && (3 != 4)) || 5
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::DoubleEquals,
+            Token::NumericConstant(2),
+            Token::DoubleAmpersand,
+            Token::NumericConstant(3),
+            Token::ExclamationEquals,
+            Token::NumericConstant(4),
+            Token::DoublePipe,
+            Token::NumericConstant(5),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::Or,
+            left: Box::new(Expression::Binary {
+                op: BinaryOperator::And,
+                left: Box::new(Expression::Binary {
+                    op: BinaryOperator::Equal,
+                    left: Box::new(Expression::NumericConstant(1)),
+                    right: Box::new(Expression::NumericConstant(2)),
+                }),
+                right: Box::new(Expression::Binary {
+                    op: BinaryOperator::NotEqual,
+                    left: Box::new(Expression::NumericConstant(3)),
+                    right: Box::new(Expression::NumericConstant(4)),
+                }),
+            }),
+            right: Box::new(Expression::NumericConstant(5)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_conditional_expression() {
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::QuestionMark,
+            Token::NumericConstant(2),
+            Token::Colon,
+            Token::NumericConstant(3),
+        ]);
+        let expected_ast_node = Expression::Conditional {
+            condition: Box::new(Expression::NumericConstant(1)),
+            then: Box::new(Expression::NumericConstant(2)),
+            otherwise: Box::new(Expression::NumericConstant(3)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_nested_conditional_expressions_is_right_associative() {
+        // a ? b : c ? d : e => a ? b : (c ? d : e)
+        let tokens = spanned_tokens([
+            Token::Identifier("a".to_string()),
+            Token::QuestionMark,
+            Token::Identifier("b".to_string()),
+            Token::Colon,
+            Token::Identifier("c".to_string()),
+            Token::QuestionMark,
+            Token::Identifier("d".to_string()),
+            Token::Colon,
+            Token::Identifier("e".to_string()),
+        ]);
+        let expected_ast_node = Expression::Conditional {
+            condition: Box::new(Expression::Var("a".to_string())),
+            then: Box::new(Expression::Var("b".to_string())),
+            otherwise: Box::new(Expression::Conditional {
+                condition: Box::new(Expression::Var("c".to_string())),
+                then: Box::new(Expression::Var("d".to_string())),
+                otherwise: Box::new(Expression::Var("e".to_string())),
+            }),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_conditional_expression_with_logical_or_condition() {
+        // a || b ? 1 : 2 => (a || b) ? 1 : 2
+        let tokens = spanned_tokens([
+            Token::Identifier("a".to_string()),
+            Token::DoublePipe,
+            Token::Identifier("b".to_string()),
+            Token::QuestionMark,
+            Token::NumericConstant(1),
+            Token::Colon,
+            Token::NumericConstant(2),
+        ]);
+        let expected_ast_node = Expression::Conditional {
+            condition: Box::new(Expression::Binary {
+                op: BinaryOperator::Or,
+                left: Box::new(Expression::Var("a".to_string())),
+                right: Box::new(Expression::Var("b".to_string())),
+            }),
+            then: Box::new(Expression::NumericConstant(1)),
+            otherwise: Box::new(Expression::NumericConstant(2)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn parse_conditional_expression_with_assignments_in_branches() {
+        // a ? b = 1 : c = 2 => (a ? (b = 1) : c) = 2
+        let tokens = spanned_tokens([
+            Token::Identifier("a".to_string()),
+            Token::QuestionMark,
+            Token::Identifier("b".to_string()),
+            Token::Equals,
+            Token::NumericConstant(1),
+            Token::Colon,
+            Token::Identifier("c".to_string()),
+            Token::Equals,
+            Token::NumericConstant(2),
+        ]);
+        let expected_ast_node = Expression::Assignment {
+            lhs: Box::new(Expression::Conditional {
+                condition: Box::new(Expression::Var("a".to_string())),
+                then: Box::new(Expression::Assignment {
+                    lhs: Box::new(Expression::Var("b".to_string())),
+                    rhs: Box::new(Expression::NumericConstant(1)),
+                }),
+                otherwise: Box::new(Expression::Var("c".to_string())),
+            }),
+            rhs: Box::new(Expression::NumericConstant(2)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(expected_ast_node, ast_node);
+    }
+
+    #[test]
+    fn error_if_conditional_expression_missing_colon() {
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::QuestionMark,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "`:`".to_string(),
+            found: Token::Semicolon,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_expression_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_for_statement_with_declaration_in_init_clause() {
+        // for (int i = 0; i < 10; i = i + 1) a = i;
+        let tokens = spanned_tokens([
+            Token::ForKeyword,
+            Token::OpenParenthesis,
+            Token::IntKeyword,
+            Token::Identifier("i".to_string()),
+            Token::Equals,
+            Token::NumericConstant(0),
+            Token::Semicolon,
+            Token::Identifier("i".to_string()),
+            Token::LeftAngleBracket,
+            Token::NumericConstant(10),
+            Token::Semicolon,
+            Token::Identifier("i".to_string()),
+            Token::Equals,
+            Token::Identifier("i".to_string()),
+            Token::Plus,
+            Token::NumericConstant(1),
+            Token::CloseParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::Identifier("i".to_string()),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::For {
+            init: ForInit::InitDecl(Declaration::Declaration {
+                name: "i".to_string(),
+                init: Some(Expression::NumericConstant(0)),
+            }),
+            condition: Some(Expression::Binary {
+                op: BinaryOperator::LessThan,
+                left: Box::new(Expression::Var("i".to_string())),
+                right: Box::new(Expression::NumericConstant(10)),
+            }),
+            post: Some(Expression::Assignment {
+                lhs: Box::new(Expression::Var("i".to_string())),
+                rhs: Box::new(Expression::Binary {
+                    op: BinaryOperator::Add,
+                    left: Box::new(Expression::Var("i".to_string())),
+                    right: Box::new(Expression::NumericConstant(1)),
+                }),
+            }),
+            body: Box::new(Statement::Expression(Expression::Assignment {
+                lhs: Box::new(Expression::Var("a".to_string())),
+                rhs: Box::new(Expression::Var("i".to_string())),
+            })),
+            label: None,
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_for_statement_with_each_combination_of_empty_clauses() {
+        // for (<a = 1>; <a>; <a = 2>) 3;
+        for (has_init, has_condition, has_post) in [
+            (false, false, false),
+            (true, false, false),
+            (false, true, false),
+            (false, false, true),
+            (true, true, false),
+            (true, false, true),
+            (false, true, true),
+            (true, true, true),
+        ] {
+            let mut tokens = spanned_tokens([Token::ForKeyword, Token::OpenParenthesis]);
+            if has_init {
+                tokens.extend(spanned_tokens([
+                    Token::Identifier("a".to_string()),
+                    Token::Equals,
+                    Token::NumericConstant(1),
+                ]));
+            }
+            tokens.extend(spanned_tokens([Token::Semicolon]));
+            if has_condition {
+                tokens.extend(spanned_tokens([Token::Identifier("a".to_string())]));
+            }
+            tokens.extend(spanned_tokens([Token::Semicolon]));
+            if has_post {
+                tokens.extend(spanned_tokens([
+                    Token::Identifier("a".to_string()),
+                    Token::Equals,
+                    Token::NumericConstant(2),
+                ]));
+            }
+            tokens.extend(spanned_tokens([
+                Token::CloseParenthesis,
+                Token::NumericConstant(3),
+                Token::Semicolon,
+            ]));
+
+            let assignment = |value| Expression::Assignment {
+                lhs: Box::new(Expression::Var("a".to_string())),
+                rhs: Box::new(Expression::NumericConstant(value)),
+            };
+            let expected_ast_node = Statement::For {
+                init: ForInit::InitExp(has_init.then(|| assignment(1))),
+                condition: has_condition.then(|| Expression::Var("a".to_string())),
+                post: has_post.then(|| assignment(2)),
+                body: Box::new(Statement::Expression(Expression::NumericConstant(3))),
+                label: None,
+            };
+            let ast_node = parse_statement_in_program(tokens).unwrap();
+            assert_eq!(ast_node, expected_ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_nested_for_statements() {
+        // for (;;) for (;;) 1;
+        let tokens = spanned_tokens([
+            Token::ForKeyword,
+            Token::OpenParenthesis,
+            Token::Semicolon,
+            Token::Semicolon,
+            Token::CloseParenthesis,
+            Token::ForKeyword,
+            Token::OpenParenthesis,
+            Token::Semicolon,
+            Token::Semicolon,
+            Token::CloseParenthesis,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::For {
+            init: ForInit::InitExp(None),
+            condition: None,
+            post: None,
+            body: Box::new(Statement::For {
+                init: ForInit::InitExp(None),
+                condition: None,
+                post: None,
+                body: Box::new(Statement::Expression(Expression::NumericConstant(1))),
+                label: None,
+            }),
+            label: None,
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_goto_statement() {
+        let label = "end";
+        let tokens = spanned_tokens([
+            Token::GotoKeyword,
+            Token::Identifier(label.to_string()),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::Goto(label.to_string());
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_goto_statement_missing_semicolon() {
+        let tokens = spanned_tokens([
+            Token::GotoKeyword,
+            Token::Identifier("end".to_string()),
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "`;`".to_string(),
+            found: Token::ReturnKeyword,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_statement_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_label_followed_by_return_statement() {
+        let label = "end";
+        let tokens = spanned_tokens([
+            Token::Identifier(label.to_string()),
+            Token::Colon,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::Labeled {
+            label: label.to_string(),
+            statement: Box::new(Statement::Return(Expression::NumericConstant(2))),
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_identifier_followed_by_semicolon_as_expression_statement() {
+        let tokens = spanned_tokens([Token::Identifier("a".to_string()), Token::Semicolon]);
+        let expected_ast_node = Statement::Expression(Expression::Var("a".to_string()));
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_function_defn_with_goto_to_label_defined_later() {
+        // int main() { goto end; return 1; end: return 2; }
+        let identifier = "main";
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::GotoKeyword,
+            Token::Identifier("end".to_string()),
+            Token::Semicolon,
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::Identifier("end".to_string()),
+            Token::Colon,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = FunctionDefinition::Function {
+            name: identifier.to_string(),
+            params: vec![],
+            body: Some(vec![
+                BlockItem::Statement(
+                    Statement::Goto("end".to_string()),
+                    Some(Span { line: 1, column: 1 }),
+                ),
+                BlockItem::Statement(
+                    Statement::Return(Expression::NumericConstant(1)),
+                    Some(Span { line: 1, column: 1 }),
+                ),
+                BlockItem::Statement(
+                    Statement::Labeled {
+                        label: "end".to_string(),
+                        statement: Box::new(Statement::Return(Expression::NumericConstant(2))),
+                    },
+                    Some(Span { line: 1, column: 1 }),
+                ),
+            ]),
+        };
+        let ast_node = parse_function_defn_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_label_at_end_of_function_body() {
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::Identifier("end".to_string()),
+            Token::Colon,
+            Token::CloseBrace,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "statement after label".to_string(),
+            found: Token::CloseBrace,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_function_defn_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_null_statement() {
+        let tokens = spanned_tokens([Token::Semicolon]);
+        let expected_ast_node = Statement::Null;
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_function_defn_with_null_statements_before_return() {
+        // int main() { ;; return 2; }
+        let identifier = "main";
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier(identifier.to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::Semicolon,
+            Token::Semicolon,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = FunctionDefinition::Function {
+            name: identifier.to_string(),
+            params: vec![],
+            body: Some(vec![
+                BlockItem::Statement(Statement::Null, Some(Span { line: 1, column: 1 })),
+                BlockItem::Statement(Statement::Null, Some(Span { line: 1, column: 1 })),
+                BlockItem::Statement(
+                    Statement::Return(Expression::NumericConstant(2)),
+                    Some(Span { line: 1, column: 1 }),
+                ),
+            ]),
+        };
+        let ast_node = parse_function_defn_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_for_statement_with_null_statement_body() {
+        let tokens = spanned_tokens([
+            Token::ForKeyword,
+            Token::OpenParenthesis,
+            Token::Semicolon,
+            Token::Semicolon,
+            Token::CloseParenthesis,
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::For {
+            init: ForInit::InitExp(None),
+            condition: None,
+            post: None,
+            body: Box::new(Statement::Null),
+            label: None,
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_program_defn_consisting_of_two_function_defns() {
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("foo".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::CloseBrace,
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                params: vec![],
+                body: Some(vec![BlockItem::Statement(
+                    Statement::Return(Expression::NumericConstant(1)),
+                    Some(Span { line: 1, column: 1 }),
+                )]),
+            },
+            FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![BlockItem::Statement(
+                    Statement::Return(Expression::NumericConstant(2)),
+                    Some(Span { line: 1, column: 1 }),
+                )]),
+            },
+        ]);
+        let ast_node = parse_program_definition(&mut tokens).unwrap();
+        assert_eq!(0, tokens.len());
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_empty_parameter_list() {
+        let tokens = spanned_tokens([Token::CloseParenthesis]);
+        let params = parse_parameter_list_in_program(tokens).unwrap();
+        assert_eq!(params, Vec::<Identifier>::new());
+    }
+
+    #[test]
+    fn parse_void_parameter_list() {
+        let tokens = spanned_tokens([Token::VoidKeyword, Token::CloseParenthesis]);
+        let params = parse_parameter_list_in_program(tokens).unwrap();
+        assert_eq!(params, Vec::<Identifier>::new());
+    }
+
+    #[test]
+    fn parse_parameter_list_with_one_parameter() {
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+        ]);
+        let params = parse_parameter_list_in_program(tokens).unwrap();
+        assert_eq!(params, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn parse_parameter_list_with_three_parameters() {
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Comma,
+            Token::IntKeyword,
+            Token::Identifier("b".to_string()),
+            Token::Comma,
+            Token::IntKeyword,
+            Token::Identifier("c".to_string()),
+            Token::CloseParenthesis,
+        ]);
+        let params = parse_parameter_list_in_program(tokens).unwrap();
+        assert_eq!(
+            params,
+            vec!["a".to_string(), "b".to_string(), "c".to_string()]
+        );
+    }
+
+    #[test]
+    fn error_if_parameter_list_has_trailing_comma() {
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Comma,
+            Token::CloseParenthesis,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "type of function parameter".to_string(),
+            found: Token::CloseParenthesis,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_parameter_list_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_parameter_missing_type_keyword() {
+        let tokens = spanned_tokens([Token::Identifier("a".to_string()), Token::CloseParenthesis]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "type of function parameter".to_string(),
+            found: Token::Identifier("a".to_string()),
+            line: 1,
+            column: 1,
+        };
+        let res = parse_parameter_list_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_function_defn_with_parameters() {
+        // int add(int a, int b) { return a + b; }
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("add".to_string()),
+            Token::OpenParenthesis,
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Comma,
+            Token::IntKeyword,
+            Token::Identifier("b".to_string()),
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Plus,
+            Token::Identifier("b".to_string()),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = FunctionDefinition::Function {
+            name: "add".to_string(),
+            params: vec!["a".to_string(), "b".to_string()],
+            body: Some(vec![BlockItem::Statement(
+                Statement::Return(Expression::Binary {
+                    op: BinaryOperator::Add,
+                    left: Box::new(Expression::Var("a".to_string())),
+                    right: Box::new(Expression::Var("b".to_string())),
+                }),
+                Some(Span { line: 1, column: 1 }),
+            )]),
+        };
+        let ast_node = parse_function_defn_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_function_declaration_without_body() {
+        // int foo(void);
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("foo".to_string()),
+            Token::OpenParenthesis,
+            Token::VoidKeyword,
+            Token::CloseParenthesis,
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = FunctionDefinition::Function {
+            name: "foo".to_string(),
+            params: vec![],
+            body: None,
+        };
+        let ast_node = parse_function_defn_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_program_defn_with_function_declaration_followed_by_definition() {
+        // int foo(int a); int foo(int a) { return a; }
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("foo".to_string()),
+            Token::OpenParenthesis,
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::Semicolon,
+            Token::IntKeyword,
+            Token::Identifier("foo".to_string()),
+            Token::OpenParenthesis,
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                params: vec!["a".to_string()],
+                body: None,
+            },
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                params: vec!["a".to_string()],
+                body: Some(vec![BlockItem::Statement(
+                    Statement::Return(Expression::Var("a".to_string())),
+                    Some(Span { line: 1, column: 1 }),
+                )]),
+            },
+        ]);
+        let ast_node = parse_program_definition(&mut tokens).unwrap();
+        assert_eq!(0, tokens.len());
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_parameter_list_followed_by_neither_body_nor_semicolon() {
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("foo".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::ReturnKeyword,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "function body or `;`".to_string(),
+            found: Token::ReturnKeyword,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_function_defn_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_return_statement_missing_semicolon() {
+        // int main() { return 2 }
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::CloseBrace,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "`;`".to_string(),
+            found: Token::CloseBrace,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_function_body_missing_close_brace() {
+        // int main() { return 2;
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ]);
+        let expected_error = ParseError::UnexpectedEndOfInput {
+            expected: "`}`".to_string(),
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_return_keyword_misspelled() {
+        // int main() { retrun 2; }
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::Identifier("retrun".to_string()),
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "`;`".to_string(),
+            found: Token::NumericConstant(2),
+            line: 1,
+            column: 1,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_input_ends_in_middle_of_return_statement() {
+        // int main() { return
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+        ]);
+        let expected_error = ParseError::UnexpectedEndOfInput {
+            expected: "expression".to_string(),
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_input_ends_after_function_name() {
+        let mut tokens = spanned_tokens([Token::IntKeyword, Token::Identifier("main".to_string())]);
+        let expected_error = ParseError::UnexpectedEndOfInput {
+            expected: "`(`".to_string(),
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn unexpected_token_error_reports_position_of_token() {
+        let mut tokens = VecDeque::from(lex("int main() {\n    return 2\n}").unwrap());
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "`;`".to_string(),
+            found: Token::CloseBrace,
+            line: 3,
+            column: 1,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn display_parse_errors() {
+        let unexpected_token_error = ParseError::UnexpectedToken {
+            expected: "`;`".to_string(),
+            found: Token::CloseBrace,
+            line: 3,
+            column: 1,
+        };
+        let end_of_input_error = ParseError::UnexpectedEndOfInput {
+            expected: "`}`".to_string(),
+        };
+        assert_eq!(
+            unexpected_token_error.to_string(),
+            "3:1: Expected `;`, found `}`"
+        );
+        assert_eq!(
+            end_of_input_error.to_string(),
+            "Unexpected end of input, expected `}`"
+        );
+    }
+
+    #[test]
+    fn error_if_identifiers_after_end_of_program() {
+        let mut tokens = VecDeque::from(lex("int main() { return 2; } blah blah").unwrap());
+        let expected_error = ParseError::TrailingToken {
+            found: Token::Identifier("blah".to_string()),
+            line: 1,
+            column: 26,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_close_brace_after_end_of_program() {
+        let mut tokens = VecDeque::from(lex("int main() { return 2; }\n}").unwrap());
+        let expected_error = ParseError::TrailingToken {
+            found: Token::CloseBrace,
+            line: 2,
+            column: 1,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_partial_function_defn_after_complete_function_defn() {
+        let mut tokens = VecDeque::from(lex("int main() { return 2; } int foo(").unwrap());
+        let expected_error = ParseError::UnexpectedEndOfInput {
+            expected: "parameter list".to_string(),
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_partial_function_defn_without_return_type_after_end_of_program() {
+        let mut tokens = VecDeque::from(lex("int main() { return 2; } foo() {").unwrap());
+        let expected_error = ParseError::TrailingToken {
+            found: Token::Identifier("foo".to_string()),
+            line: 1,
+            column: 26,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn no_error_if_no_tokens_after_end_of_program() {
+        let mut tokens = VecDeque::from(lex("int main() { return 2; }\n").unwrap());
+        let expected_ast_node = ProgramDefinition::Program(vec![FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![BlockItem::Statement(
+                Statement::Return(Expression::NumericConstant(2)),
+                Some(Span {
+                    line: 1,
+                    column: 14,
+                }),
+            )]),
+        }]);
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(0, tokens.len());
+        assert_eq!(res, Ok(expected_ast_node));
+    }
+
+    #[test]
+    fn display_trailing_token_error() {
+        let error = ParseError::TrailingToken {
+            found: Token::Identifier("blah".to_string()),
+            line: 1,
+            column: 26,
+        };
+        assert_eq!(
+            error.to_string(),
+            "1:26: Unexpected token after end of program: `blah`"
+        );
+    }
+
+    #[test]
+    fn every_punctuator_token_is_either_binary_operator_or_not_without_panicking() {
+        let non_operators = [
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::CloseBrace,
+            Token::Semicolon,
+            Token::Tilde,
+            Token::Colon,
+            Token::DoublePlus,
+            Token::DoubleMinus,
+            Token::Exclamation,
+        ];
+        for (_, token) in PUNCTUATORS.iter() {
+            let info = binary_operator_info(token);
+            assert_eq!(info.is_none(), non_operators.contains(token), "{}", token);
+        }
+    }
+
+    #[test]
+    fn keyword_and_operand_tokens_are_not_binary_operators() {
+        let tokens = [
+            Token::IntKeyword,
+            Token::VoidKeyword,
+            Token::ReturnKeyword,
+            Token::ForKeyword,
+            Token::GotoKeyword,
+            Token::Identifier("a".to_string()),
+            Token::NumericConstant(1),
+        ];
+        for token in tokens {
+            assert_eq!(binary_operator_info(&token), None);
+        }
+    }
+
+    #[test]
+    fn expression_ends_at_token_that_is_not_binary_operator() {
+        let non_operators = [
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::CloseBrace,
+            Token::Tilde,
+            Token::Colon,
+            Token::Exclamation,
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+        ];
+        for token in non_operators {
+            // The expression is followed by the token rather than the semicolon ending the return
+            // statement
+            let tokens = spanned_tokens([Token::NumericConstant(1), token.clone()]);
+            let expected_error = ParseError::UnexpectedToken {
+                expected: "`;`".to_string(),
+                found: token,
+                line: 1,
+                column: 1,
+            };
+            let res = parse_expression_in_program(tokens);
+            assert_eq!(res, Err(expected_error));
+        }
+    }
+
+    #[test]
+    fn assignment_and_conditional_operators_are_right_associative() {
+        for token in [Token::Equals, Token::QuestionMark] {
+            let (_, _, associativity) = binary_operator_info(&token).unwrap();
+            assert_eq!(associativity, Associativity::Right);
+        }
+    }
+
+    #[cfg(feature = "ast-json")]
+    #[test]
+    fn serialize_program_defn_to_json() {
+        let mut tokens = VecDeque::from(lex("int main(void) { return ~((1 + 2) * -3); }").unwrap());
+        let ast_node = parse_program_definition(&mut tokens).unwrap();
+        let expected_json = serde_json::json!({
+            "Program": [{
+                "Function": {
+                    "name": "main",
+                    "params": [],
+                    "body": [{
+                        "Statement": [{
+                            "Return": {
+                                "Unary": ["BitwiseComplement", {
+                                    "Binary": {
+                                        "op": "Multiply",
+                                        "left": {
+                                            "Binary": {
+                                                "op": "Add",
+                                                "left": { "NumericConstant": 1 },
+                                                "right": { "NumericConstant": 2 },
+                                            }
+                                        },
+                                        "right": { "NumericConstant": -3 },
+                                    }
+                                }]
+                            }
+                        }, { "line": 1, "column": 18 }]
+                    }]
+                }
+            }]
+        });
+        let json: serde_json::Value = serde_json::from_str(&ast_to_json(&ast_node)).unwrap();
+        assert_eq!(json, expected_json);
+    }
+
+    #[cfg(feature = "ast-json")]
+    #[test]
+    fn serialize_function_declaration_without_body_to_json() {
+        let mut tokens = VecDeque::from(lex("int foo(int a);").unwrap());
+        let ast_node = parse_program_definition(&mut tokens).unwrap();
+        let expected_json = serde_json::json!({
+            "Program": [{
+                "Function": {
+                    "name": "foo",
+                    "params": ["a"],
+                    "body": null,
+                }
+            }]
+        });
+        let json: serde_json::Value = serde_json::from_str(&ast_to_json(&ast_node)).unwrap();
+        assert_eq!(json, expected_json);
+    }
+
+    #[test]
+    fn error_if_parentheses_nested_too_deeply() {
+        let depth = 10_000;
+        let source_code = format!(
+            "int main(void) {{ return {}1{}; }}",
+            "(".repeat(depth),
+            ")".repeat(depth)
+        );
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        let expected_error = ParseError::NestingTooDeep {
+            line: 1,
+            column: 154,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_unary_operators_nested_too_deeply() {
+        let source_code = format!("int main(void) {{ return {}1; }}", "~".repeat(10_000));
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        let res = parse_program_definition(&mut tokens);
+        assert!(matches!(res, Err(ParseError::NestingTooDeep { .. })));
+    }
+
+    #[test]
+    fn error_if_assignments_nested_too_deeply() {
+        let source_code = format!("int main(void) {{ int a; {}1; }}", "a = ".repeat(10_000));
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        let res = parse_program_definition(&mut tokens);
+        assert!(matches!(res, Err(ParseError::NestingTooDeep { .. })));
+    }
+
+    #[test]
+    fn parse_parentheses_nested_up_to_maximum_depth() {
+        let depth = MAX_EXPRESSION_NESTING_DEPTH;
+        let source_code = format!(
+            "int main(void) {{ return {}1{}; }}",
+            "(".repeat(depth),
+            ")".repeat(depth)
+        );
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        let expected_ast_node = ProgramDefinition::Program(vec![FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![BlockItem::Statement(
+                Statement::Return(Expression::NumericConstant(1)),
+                Some(Span {
+                    line: 1,
+                    column: 18,
+                }),
+            )]),
+        }]);
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Ok(expected_ast_node));
+    }
+
+    #[test]
+    fn error_if_chain_of_binary_operators_is_too_long() {
+        let source_code = format!(
+            "int main(void) {{ return 1{}; }}",
+            " + 1".repeat(MAX_EXPRESSION_NESTING_DEPTH)
+        );
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        let res = parse_program_definition(&mut tokens);
+        assert!(matches!(res, Err(ParseError::NestingTooDeep { .. })));
+    }
+
+    #[test]
+    fn parse_chain_of_binary_operators_up_to_maximum_length() {
+        let source_code = format!(
+            "int main(void) {{ return 1{}; }}",
+            " + 1".repeat(MAX_EXPRESSION_NESTING_DEPTH - 1)
+        );
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        assert!(parse_program_definition(&mut tokens).is_ok());
+    }
+
+    #[test]
+    fn error_if_blocks_nested_too_deeply() {
+        let depth = 10_000;
+        let source_code = format!(
+            "int main(void) {{ {}return 1;{} }}",
+            "{".repeat(depth),
+            "}".repeat(depth)
+        );
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        let expected_error = ParseError::StatementNestingTooDeep {
+            line: 1,
+            column: 147,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_if_statements_nested_too_deeply() {
+        let source_code = format!("int main(void) {{ {}return 1; }}", "if (1) ".repeat(10_000));
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        let res = parse_program_definition(&mut tokens);
+        assert!(matches!(
+            res,
+            Err(ParseError::StatementNestingTooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn error_if_labels_nested_too_deeply() {
+        let source_code = format!("int main(void) {{ {}return 1; }}", "a: ".repeat(10_000));
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        let res = parse_program_definition(&mut tokens);
+        assert!(matches!(
+            res,
+            Err(ParseError::StatementNestingTooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_loops_nested_up_to_maximum_depth() {
+        let source_code = format!(
+            "int main(void) {{ {}return 1; }}",
+            "while (1) ".repeat(MAX_STATEMENT_NESTING_DEPTH)
+        );
+        let mut tokens = VecDeque::from(lex(&source_code).unwrap());
+        assert!(parse_program_definition(&mut tokens).is_ok());
+    }
+
+    #[test]
+    fn display_nesting_too_deep_error() {
+        let error = ParseError::NestingTooDeep {
+            line: 1,
+            column: 282,
+        };
+        assert_eq!(error.to_string(), "1:282: Expression nesting too deep");
+    }
+
+    #[test]
+    fn display_statement_nesting_too_deep_error() {
+        let error = ParseError::StatementNestingTooDeep {
+            line: 1,
+            column: 147,
+        };
+        assert_eq!(error.to_string(), "1:147: Statement nesting too deep");
+    }
+
+    #[test]
+    fn parse_largest_int_constant() {
+        let tokens = spanned_tokens([Token::NumericConstant(2147483647)]);
+        let expected_ast_node = Expression::NumericConstant(2147483647);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_constant_larger_than_largest_int() {
+        let mut tokens =
+            VecDeque::from(lex("int main(void) {\n    return 2147483648;\n}").unwrap());
+        let expected_error = ParseError::ConstantOutOfRange {
+            text: "2147483648".to_string(),
+            line: 2,
+            column: 12,
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_constant_too_large_for_int_but_not_for_i64() {
+        let tokens = spanned_tokens([Token::NumericConstant(99999999999999)]);
+        let expected_error = ParseError::ConstantOutOfRange {
+            text: "99999999999999".to_string(),
+            line: 1,
+            column: 1,
+        };
+        let res = parse_expression_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn display_constant_out_of_range_error() {
+        let error = ParseError::ConstantOutOfRange {
+            text: "2147483648".to_string(),
+            line: 2,
+            column: 12,
+        };
+        assert_eq!(
+            error.to_string(),
+            "2:12: Numeric constant is out of range for `int`: 2147483648"
+        );
+    }
+
+    #[test]
+    fn fold_negated_constant_into_negative_constant() {
+        let tokens = spanned_tokens([Token::Minus, Token::NumericConstant(5)]);
+        let expected_ast_node = Expression::NumericConstant(-5);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn fold_negated_parenthesized_constant_into_negative_constant() {
+        let tokens = spanned_tokens([
+            Token::Minus,
+            Token::OpenParenthesis,
+            Token::NumericConstant(5),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::NumericConstant(-5);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_smallest_int_constant() {
+        let tokens = spanned_tokens([Token::Minus, Token::NumericConstant(2147483648)]);
+        let expected_ast_node = Expression::NumericConstant(-2147483648);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_negated_constant_smaller_than_smallest_int() {
+        let tokens = spanned_tokens([Token::Minus, Token::NumericConstant(2147483649)]);
+        let expected_error = ParseError::ConstantOutOfRange {
+            text: "-2147483649".to_string(),
+            line: 1,
+            column: 1,
+        };
+        let res = parse_expression_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn negation_of_smallest_int_constant_is_not_folded() {
+        // -(-2147483648) doesn't fit in an `int`, so is left as a negation
+        let tokens = spanned_tokens([
+            Token::Minus,
+            Token::OpenParenthesis,
+            Token::Minus,
+            Token::NumericConstant(2147483648),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::Unary(
+            UnaryOperator::Negation,
+            Box::new(Expression::NumericConstant(-2147483648)),
+        );
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_comma_operator_expression() {
+        let tokens = spanned_tokens([
+            Token::OpenParenthesis,
+            Token::NumericConstant(1),
+            Token::Comma,
+            Token::NumericConstant(2),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::Comma {
+            left: Box::new(Expression::NumericConstant(1)),
+            right: Box::new(Expression::NumericConstant(2)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_chained_comma_operators_is_left_associative() {
+        // 1, 2, 3 => (1, 2), 3
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::Comma,
+            Token::NumericConstant(2),
+            Token::Comma,
+            Token::NumericConstant(3),
+        ]);
+        let expected_ast_node = Expression::Comma {
+            left: Box::new(Expression::Comma {
+                left: Box::new(Expression::NumericConstant(1)),
+                right: Box::new(Expression::NumericConstant(2)),
+            }),
+            right: Box::new(Expression::NumericConstant(3)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_comma_operator_having_lower_precedence_than_assignment() {
+        // a = 1, 2 => (a = 1), 2
+        let tokens = spanned_tokens([
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::NumericConstant(1),
+            Token::Comma,
+            Token::NumericConstant(2),
+        ]);
+        let expected_ast_node = Expression::Comma {
+            left: Box::new(Expression::Assignment {
+                lhs: Box::new(Expression::Var("a".to_string())),
+                rhs: Box::new(Expression::NumericConstant(1)),
+            }),
+            right: Box::new(Expression::NumericConstant(2)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_declaration_initializer_contains_comma_operator() {
+        let tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::NumericConstant(1),
+            Token::Comma,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "`;`".to_string(),
+            found: Token::Comma,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_declaration_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_function_call_without_arguments() {
+        let tokens = spanned_tokens([
+            Token::Identifier("foo".to_string()),
+            Token::OpenParenthesis,
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::FunctionCall {
+            name: "foo".to_string(),
+            args: vec![],
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_function_call_with_arguments() {
+        // foo(a, 1 + 2)
+        let tokens = spanned_tokens([
+            Token::Identifier("foo".to_string()),
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::Comma,
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::NumericConstant(2),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::FunctionCall {
+            name: "foo".to_string(),
+            args: vec![
+                Expression::Var("a".to_string()),
+                Expression::Binary {
+                    op: BinaryOperator::Add,
+                    left: Box::new(Expression::NumericConstant(1)),
+                    right: Box::new(Expression::NumericConstant(2)),
+                },
+            ],
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_function_call_with_parenthesized_comma_operator_as_argument() {
+        // f((1, 2), 3)
+        let tokens = spanned_tokens([
+            Token::Identifier("f".to_string()),
+            Token::OpenParenthesis,
+            Token::OpenParenthesis,
+            Token::NumericConstant(1),
+            Token::Comma,
+            Token::NumericConstant(2),
+            Token::CloseParenthesis,
+            Token::Comma,
+            Token::NumericConstant(3),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::FunctionCall {
+            name: "f".to_string(),
+            args: vec![
+                Expression::Comma {
+                    left: Box::new(Expression::NumericConstant(1)),
+                    right: Box::new(Expression::NumericConstant(2)),
+                },
+                Expression::NumericConstant(3),
+            ],
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_function_call_arguments_have_trailing_comma() {
+        let tokens = spanned_tokens([
+            Token::Identifier("f".to_string()),
+            Token::OpenParenthesis,
+            Token::NumericConstant(1),
+            Token::Comma,
+            Token::CloseParenthesis,
+        ]);
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "expression".to_string(),
+            found: Token::CloseParenthesis,
+            line: 1,
+            column: 1,
+        };
+        let res = parse_expression_in_program(tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_unary_plus_applied_to_constant() {
+        let tokens = spanned_tokens([Token::Plus, Token::NumericConstant(5)]);
+        let expected_ast_node = Expression::NumericConstant(5);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_unary_plus_applied_to_parenthesized_negation() {
+        // +(-a)
+        let tokens = spanned_tokens([
+            Token::Plus,
+            Token::OpenParenthesis,
+            Token::Minus,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::Unary(
+            UnaryOperator::Negation,
+            Box::new(Expression::Var("a".to_string())),
+        );
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_unary_plus_applied_to_parenthesized_negative_constant() {
+        // +(-5)
+        let tokens = spanned_tokens([
+            Token::Plus,
+            Token::OpenParenthesis,
+            Token::Minus,
+            Token::NumericConstant(5),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::NumericConstant(-5);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_two_unary_plus_operators() {
+        // + +5
+        let tokens = spanned_tokens([Token::Plus, Token::Plus, Token::NumericConstant(5)]);
+        let expected_ast_node = Expression::NumericConstant(5);
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_addition_with_unary_plus_applied_to_right_operand() {
+        // 1 + +2
+        let tokens = spanned_tokens([
+            Token::NumericConstant(1),
+            Token::Plus,
+            Token::Plus,
+            Token::NumericConstant(2),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::Add,
+            left: Box::new(Expression::NumericConstant(1)),
+            right: Box::new(Expression::NumericConstant(2)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_compound_statement_containing_declaration_and_nested_block() {
+        // { int a = 1; { } }
+        let tokens = spanned_tokens([
+            Token::OpenBrace,
+            Token::IntKeyword,
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::OpenBrace,
+            Token::CloseBrace,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = Statement::Compound(vec![
+            BlockItem::Declaration(
+                Declaration::Declaration {
+                    name: "a".to_string(),
+                    init: Some(Expression::NumericConstant(1)),
+                },
+                Some(Span { line: 1, column: 1 }),
+            ),
+            BlockItem::Statement(
+                Statement::Compound(vec![]),
+                Some(Span { line: 1, column: 1 }),
+            ),
+        ]);
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_compound_statement_missing_close_brace() {
+        // int main(void) { { return 1; }
+        let mut tokens = spanned_tokens([
+            Token::IntKeyword,
+            Token::Identifier("main".to_string()),
+            Token::OpenParenthesis,
+            Token::VoidKeyword,
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::OpenBrace,
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_error = ParseError::UnexpectedEndOfInput {
+            expected: "`}`".to_string(),
+        };
+        let res = parse_program_definition(&mut tokens);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_prefix_increment_and_decrement() {
+        let operators = [
+            (Token::DoublePlus, IncrementOperator::Increment),
+            (Token::DoubleMinus, IncrementOperator::Decrement),
+        ];
+        for (token, op) in operators {
+            let tokens = spanned_tokens([token, Token::Identifier("a".to_string())]);
+            let expected_ast_node = Expression::Prefix {
+                op,
+                operand: Box::new(Expression::Var("a".to_string())),
+            };
+            let ast_node = parse_expression_in_program(tokens).unwrap();
+            assert_eq!(ast_node, expected_ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_postfix_increment_and_decrement() {
+        let operators = [
+            (Token::DoublePlus, IncrementOperator::Increment),
+            (Token::DoubleMinus, IncrementOperator::Decrement),
+        ];
+        for (token, op) in operators {
+            let tokens = spanned_tokens([Token::Identifier("a".to_string()), token]);
+            let expected_ast_node = Expression::Postfix {
+                op,
+                operand: Box::new(Expression::Var("a".to_string())),
+            };
+            let ast_node = parse_expression_in_program(tokens).unwrap();
+            assert_eq!(ast_node, expected_ast_node);
+        }
+    }
+
+    #[test]
+    fn postfix_increment_binds_more_tightly_than_negation() {
+        // -a++
+        let tokens = spanned_tokens([
+            Token::Minus,
+            Token::Identifier("a".to_string()),
+            Token::DoublePlus,
+        ]);
+        let expected_ast_node = Expression::Unary(
+            UnaryOperator::Negation,
+            Box::new(Expression::Postfix {
+                op: IncrementOperator::Increment,
+                operand: Box::new(Expression::Var("a".to_string())),
+            }),
+        );
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_postfix_increment_followed_by_binary_operator() {
+        // a++ + 1
+        let tokens = spanned_tokens([
+            Token::Identifier("a".to_string()),
+            Token::DoublePlus,
+            Token::Plus,
+            Token::NumericConstant(1),
+        ]);
+        let expected_ast_node = Expression::Binary {
+            op: BinaryOperator::Add,
+            left: Box::new(Expression::Postfix {
+                op: IncrementOperator::Increment,
+                operand: Box::new(Expression::Var("a".to_string())),
+            }),
+            right: Box::new(Expression::NumericConstant(1)),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_prefix_increment_of_parenthesized_expression() {
+        // ++(a + 1)
+        let tokens = spanned_tokens([
+            Token::DoublePlus,
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::Plus,
+            Token::NumericConstant(1),
+            Token::CloseParenthesis,
+        ]);
+        let expected_ast_node = Expression::Prefix {
+            op: IncrementOperator::Increment,
+            operand: Box::new(Expression::Binary {
+                op: BinaryOperator::Add,
+                left: Box::new(Expression::Var("a".to_string())),
+                right: Box::new(Expression::NumericConstant(1)),
+            }),
+        };
+        let ast_node = parse_expression_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_while_statement() {
+        // while (a) a = a - 1;
+        let tokens = spanned_tokens([
+            Token::WhileKeyword,
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::Equals,
+            Token::Identifier("a".to_string()),
+            Token::Minus,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::While {
+            condition: Expression::Var("a".to_string()),
+            body: Box::new(Statement::Expression(Expression::Assignment {
+                lhs: Box::new(Expression::Var("a".to_string())),
+                rhs: Box::new(Expression::Binary {
+                    op: BinaryOperator::Subtract,
+                    left: Box::new(Expression::Var("a".to_string())),
+                    right: Box::new(Expression::NumericConstant(1)),
+                }),
+            })),
+            label: None,
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_do_while_statement() {
+        // do { } while (1);
+        let tokens = spanned_tokens([
+            Token::DoKeyword,
+            Token::OpenBrace,
+            Token::CloseBrace,
+            Token::WhileKeyword,
+            Token::OpenParenthesis,
+            Token::NumericConstant(1),
+            Token::CloseParenthesis,
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::DoWhile {
+            body: Box::new(Statement::Compound(vec![])),
+            condition: Expression::NumericConstant(1),
+            label: None,
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_do_while_statement_missing_semicolon() {
+        let tokens = lex("int main(void) { do ; while (1) }").unwrap();
+        let span = tokens[tokens.len() - 1].span;
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "`;`".to_string(),
+            found: Token::CloseBrace,
+            line: span.line,
+            column: span.column,
+        };
+        let res = parse_program_definition(&mut VecDeque::from(tokens));
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_break_and_continue_statements() {
+        let statements = [
+            (Token::BreakKeyword, Statement::Break(None)),
+            (Token::ContinueKeyword, Statement::Continue(None)),
+        ];
+        for (token, expected_ast_node) in statements {
+            let tokens = spanned_tokens([token, Token::Semicolon]);
+            let ast_node = parse_statement_in_program(tokens).unwrap();
+            assert_eq!(ast_node, expected_ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_switch_statement_with_case_and_default() {
+        // switch (a) { case 1: return 2; default: ; }
+        let tokens = spanned_tokens([
+            Token::SwitchKeyword,
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::OpenBrace,
+            Token::CaseKeyword,
+            Token::NumericConstant(1),
+            Token::Colon,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+            Token::DefaultKeyword,
+            Token::Colon,
+            Token::Semicolon,
+            Token::CloseBrace,
+        ]);
+        let expected_ast_node = Statement::Switch {
+            condition: Expression::Var("a".to_string()),
+            body: Box::new(Statement::Compound(vec![
+                BlockItem::Statement(
+                    Statement::Case {
+                        value: Expression::NumericConstant(1),
+                        statement: Box::new(Statement::Return(Expression::NumericConstant(2))),
+                        label: None,
+                    },
+                    Some(Span { line: 1, column: 1 }),
+                ),
+                BlockItem::Statement(
+                    Statement::Default {
+                        statement: Box::new(Statement::Null),
+                        label: None,
+                    },
+                    Some(Span { line: 1, column: 1 }),
+                ),
+            ])),
+            label: None,
+            cases: vec![],
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_case_with_conditional_expression_value() {
+        // case a ? 1 : 2: ;
+        let tokens = spanned_tokens([
+            Token::CaseKeyword,
+            Token::Identifier("a".to_string()),
+            Token::QuestionMark,
+            Token::NumericConstant(1),
+            Token::Colon,
+            Token::NumericConstant(2),
+            Token::Colon,
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::Case {
+            value: Expression::Conditional {
+                condition: Box::new(Expression::Var("a".to_string())),
+                then: Box::new(Expression::NumericConstant(1)),
+                otherwise: Box::new(Expression::NumericConstant(2)),
+            },
+            statement: Box::new(Statement::Null),
+            label: None,
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn error_if_default_at_end_of_block() {
+        let tokens = lex("int main(void) { switch (1) { default: } }").unwrap();
+        let span = tokens[tokens.len() - 2].span;
+        let expected_error = ParseError::UnexpectedToken {
+            expected: "statement after label".to_string(),
+            found: Token::CloseBrace,
+            line: span.line,
+            column: span.column,
+        };
+        let res = parse_program_definition(&mut VecDeque::from(tokens));
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn parse_if_statement_without_else() {
+        // if (a) return 1;
+        let tokens = spanned_tokens([
+            Token::IfKeyword,
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::If {
+            condition: Expression::Var("a".to_string()),
+            then: Box::new(Statement::Return(Expression::NumericConstant(1))),
+            otherwise: None,
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn parse_if_statement_with_else() {
+        // if (a) return 1; else return 2;
+        let tokens = spanned_tokens([
+            Token::IfKeyword,
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::ReturnKeyword,
+            Token::NumericConstant(1),
+            Token::Semicolon,
+            Token::ElseKeyword,
+            Token::ReturnKeyword,
+            Token::NumericConstant(2),
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::If {
+            condition: Expression::Var("a".to_string()),
+            then: Box::new(Statement::Return(Expression::NumericConstant(1))),
+            otherwise: Some(Box::new(Statement::Return(Expression::NumericConstant(2)))),
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
+
+    #[test]
+    fn else_belongs_to_innermost_if() {
+        // if (a) if (b) ; else ;
+        let tokens = spanned_tokens([
+            Token::IfKeyword,
+            Token::OpenParenthesis,
+            Token::Identifier("a".to_string()),
+            Token::CloseParenthesis,
+            Token::IfKeyword,
+            Token::OpenParenthesis,
+            Token::Identifier("b".to_string()),
+            Token::CloseParenthesis,
+            Token::Semicolon,
+            Token::ElseKeyword,
+            Token::Semicolon,
+        ]);
+        let expected_ast_node = Statement::If {
+            condition: Expression::Var("a".to_string()),
+            then: Box::new(Statement::If {
+                condition: Expression::Var("b".to_string()),
+                then: Box::new(Statement::Null),
+                otherwise: Some(Box::new(Statement::Null)),
+            }),
+            otherwise: None,
+        };
+        let ast_node = parse_statement_in_program(tokens).unwrap();
+        assert_eq!(ast_node, expected_ast_node);
+    }
 }
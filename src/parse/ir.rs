@@ -1,23 +1,56 @@
+pub mod cfg;
+pub mod optimize;
+pub mod pass_manager;
+pub mod validation;
+
+use std::collections::BTreeMap;
+
+use crate::lex::Span;
 use crate::parse::c;
 
 use crate::parse::Identifier;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum UnaryOperator {
     BitwiseComplement,
     Negation,
+    Not,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
+    Equal,
+    NotEqual,
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
 }
 
 // TODO: Deriving `Clone` for now to avoid issues with needing to use tmp var AST nodes in
 // multiple places, but this should be revisited to see if shared ownership of tmp var AST nodes is
 // better
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum Value {
-    Constant(u8),
+    Constant(i64),
     Var(Identifier),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum Instruction {
     Return(Value),
     Unary {
@@ -25,60 +58,504 @@ pub enum Instruction {
         src: Value,
         dst: Value,
     },
+    Binary {
+        op: BinaryOperator,
+        left: Value,
+        right: Value,
+        dst: Value,
+    },
+    Copy {
+        src: Value,
+        dst: Value,
+    },
+    Jump(Identifier),
+    JumpIfZero {
+        condition: Value,
+        target: Identifier,
+    },
+    JumpIfNotZero {
+        condition: Value,
+        target: Identifier,
+    },
+    Label(Identifier),
+    FunCall {
+        name: Identifier,
+        args: Vec<Value>,
+        dst: Value,
+    },
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum FunctionDefinition {
     Function {
         identifier: String,
         body: Vec<Instruction>,
+        /// The position in the C source code of the block item that each instruction was lowered
+        /// from, keyed by the index of the instruction in the body. Instructions that the compiler
+        /// adds by itself (such as the implicit `return 0` at the end of a function) have no
+        /// position.
+        locations: BTreeMap<usize, Span>,
     },
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub enum ProgramDefinition {
-    Program(FunctionDefinition),
+    Program(Vec<FunctionDefinition>),
+}
+
+/// Serialize the IR to JSON, for inspecting the lowered program outside of the compiler
+#[cfg(feature = "ast-json")]
+pub fn ir_to_json(program: &ProgramDefinition) -> String {
+    serde_json::to_string_pretty(program).expect("IR should always be serializable to JSON")
+}
+
+/// A C construct that passed semantic analysis, but that can't be lowered to IR yet. The C AST
+/// doesn't keep the positions of constructs in the source code, so the location is the name of the
+/// function that the construct is in.
+#[derive(Debug, PartialEq)]
+pub struct LoweringError {
+    pub construct: String,
+    pub location: Identifier,
+}
+
+impl std::fmt::Display for LoweringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "lowering for {} in function `{}` not implemented",
+            self.construct, self.location
+        )
+    }
+}
+
+/// A construct without a lowering to IR, found inside a function body. It's turned into a
+/// [`LoweringError`] once it reaches the function that it's in. This includes loops, `switch`
+/// statements, cases, `break` and `continue` that weren't labeled during semantic analysis, which
+/// would otherwise be lowered to jumps to labels that don't exist.
+#[derive(Debug, PartialEq)]
+struct Unsupported(&'static str);
+
+impl std::fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spelling = match self {
+            UnaryOperator::BitwiseComplement => "~",
+            UnaryOperator::Negation => "-",
+            UnaryOperator::Not => "!",
+        };
+        write!(f, "{}", spelling)
+    }
+}
+
+impl std::fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spelling = match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            BinaryOperator::BitwiseAnd => "&",
+            BinaryOperator::BitwiseOr => "|",
+            BinaryOperator::BitwiseXor => "^",
+            BinaryOperator::ShiftLeft => "<<",
+            BinaryOperator::ShiftRight => ">>",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::LessOrEqual => "<=",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::GreaterOrEqual => ">=",
+        };
+        write!(f, "{}", spelling)
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Constant(val) => write!(f, "{}", val),
+            Value::Var(identifier) => write!(f, "{}", identifier),
+        }
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Return(val) => write!(f, "return {}", val),
+            Instruction::Unary { op, src, dst } => write!(f, "{} = {} {}", dst, op, src),
+            Instruction::Binary {
+                op,
+                left,
+                right,
+                dst,
+            } => write!(f, "{} = {} {} {}", dst, left, op, right),
+            Instruction::Copy { src, dst } => write!(f, "{} = {}", dst, src),
+            Instruction::Jump(target) => write!(f, "jump {}", target),
+            Instruction::JumpIfZero { condition, target } => {
+                write!(f, "jump_if_zero {}, {}", condition, target)
+            }
+            Instruction::JumpIfNotZero { condition, target } => {
+                write!(f, "jump_if_not_zero {}, {}", condition, target)
+            }
+            Instruction::Label(identifier) => write!(f, "{}:", identifier),
+            Instruction::FunCall { name, args, dst } => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{} = {}({})", dst, name, args.join(", "))
+            }
+        }
+    }
+}
+
+/// One instruction per line, with labels unindented so that the jump targets stand out. Whenever
+/// the line in the C source code that the instructions came from changes, the new line is noted
+/// before the next instruction.
+impl std::fmt::Display for FunctionDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FunctionDefinition::Function {
+                identifier,
+                body,
+                locations,
+            } => {
+                writeln!(f, "function {}:", identifier)?;
+                let mut current_line = None;
+                for (index, instruction) in body.iter().enumerate() {
+                    let line = locations.get(&index).map(|span| span.line);
+                    if let Some(line) = line.filter(|line| current_line != Some(*line)) {
+                        writeln!(f, "    # line {}", line)?;
+                    }
+                    current_line = line;
+                    match instruction {
+                        Instruction::Label(_) => writeln!(f, "{}", instruction)?,
+                        _ => writeln!(f, "    {}", instruction)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Pair each instruction in the body of a function with its position in the C source code, for
+/// passes that remove instructions (and so change the indices that the positions are keyed by)
+fn located_instructions(
+    body: Vec<Instruction>,
+    mut locations: BTreeMap<usize, Span>,
+) -> Vec<(Instruction, Option<Span>)> {
+    body.into_iter()
+        .enumerate()
+        .map(|(index, instruction)| (instruction, locations.remove(&index)))
+        .collect()
 }
 
-pub fn parse_unary_operator(node: c::UnaryOperator) -> UnaryOperator {
+/// Split instructions paired with their positions in the C source code back into the body of a
+/// function and the positions keyed by instruction index
+fn split_located_instructions(
+    located: Vec<(Instruction, Option<Span>)>,
+) -> (Vec<Instruction>, BTreeMap<usize, Span>) {
+    let mut locations = BTreeMap::new();
+    let body = located
+        .into_iter()
+        .enumerate()
+        .map(|(index, (instruction, span))| {
+            if let Some(span) = span {
+                locations.insert(index, span);
+            }
+            instruction
+        })
+        .collect();
+    (body, locations)
+}
+
+fn parse_unary_operator(node: c::UnaryOperator) -> UnaryOperator {
     match node {
         c::UnaryOperator::BitwiseComplement => UnaryOperator::BitwiseComplement,
         c::UnaryOperator::Negation => UnaryOperator::Negation,
+        c::UnaryOperator::Not => UnaryOperator::Not,
     }
 }
 
-pub fn parse_value(node: c::Expression) -> Value {
+fn parse_binary_operator(node: c::BinaryOperator) -> BinaryOperator {
     match node {
-        c::Expression::NumericConstant(val) => Value::Constant(val),
-        _ => todo!(),
+        c::BinaryOperator::Add => BinaryOperator::Add,
+        c::BinaryOperator::Subtract => BinaryOperator::Subtract,
+        c::BinaryOperator::Multiply => BinaryOperator::Multiply,
+        c::BinaryOperator::Divide => BinaryOperator::Divide,
+        c::BinaryOperator::Modulo => BinaryOperator::Modulo,
+        c::BinaryOperator::ShiftLeft => BinaryOperator::ShiftLeft,
+        c::BinaryOperator::ShiftRight => BinaryOperator::ShiftRight,
+        c::BinaryOperator::LessThan => BinaryOperator::LessThan,
+        c::BinaryOperator::GreaterThan => BinaryOperator::GreaterThan,
+        c::BinaryOperator::LessOrEqual => BinaryOperator::LessOrEqual,
+        c::BinaryOperator::GreaterOrEqual => BinaryOperator::GreaterOrEqual,
+        c::BinaryOperator::Equal => BinaryOperator::Equal,
+        c::BinaryOperator::NotEqual => BinaryOperator::NotEqual,
+        c::BinaryOperator::BitwiseAnd => BinaryOperator::BitwiseAnd,
+        c::BinaryOperator::BitwiseOr => BinaryOperator::BitwiseOr,
+        c::BinaryOperator::BitwiseXor => BinaryOperator::BitwiseXor,
+        c::BinaryOperator::And | c::BinaryOperator::Or => {
+            panic!("Logical AND and OR aren't represented by a binary instruction in IR")
+        }
     }
 }
 
-pub fn parse_instruction(node: c::Statement) -> Vec<Instruction> {
-    let mut instructions = Vec::new();
-    let mut identifier_count: usize = 0;
+fn parse_value(node: c::Expression) -> Value {
+    match node {
+        c::Expression::NumericConstant(val) => Value::Constant(val),
+        c::Expression::Var(identifier) => Value::Var(identifier),
+        _ => panic!("Only constants and variables can be converted directly to a value"),
+    }
+}
 
+fn recurse_statement(
+    node: c::Statement,
+    instructions: &mut Vec<Instruction>,
+    locations: &mut BTreeMap<usize, Span>,
+    names: &mut NameGenerator,
+) -> Result<(), Unsupported> {
     match node {
         c::Statement::Return(exp) => {
-            let dst = recurse_unary_expression(exp, &mut instructions, &mut identifier_count);
+            let dst = recurse_expression(exp, instructions, names)?;
             instructions.push(Instruction::Return(dst));
         }
+        c::Statement::Expression(exp) => {
+            // The value of an expression statement is unused, but any instructions needed to
+            // compute it are still required
+            _ = recurse_expression(exp, instructions, names)?;
+        }
+        c::Statement::If {
+            condition,
+            then,
+            otherwise,
+        } => {
+            let condition = recurse_expression(condition, instructions, names)?;
+            let else_label = names.fresh_label("if_else");
+            let end_label = names.fresh_label("if_end");
+            match otherwise {
+                Some(otherwise) => {
+                    instructions.push(Instruction::JumpIfZero {
+                        condition,
+                        target: else_label.clone(),
+                    });
+                    recurse_statement(*then, instructions, locations, names)?;
+                    instructions.append(&mut vec![
+                        Instruction::Jump(end_label.clone()),
+                        Instruction::Label(else_label),
+                    ]);
+                    recurse_statement(*otherwise, instructions, locations, names)?;
+                }
+                None => {
+                    instructions.push(Instruction::JumpIfZero {
+                        condition,
+                        target: end_label.clone(),
+                    });
+                    recurse_statement(*then, instructions, locations, names)?;
+                }
+            }
+            instructions.push(Instruction::Label(end_label));
+        }
+        c::Statement::Compound(block_items) => {
+            for block_item in block_items.into_iter() {
+                recurse_block_item(block_item, instructions, locations, names)?;
+            }
+        }
+        c::Statement::While {
+            condition,
+            body,
+            label,
+        } => {
+            let label = label.ok_or(Unsupported("loop without a label"))?;
+            instructions.push(Instruction::Label(continue_label(&label)));
+            let condition = recurse_expression(condition, instructions, names)?;
+            instructions.push(Instruction::JumpIfZero {
+                condition,
+                target: break_label(&label),
+            });
+            recurse_statement(*body, instructions, locations, names)?;
+            instructions.append(&mut vec![
+                Instruction::Jump(continue_label(&label)),
+                Instruction::Label(break_label(&label)),
+            ]);
+        }
+        c::Statement::DoWhile {
+            body,
+            condition,
+            label,
+        } => {
+            let label = label.ok_or(Unsupported("loop without a label"))?;
+            instructions.push(Instruction::Label(start_label(&label)));
+            recurse_statement(*body, instructions, locations, names)?;
+            instructions.push(Instruction::Label(continue_label(&label)));
+            let condition = recurse_expression(condition, instructions, names)?;
+            instructions.append(&mut vec![
+                Instruction::JumpIfNotZero {
+                    condition,
+                    target: start_label(&label),
+                },
+                Instruction::Label(break_label(&label)),
+            ]);
+        }
+        c::Statement::For {
+            init,
+            condition,
+            post,
+            body,
+            label,
+        } => {
+            let label = label.ok_or(Unsupported("loop without a label"))?;
+            match init {
+                c::ForInit::InitDecl(declaration) => {
+                    recurse_declaration(declaration, instructions, names)?
+                }
+                c::ForInit::InitExp(Some(exp)) => _ = recurse_expression(exp, instructions, names)?,
+                c::ForInit::InitExp(None) => (),
+            }
+            instructions.push(Instruction::Label(start_label(&label)));
+            // A missing condition is treated as always being true, so no check is needed
+            if let Some(condition) = condition {
+                let condition = recurse_expression(condition, instructions, names)?;
+                instructions.push(Instruction::JumpIfZero {
+                    condition,
+                    target: break_label(&label),
+                });
+            }
+            recurse_statement(*body, instructions, locations, names)?;
+            instructions.push(Instruction::Label(continue_label(&label)));
+            if let Some(post) = post {
+                _ = recurse_expression(post, instructions, names)?;
+            }
+            instructions.append(&mut vec![
+                Instruction::Jump(start_label(&label)),
+                Instruction::Label(break_label(&label)),
+            ]);
+        }
+        c::Statement::Break(label) => {
+            let label = label.ok_or(Unsupported("`break` without a label"))?;
+            instructions.push(Instruction::Jump(break_label(&label)));
+        }
+        c::Statement::Continue(label) => {
+            let label = label.ok_or(Unsupported("`continue` without a label"))?;
+            instructions.push(Instruction::Jump(continue_label(&label)));
+        }
+        c::Statement::Null => (),
+        c::Statement::Switch {
+            condition,
+            body,
+            label,
+            cases,
+        } => {
+            let label = label.ok_or(Unsupported("`switch` without a label"))?;
+            let condition = recurse_expression(condition, instructions, names)?;
+            // Each case value is compared with the controlling expression in turn, jumping to the
+            // first matching case. If no case matches, execution continues from `default` if there
+            // is one, or skips the body otherwise.
+            let mut default_label = None;
+            for (value, case_label) in cases.into_iter() {
+                match value {
+                    Some(value) => {
+                        let dst = names.fresh_temp();
+                        instructions.append(&mut vec![
+                            Instruction::Binary {
+                                op: BinaryOperator::Equal,
+                                left: condition.clone(),
+                                right: Value::Constant(value),
+                                dst: dst.clone(),
+                            },
+                            Instruction::JumpIfNotZero {
+                                condition: dst,
+                                target: case_label,
+                            },
+                        ]);
+                    }
+                    None => default_label = Some(case_label),
+                }
+            }
+            instructions.push(Instruction::Jump(
+                default_label.unwrap_or_else(|| break_label(&label)),
+            ));
+            // Cases are only labels within the body, so execution falls through from one case
+            // to the next unless there's a `break`
+            recurse_statement(*body, instructions, locations, names)?;
+            instructions.push(Instruction::Label(break_label(&label)));
+        }
+        c::Statement::Case {
+            statement, label, ..
+        }
+        | c::Statement::Default { statement, label } => {
+            let label = label.ok_or(Unsupported("case without a label"))?;
+            instructions.push(Instruction::Label(label));
+            recurse_statement(*statement, instructions, locations, names)?;
+        }
+        c::Statement::Goto(_) => return Err(Unsupported("`goto` statement")),
+        c::Statement::Labeled { .. } => return Err(Unsupported("labeled statement")),
+    }
+    Ok(())
+}
+
+fn recurse_block_item(
+    node: c::BlockItem,
+    instructions: &mut Vec<Instruction>,
+    locations: &mut BTreeMap<usize, Span>,
+    names: &mut NameGenerator,
+) -> Result<(), Unsupported> {
+    let first_instruction = instructions.len();
+    let span = match node {
+        c::BlockItem::Statement(statement, span) => {
+            recurse_statement(statement, instructions, locations, names)?;
+            span
+        }
+        c::BlockItem::Declaration(declaration, span) => {
+            recurse_declaration(declaration, instructions, names)?;
+            span
+        }
+    };
+
+    // Block items nested inside this one (in a compound statement) have already given their
+    // instructions their own, more precise, position
+    if let Some(span) = span {
+        for index in first_instruction..instructions.len() {
+            locations.entry(index).or_insert(span);
+        }
     }
+    Ok(())
+}
 
-    instructions
+fn recurse_declaration(
+    node: c::Declaration,
+    instructions: &mut Vec<Instruction>,
+    names: &mut NameGenerator,
+) -> Result<(), Unsupported> {
+    match node {
+        c::Declaration::Declaration {
+            name,
+            init: Some(exp),
+        } => {
+            let src = recurse_expression(exp, instructions, names)?;
+            instructions.push(Instruction::Copy {
+                src,
+                dst: Value::Var(name),
+            });
+        }
+        // A declaration without an initializer doesn't do anything at runtime, the variable only
+        // gets a stack address when it's first used
+        c::Declaration::Declaration { init: None, .. } => (),
+    }
+    Ok(())
 }
 
-fn recurse_unary_expression(
+fn recurse_expression(
     exp: c::Expression,
     instructions: &mut Vec<Instruction>,
-    id: &mut usize,
-) -> Value {
-    match exp {
-        c::Expression::NumericConstant(_) => parse_value(exp),
+    names: &mut NameGenerator,
+) -> Result<Value, Unsupported> {
+    let value = match exp {
+        c::Expression::NumericConstant(_) | c::Expression::Var(_) => parse_value(exp),
         c::Expression::Unary(unop, boxed_inner_exp) => {
-            let src = recurse_unary_expression(*boxed_inner_exp, instructions, id);
-            let dst = make_temporary(id);
-            *id += 1;
+            let src = recurse_expression(*boxed_inner_exp, instructions, names)?;
+            let dst = names.fresh_temp();
             let unop_ast_node = parse_unary_operator(unop);
             let unop_instruction_ast_node = Instruction::Unary {
                 op: unop_ast_node,
@@ -88,30 +565,263 @@ fn recurse_unary_expression(
             instructions.push(unop_instruction_ast_node);
             dst
         }
-        c::Expression::Binary { .. } => todo!(),
+        c::Expression::Binary {
+            op: c::BinaryOperator::And,
+            left,
+            right,
+        } => {
+            // The right operand is only evaluated if the left operand is non-zero
+            let left = recurse_expression(*left, instructions, names)?;
+            let dst = names.fresh_temp();
+            let false_label = names.fresh_label("and_false");
+            let end_label = names.fresh_label("and_end");
+            instructions.push(Instruction::JumpIfZero {
+                condition: left,
+                target: false_label.clone(),
+            });
+            let right = recurse_expression(*right, instructions, names)?;
+            instructions.append(&mut vec![
+                Instruction::JumpIfZero {
+                    condition: right,
+                    target: false_label.clone(),
+                },
+                Instruction::Copy {
+                    src: Value::Constant(1),
+                    dst: dst.clone(),
+                },
+                Instruction::Jump(end_label.clone()),
+                Instruction::Label(false_label),
+                Instruction::Copy {
+                    src: Value::Constant(0),
+                    dst: dst.clone(),
+                },
+                Instruction::Label(end_label),
+            ]);
+            dst
+        }
+        c::Expression::Binary {
+            op: c::BinaryOperator::Or,
+            left,
+            right,
+        } => {
+            // The right operand is only evaluated if the left operand is zero
+            let left = recurse_expression(*left, instructions, names)?;
+            let dst = names.fresh_temp();
+            let true_label = names.fresh_label("or_true");
+            let end_label = names.fresh_label("or_end");
+            instructions.push(Instruction::JumpIfNotZero {
+                condition: left,
+                target: true_label.clone(),
+            });
+            let right = recurse_expression(*right, instructions, names)?;
+            instructions.append(&mut vec![
+                Instruction::JumpIfNotZero {
+                    condition: right,
+                    target: true_label.clone(),
+                },
+                Instruction::Copy {
+                    src: Value::Constant(0),
+                    dst: dst.clone(),
+                },
+                Instruction::Jump(end_label.clone()),
+                Instruction::Label(true_label),
+                Instruction::Copy {
+                    src: Value::Constant(1),
+                    dst: dst.clone(),
+                },
+                Instruction::Label(end_label),
+            ]);
+            dst
+        }
+        c::Expression::Binary { op, left, right } => {
+            let left = recurse_expression(*left, instructions, names)?;
+            let right = recurse_expression(*right, instructions, names)?;
+            let dst = names.fresh_temp();
+            let binop_ast_node = parse_binary_operator(op);
+            let binop_instruction_ast_node = Instruction::Binary {
+                op: binop_ast_node,
+                left,
+                right,
+                dst: dst.clone(),
+            };
+            instructions.push(binop_instruction_ast_node);
+            dst
+        }
+        c::Expression::Comma { left, right } => {
+            // The value of the left operand is unused, but any instructions needed to compute it
+            // are still required
+            _ = recurse_expression(*left, instructions, names)?;
+            recurse_expression(*right, instructions, names)?
+        }
+        c::Expression::Assignment { lhs, rhs } => {
+            let src = recurse_expression(*rhs, instructions, names)?;
+            let dst = match *lhs {
+                c::Expression::Var(identifier) => Value::Var(identifier),
+                _ => panic!("Lvalue should have been validated during semantic analysis"),
+            };
+            instructions.push(Instruction::Copy {
+                src,
+                dst: dst.clone(),
+            });
+            dst
+        }
+        c::Expression::Conditional {
+            condition,
+            then,
+            otherwise,
+        } => {
+            // Only one of the two branches is evaluated, and both write their value to the same
+            // temporary variable
+            let condition = recurse_expression(*condition, instructions, names)?;
+            let dst = names.fresh_temp();
+            let else_label = names.fresh_label("cond_else");
+            let end_label = names.fresh_label("cond_end");
+            instructions.push(Instruction::JumpIfZero {
+                condition,
+                target: else_label.clone(),
+            });
+            let then = recurse_expression(*then, instructions, names)?;
+            instructions.append(&mut vec![
+                Instruction::Copy {
+                    src: then,
+                    dst: dst.clone(),
+                },
+                Instruction::Jump(end_label.clone()),
+                Instruction::Label(else_label),
+            ]);
+            let otherwise = recurse_expression(*otherwise, instructions, names)?;
+            instructions.append(&mut vec![
+                Instruction::Copy {
+                    src: otherwise,
+                    dst: dst.clone(),
+                },
+                Instruction::Label(end_label),
+            ]);
+            dst
+        }
+        c::Expression::FunctionCall { name, args } => {
+            let args = args
+                .into_iter()
+                .map(|arg| recurse_expression(arg, instructions, names))
+                .collect::<Result<Vec<Value>, Unsupported>>()?;
+            let dst = names.fresh_temp();
+            instructions.push(Instruction::FunCall {
+                name,
+                args,
+                dst: dst.clone(),
+            });
+            dst
+        }
+        c::Expression::CompoundAssignment { .. } => return Err(Unsupported("compound assignment")),
+        c::Expression::Prefix { .. } => return Err(Unsupported("prefix increment/decrement")),
+        c::Expression::Postfix { .. } => return Err(Unsupported("postfix increment/decrement")),
+    };
+    Ok(value)
+}
+
+/// Generates the names of the temporary variables and labels that are introduced when lowering a
+/// function to IR, so that every name is only used once within the function
+struct NameGenerator {
+    temp_count: usize,
+    label_count: usize,
+}
+
+impl NameGenerator {
+    fn new() -> NameGenerator {
+        NameGenerator {
+            temp_count: 0,
+            label_count: 0,
+        }
+    }
+
+    /// Generate an AST node representing a uniquely named temporary variable. Variables declared
+    /// in the C source are renamed to `<name>.<number>` during semantic analysis, and temporary
+    /// variable names never contain a `.`, so they can't clash with each other.
+    fn fresh_temp(&mut self) -> Value {
+        let identifier = format!("tmp{}", self.temp_count);
+        self.temp_count += 1;
+        Value::Var(identifier)
     }
+
+    /// Generate a uniquely named label for a jump target. Every label gets a different number
+    /// regardless of its prefix, so labels with different prefixes can't clash either.
+    fn fresh_label(&mut self, prefix: &str) -> Identifier {
+        let label = format!("{}{}", prefix, self.label_count);
+        self.label_count += 1;
+        label
+    }
+}
+
+/// The label at the start of a loop's body, for loops that don't start by checking their condition
+fn start_label(loop_label: &Identifier) -> Identifier {
+    format!("start_{}", loop_label)
 }
 
-/// Generate an AST node representing a uniquely named temporary variable
-fn make_temporary(id: &usize) -> Value {
-    let identifier = format!("tmp{}", *id);
-    Value::Var(identifier)
+/// The label that a `continue` in a loop jumps to
+fn continue_label(loop_label: &Identifier) -> Identifier {
+    format!("continue_{}", loop_label)
 }
 
-pub fn parse_function_definition(node: c::FunctionDefinition) -> FunctionDefinition {
+/// The label that a `break` in a loop (or `switch`) jumps to
+fn break_label(loop_label: &Identifier) -> Identifier {
+    format!("break_{}", loop_label)
+}
+
+fn parse_function_definition(
+    node: c::FunctionDefinition,
+) -> Result<FunctionDefinition, LoweringError> {
     match node {
-        c::FunctionDefinition::Function { name, body } => FunctionDefinition::Function {
-            identifier: name,
-            body: parse_instruction(body),
-        },
+        c::FunctionDefinition::Function { name, body, .. } => {
+            let mut instructions = Vec::new();
+            let mut locations = BTreeMap::new();
+            let body = body.expect("Function declarations without a body shouldn't be lowered");
+
+            // Temporary variables and labels need to be unique across the whole function, not
+            // just within each block item
+            let mut names = NameGenerator::new();
+            for block_item in body.into_iter() {
+                recurse_block_item(block_item, &mut instructions, &mut locations, &mut names)
+                    .map_err(|Unsupported(construct)| LoweringError {
+                        construct: construct.to_string(),
+                        location: name.clone(),
+                    })?;
+            }
+
+            // Reaching the end of `main` returns 0, and for any other function the return value
+            // is undefined, so returning 0 is also fine there (and avoids execution falling through
+            // into whatever code follows the function)
+            if !matches!(instructions.last(), Some(Instruction::Return(_))) {
+                instructions.push(Instruction::Return(Value::Constant(0)));
+            }
+
+            Ok(FunctionDefinition::Function {
+                identifier: name,
+                body: instructions,
+                locations,
+            })
+        }
     }
 }
 
-pub fn parse_program_definition(node: c::ProgramDefinition) -> ProgramDefinition {
+/// Lower a semantically valid C program to IR. Constructs that don't have a lowering to IR yet are
+/// reported as an error rather than causing a panic.
+pub fn parse_program_definition(
+    node: c::ProgramDefinition,
+) -> Result<ProgramDefinition, LoweringError> {
     match node {
-        c::ProgramDefinition::Program(func_defn) => {
-            ProgramDefinition::Program(parse_function_definition(func_defn))
-        }
+        c::ProgramDefinition::Program(func_defns) => Ok(ProgramDefinition::Program(
+            func_defns
+                .into_iter()
+                // Function declarations without a body don't produce any code
+                .filter(|func_defn| {
+                    matches!(
+                        func_defn,
+                        c::FunctionDefinition::Function { body: Some(_), .. }
+                    )
+                })
+                .map(parse_function_definition)
+                .collect::<Result<Vec<FunctionDefinition>, LoweringError>>()?,
+        )),
     }
 }
 
@@ -119,20 +829,76 @@ pub fn parse_program_definition(node: c::ProgramDefinition) -> ProgramDefinition
 mod tests {
     use super::*;
 
+    /// Lower a program containing only `int main(void) { <body> }` through the public entry point,
+    /// returning the instructions in the body of `main`
+    fn lower_main(body: Vec<c::BlockItem>) -> Result<Vec<Instruction>, LoweringError> {
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(body),
+        };
+        let FunctionDefinition::Function { body, .. } =
+            lower_function_defn_in_program(c_function_defn_ast_node)?;
+        Ok(body)
+    }
+
+    /// Lower a program containing only the given function definition through the public entry
+    /// point
+    fn lower_function_defn_in_program(
+        func_defn: c::FunctionDefinition,
+    ) -> Result<FunctionDefinition, LoweringError> {
+        let c_program_defn_ast_node = c::ProgramDefinition::Program(vec![func_defn]);
+        match parse_program_definition(c_program_defn_ast_node)? {
+            ProgramDefinition::Program(mut func_defns) => Ok(func_defns.remove(0)),
+        }
+    }
+
+    /// Lower `int main(void) { <statement> }`, returning the instructions in the body of `main`
+    fn lower_statement_in_main(statement: c::Statement) -> Vec<Instruction> {
+        lower_main(vec![c::BlockItem::Statement(statement, None)]).unwrap()
+    }
+
+    /// Lower `int main(void) { return <exp>; }`, returning the instructions in the body of `main`
+    fn lower_return_in_main(exp: c::Expression) -> Vec<Instruction> {
+        lower_statement_in_main(c::Statement::Return(exp))
+    }
+
+    /// The IR operator that a C unary operator is lowered to, found by lowering `return <op> 2;`
+    fn lower_unary_operator(op: c::UnaryOperator) -> UnaryOperator {
+        let exp = c::Expression::Unary(op, Box::new(c::Expression::NumericConstant(2)));
+        match lower_return_in_main(exp).remove(0) {
+            Instruction::Unary { op, .. } => op,
+            instruction => panic!("Expected unary instruction, found {:?}", instruction),
+        }
+    }
+
+    /// The IR operator that a C binary operator is lowered to, found by lowering `return 2 <op> 3;`
+    fn lower_binary_operator(op: c::BinaryOperator) -> BinaryOperator {
+        let exp = c::Expression::Binary {
+            op,
+            left: Box::new(c::Expression::NumericConstant(2)),
+            right: Box::new(c::Expression::NumericConstant(3)),
+        };
+        match lower_return_in_main(exp).remove(0) {
+            Instruction::Binary { op, .. } => op,
+            instruction => panic!("Expected binary instruction, found {:?}", instruction),
+        }
+    }
+
     #[test]
     fn parse_c_constant_to_ir_constant() {
         let value = 2;
         let c_ast_node = c::Expression::NumericConstant(value);
-        let expected_ir_ast_node = Value::Constant(value);
-        let ir_ast_node = parse_value(c_ast_node);
-        assert_eq!(ir_ast_node, expected_ir_ast_node);
+        let expected_ir_ast_nodes = vec![Instruction::Return(Value::Constant(value))];
+        let ir_ast_nodes = lower_return_in_main(c_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_ast_nodes);
     }
 
     #[test]
     fn parse_c_complement_operstor_to_ir_unary_operator() {
         let c_ast_node = c::UnaryOperator::BitwiseComplement;
         let expected_ir_ast_node = UnaryOperator::BitwiseComplement;
-        let ir_ast_node = parse_unary_operator(c_ast_node);
+        let ir_ast_node = lower_unary_operator(c_ast_node);
         assert_eq!(ir_ast_node, expected_ir_ast_node);
     }
 
@@ -140,7 +906,7 @@ mod tests {
     fn parse_c_negation_operator_to_ir_unary_operator() {
         let c_ast_node = c::UnaryOperator::Negation;
         let expected_ir_ast_node = UnaryOperator::Negation;
-        let ir_ast_node = parse_unary_operator(c_ast_node);
+        let ir_ast_node = lower_unary_operator(c_ast_node);
         assert_eq!(ir_ast_node, expected_ir_ast_node);
     }
 
@@ -150,7 +916,7 @@ mod tests {
         let c_constant_ast_node = c::Expression::NumericConstant(value);
         let c_statement_ast_node = c::Statement::Return(c_constant_ast_node);
         let expected_ir_ast_nodes = vec![Instruction::Return(Value::Constant(value))];
-        let ir_ast_nodes = parse_instruction(c_statement_ast_node);
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
         assert_eq!(ir_ast_nodes, expected_ir_ast_nodes);
     }
 
@@ -171,7 +937,7 @@ mod tests {
             },
             Instruction::Return(Value::Var(expected_tmp_var_identifier.to_string())),
         ];
-        let ir_ast_nodes = parse_instruction(c_statement_ast_node);
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
         assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
     }
 
@@ -199,7 +965,7 @@ mod tests {
             },
             Instruction::Return(Value::Var("tmp1".to_string())),
         ];
-        let ir_ast_nodes = parse_instruction(c_statement_ast_node);
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
         assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
     }
 
@@ -217,7 +983,8 @@ mod tests {
         let c_statement_ast_node = c::Statement::Return(c_outer_unary_ast_node);
         let c_function_defn_ast_node = c::FunctionDefinition::Function {
             name: function_identifier.to_string(),
-            body: c_statement_ast_node,
+            params: vec![],
+            body: Some(vec![c::BlockItem::Statement(c_statement_ast_node, None)]),
         };
         let ir_instruction_ast_nodes = vec![
             Instruction::Unary {
@@ -235,8 +1002,9 @@ mod tests {
         let expected_ir_ast_node = FunctionDefinition::Function {
             identifier: function_identifier.to_string(),
             body: ir_instruction_ast_nodes,
+            locations: BTreeMap::new(),
         };
-        let ir_ast_node = parse_function_definition(c_function_defn_ast_node);
+        let ir_ast_node = lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
         assert_eq!(ir_ast_node, expected_ir_ast_node);
     }
 
@@ -254,9 +1022,10 @@ mod tests {
         let c_statement_ast_node = c::Statement::Return(c_outer_unary_ast_node);
         let c_function_defn_ast_node = c::FunctionDefinition::Function {
             name: function_identifier.to_string(),
-            body: c_statement_ast_node,
+            params: vec![],
+            body: Some(vec![c::BlockItem::Statement(c_statement_ast_node, None)]),
         };
-        let c_program_defn_ast_node = c::ProgramDefinition::Program(c_function_defn_ast_node);
+        let c_program_defn_ast_node = c::ProgramDefinition::Program(vec![c_function_defn_ast_node]);
         let ir_instruction_ast_nodes = vec![
             Instruction::Unary {
                 op: UnaryOperator::BitwiseComplement,
@@ -273,9 +1042,2141 @@ mod tests {
         let ir_function_defn_ast_node = FunctionDefinition::Function {
             identifier: function_identifier.to_string(),
             body: ir_instruction_ast_nodes,
+            locations: BTreeMap::new(),
+        };
+        let expected_ir_ast_node = ProgramDefinition::Program(vec![ir_function_defn_ast_node]);
+        let ir_ast_node = parse_program_definition(c_program_defn_ast_node).unwrap();
+        assert_eq!(ir_ast_node, expected_ir_ast_node);
+    }
+
+    #[test]
+    fn parse_c_modulo_operator_to_ir_binary_operator() {
+        let c_ast_node = c::BinaryOperator::Modulo;
+        let expected_ir_ast_node = BinaryOperator::Modulo;
+        let ir_ast_node = lower_binary_operator(c_ast_node);
+        assert_eq!(ir_ast_node, expected_ir_ast_node);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_modulo_expression_to_ir_instructions() {
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::Modulo,
+            left: Box::new(c::Expression::NumericConstant(7)),
+            right: Box::new(c::Expression::NumericConstant(3)),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::Modulo,
+                left: Value::Constant(7),
+                right: Value::Constant(3),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp0".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_nested_binary_expressions_to_ir_instructions() {
+        // (1 + 2) * -3
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::Multiply,
+            left: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::Add,
+                left: Box::new(c::Expression::NumericConstant(1)),
+                right: Box::new(c::Expression::NumericConstant(2)),
+            }),
+            right: Box::new(c::Expression::Unary(
+                c::UnaryOperator::Negation,
+                Box::new(c::Expression::NumericConstant(3)),
+            )),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::Add,
+                left: Value::Constant(1),
+                right: Value::Constant(2),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Unary {
+                op: UnaryOperator::Negation,
+                src: Value::Constant(3),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::Multiply,
+                left: Value::Var("tmp0".to_string()),
+                right: Value::Var("tmp1".to_string()),
+                dst: Value::Var("tmp2".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp2".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_c_not_operator_to_ir_unary_operator() {
+        let c_ast_node = c::UnaryOperator::Not;
+        let expected_ir_ast_node = UnaryOperator::Not;
+        let ir_ast_node = lower_unary_operator(c_ast_node);
+        assert_eq!(ir_ast_node, expected_ir_ast_node);
+    }
+
+    #[test]
+    fn parse_c_function_defn_with_multiple_statements_to_ir_instructions_in_order() {
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![
+                c::BlockItem::Statement(
+                    c::Statement::Expression(c::Expression::Unary(
+                        c::UnaryOperator::Negation,
+                        Box::new(c::Expression::NumericConstant(1)),
+                    )),
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::NumericConstant(2)),
+                    None,
+                ),
+            ]),
+        };
+        let expected_ir_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Unary {
+                    op: UnaryOperator::Negation,
+                    src: Value::Constant(1),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Return(Value::Constant(2)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let ir_ast_node = lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(ir_ast_node, expected_ir_ast_node);
+    }
+
+    #[test]
+    fn parse_null_statement_to_no_ir_instructions() {
+        let c_statement_ast_node = c::Statement::Null;
+        // Only the implicit `return 0` at the end of `main` is left
+        let expected_ir_instruction_ast_nodes = vec![Instruction::Return(Value::Constant(0))];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_c_program_defn_with_two_function_defns_to_ir_program_defn() {
+        let c_program_defn_ast_node = c::ProgramDefinition::Program(vec![
+            c::FunctionDefinition::Function {
+                name: "foo".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::NumericConstant(1)),
+                    None,
+                )]),
+            },
+            c::FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::NumericConstant(2)),
+                    None,
+                )]),
+            },
+        ]);
+        let expected_ir_ast_node = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                identifier: "foo".to_string(),
+                body: vec![Instruction::Return(Value::Constant(1))],
+                locations: BTreeMap::new(),
+            },
+            FunctionDefinition::Function {
+                identifier: "main".to_string(),
+                body: vec![Instruction::Return(Value::Constant(2))],
+                locations: BTreeMap::new(),
+            },
+        ]);
+        let ir_ast_node = parse_program_definition(c_program_defn_ast_node).unwrap();
+        assert_eq!(ir_ast_node, expected_ir_ast_node);
+    }
+
+    #[test]
+    fn parse_c_function_defn_with_parameters_to_ir_function_defn() {
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "foo".to_string(),
+            params: vec!["a".to_string(), "b".to_string()],
+            body: Some(vec![c::BlockItem::Statement(
+                c::Statement::Return(c::Expression::NumericConstant(2)),
+                None,
+            )]),
+        };
+        let expected_ir_ast_node = FunctionDefinition::Function {
+            identifier: "foo".to_string(),
+            body: vec![Instruction::Return(Value::Constant(2))],
+            locations: BTreeMap::new(),
+        };
+        let ir_ast_node = lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(ir_ast_node, expected_ir_ast_node);
+    }
+
+    #[test]
+    fn parse_c_program_defn_skips_function_declarations_without_body() {
+        let c_program_defn_ast_node = c::ProgramDefinition::Program(vec![
+            c::FunctionDefinition::Function {
+                name: "foo".to_string(),
+                params: vec![],
+                body: None,
+            },
+            c::FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::NumericConstant(2)),
+                    None,
+                )]),
+            },
+        ]);
+        let expected_ir_ast_node = ProgramDefinition::Program(vec![FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![Instruction::Return(Value::Constant(2))],
+            locations: BTreeMap::new(),
+        }]);
+        let ir_ast_node = parse_program_definition(c_program_defn_ast_node).unwrap();
+        assert_eq!(ir_ast_node, expected_ir_ast_node);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_negative_constant_to_single_ir_instruction() {
+        let c_statement_ast_node = c::Statement::Return(c::Expression::NumericConstant(-5));
+        let expected_ir_ast_nodes = vec![Instruction::Return(Value::Constant(-5))];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_comma_operator_to_ir_instructions() {
+        // return (~1, 2);
+        let c_statement_ast_node = c::Statement::Return(c::Expression::Comma {
+            left: Box::new(c::Expression::Unary(
+                c::UnaryOperator::BitwiseComplement,
+                Box::new(c::Expression::NumericConstant(1)),
+            )),
+            right: Box::new(c::Expression::NumericConstant(2)),
+        });
+        let expected_ir_ast_nodes = vec![
+            Instruction::Unary {
+                op: UnaryOperator::BitwiseComplement,
+                src: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Return(Value::Constant(2)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_ast_nodes);
+    }
+
+    #[test]
+    fn parse_c_function_defn_without_return_statement_to_ir_function_defn_returning_zero() {
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![c::BlockItem::Statement(
+                c::Statement::Expression(c::Expression::Unary(
+                    c::UnaryOperator::Negation,
+                    Box::new(c::Expression::NumericConstant(1)),
+                )),
+                None,
+            )]),
+        };
+        let expected_ir_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Unary {
+                    op: UnaryOperator::Negation,
+                    src: Value::Constant(1),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Return(Value::Constant(0)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let ir_ast_node = lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(ir_ast_node, expected_ir_ast_node);
+    }
+
+    #[test]
+    fn parse_c_function_defn_with_empty_body_to_ir_function_defn_returning_zero() {
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "foo".to_string(),
+            params: vec![],
+            body: Some(vec![]),
         };
-        let expected_ir_ast_node = ProgramDefinition::Program(ir_function_defn_ast_node);
-        let ir_ast_node = parse_program_definition(c_program_defn_ast_node);
+        let expected_ir_ast_node = FunctionDefinition::Function {
+            identifier: "foo".to_string(),
+            body: vec![Instruction::Return(Value::Constant(0))],
+            locations: BTreeMap::new(),
+        };
+        let ir_ast_node = lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
         assert_eq!(ir_ast_node, expected_ir_ast_node);
     }
+
+    #[test]
+    fn parse_c_bitwise_operators_to_ir_binary_operators() {
+        let operators = [
+            (c::BinaryOperator::BitwiseAnd, BinaryOperator::BitwiseAnd),
+            (c::BinaryOperator::BitwiseOr, BinaryOperator::BitwiseOr),
+            (c::BinaryOperator::BitwiseXor, BinaryOperator::BitwiseXor),
+        ];
+        for (c_ast_node, expected_ir_ast_node) in operators {
+            let ir_ast_node = lower_binary_operator(c_ast_node);
+            assert_eq!(ir_ast_node, expected_ir_ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_return_statement_containing_bitwise_xor_expression_to_ir_instructions() {
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::BitwiseXor,
+            left: Box::new(c::Expression::NumericConstant(6)),
+            right: Box::new(c::Expression::NumericConstant(3)),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::BitwiseXor,
+                left: Value::Constant(6),
+                right: Value::Constant(3),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp0".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_bitwise_and_then_or_to_ir_instructions() {
+        // 1 & 2 | 4
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::BitwiseOr,
+            left: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::BitwiseAnd,
+                left: Box::new(c::Expression::NumericConstant(1)),
+                right: Box::new(c::Expression::NumericConstant(2)),
+            }),
+            right: Box::new(c::Expression::NumericConstant(4)),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::BitwiseAnd,
+                left: Value::Constant(1),
+                right: Value::Constant(2),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::BitwiseOr,
+                left: Value::Var("tmp0".to_string()),
+                right: Value::Constant(4),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp1".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_c_shift_operators_to_ir_binary_operators() {
+        let operators = [
+            (c::BinaryOperator::ShiftLeft, BinaryOperator::ShiftLeft),
+            (c::BinaryOperator::ShiftRight, BinaryOperator::ShiftRight),
+        ];
+        for (c_ast_node, expected_ir_ast_node) in operators {
+            let ir_ast_node = lower_binary_operator(c_ast_node);
+            assert_eq!(ir_ast_node, expected_ir_ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_return_statement_containing_shift_expressions_to_ir_instructions() {
+        // 1 << 3, 16 >> 2
+        let cases = [
+            (
+                c::BinaryOperator::ShiftLeft,
+                BinaryOperator::ShiftLeft,
+                1,
+                3,
+            ),
+            (
+                c::BinaryOperator::ShiftRight,
+                BinaryOperator::ShiftRight,
+                16,
+                2,
+            ),
+        ];
+        for (c_op, ir_op, left, right) in cases {
+            let c_binary_ast_node = c::Expression::Binary {
+                op: c_op,
+                left: Box::new(c::Expression::NumericConstant(left)),
+                right: Box::new(c::Expression::NumericConstant(right)),
+            };
+            let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+            let expected_ir_instruction_ast_nodes = vec![
+                Instruction::Binary {
+                    op: ir_op,
+                    left: Value::Constant(left),
+                    right: Value::Constant(right),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp0".to_string())),
+            ];
+            let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+            assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+        }
+    }
+
+    #[test]
+    fn parse_return_statement_containing_chained_shift_left_to_ir_instructions() {
+        // 1 << 2 << 3
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::ShiftLeft,
+            left: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::ShiftLeft,
+                left: Box::new(c::Expression::NumericConstant(1)),
+                right: Box::new(c::Expression::NumericConstant(2)),
+            }),
+            right: Box::new(c::Expression::NumericConstant(3)),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::ShiftLeft,
+                left: Value::Constant(1),
+                right: Value::Constant(2),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::ShiftLeft,
+                left: Value::Var("tmp0".to_string()),
+                right: Value::Constant(3),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp1".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_assignment_expression_statement_to_ir_copy_instruction() {
+        // a.0 = 5;
+        let c_assignment_ast_node = c::Expression::Assignment {
+            lhs: Box::new(c::Expression::Var("a.0".to_string())),
+            rhs: Box::new(c::Expression::NumericConstant(5)),
+        };
+        let c_statement_ast_node = c::Statement::Expression(c_assignment_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Copy {
+                src: Value::Constant(5),
+                dst: Value::Var("a.0".to_string()),
+            },
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_assignment_to_ir_instructions_returning_var() {
+        // return a.0 = b.1 + 1;
+        let c_assignment_ast_node = c::Expression::Assignment {
+            lhs: Box::new(c::Expression::Var("a.0".to_string())),
+            rhs: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::Add,
+                left: Box::new(c::Expression::Var("b.1".to_string())),
+                right: Box::new(c::Expression::NumericConstant(1)),
+            }),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_assignment_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::Add,
+                left: Value::Var("b.1".to_string()),
+                right: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Copy {
+                src: Value::Var("tmp0".to_string()),
+                dst: Value::Var("a.0".to_string()),
+            },
+            Instruction::Return(Value::Var("a.0".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_logical_and_to_short_circuiting_ir_instructions() {
+        // return 1 && 2;
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::And,
+            left: Box::new(c::Expression::NumericConstant(1)),
+            right: Box::new(c::Expression::NumericConstant(2)),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        // Labels are numbered separately from temporary variables, and each label gets its own
+        // number, so the end label is numbered after the false label
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfZero {
+                condition: Value::Constant(1),
+                target: "and_false0".to_string(),
+            },
+            Instruction::JumpIfZero {
+                condition: Value::Constant(2),
+                target: "and_false0".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Jump("and_end1".to_string()),
+            Instruction::Label("and_false0".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(0),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Label("and_end1".to_string()),
+            Instruction::Return(Value::Var("tmp0".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_chained_logical_and_to_ir_instructions_with_distinct_labels(
+    ) {
+        // return a.0 && b.1 && c.2;
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::And,
+            left: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::And,
+                left: Box::new(c::Expression::Var("a.0".to_string())),
+                right: Box::new(c::Expression::Var("b.1".to_string())),
+            }),
+            right: Box::new(c::Expression::Var("c.2".to_string())),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "and_false0".to_string(),
+            },
+            Instruction::JumpIfZero {
+                condition: Value::Var("b.1".to_string()),
+                target: "and_false0".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Jump("and_end1".to_string()),
+            Instruction::Label("and_false0".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(0),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Label("and_end1".to_string()),
+            Instruction::JumpIfZero {
+                condition: Value::Var("tmp0".to_string()),
+                target: "and_false2".to_string(),
+            },
+            Instruction::JumpIfZero {
+                condition: Value::Var("c.2".to_string()),
+                target: "and_false2".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(1),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Jump("and_end3".to_string()),
+            Instruction::Label("and_false2".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(0),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Label("and_end3".to_string()),
+            Instruction::Return(Value::Var("tmp1".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_logical_or_to_short_circuiting_ir_instructions() {
+        // return 1 || 2;
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::Or,
+            left: Box::new(c::Expression::NumericConstant(1)),
+            right: Box::new(c::Expression::NumericConstant(2)),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfNotZero {
+                condition: Value::Constant(1),
+                target: "or_true0".to_string(),
+            },
+            Instruction::JumpIfNotZero {
+                condition: Value::Constant(2),
+                target: "or_true0".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(0),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Jump("or_end1".to_string()),
+            Instruction::Label("or_true0".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Label("or_end1".to_string()),
+            Instruction::Return(Value::Var("tmp0".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_logical_and_then_or_to_ir_instructions() {
+        // return a.0 && b.1 || c.2;
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::Or,
+            left: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::And,
+                left: Box::new(c::Expression::Var("a.0".to_string())),
+                right: Box::new(c::Expression::Var("b.1".to_string())),
+            }),
+            right: Box::new(c::Expression::Var("c.2".to_string())),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "and_false0".to_string(),
+            },
+            Instruction::JumpIfZero {
+                condition: Value::Var("b.1".to_string()),
+                target: "and_false0".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Jump("and_end1".to_string()),
+            Instruction::Label("and_false0".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(0),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Label("and_end1".to_string()),
+            Instruction::JumpIfNotZero {
+                condition: Value::Var("tmp0".to_string()),
+                target: "or_true2".to_string(),
+            },
+            Instruction::JumpIfNotZero {
+                condition: Value::Var("c.2".to_string()),
+                target: "or_true2".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(0),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Jump("or_end3".to_string()),
+            Instruction::Label("or_true2".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(1),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Label("or_end3".to_string()),
+            Instruction::Return(Value::Var("tmp1".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_double_logical_not_to_ir_instructions() {
+        // return !!1;
+        let c_unary_ast_node = c::Expression::Unary(
+            c::UnaryOperator::Not,
+            Box::new(c::Expression::Unary(
+                c::UnaryOperator::Not,
+                Box::new(c::Expression::NumericConstant(1)),
+            )),
+        );
+        let c_statement_ast_node = c::Statement::Return(c_unary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Unary {
+                op: UnaryOperator::Not,
+                src: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Unary {
+                op: UnaryOperator::Not,
+                src: Value::Var("tmp0".to_string()),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp1".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_c_comparison_operators_to_ir_binary_operators() {
+        let operators = [
+            (c::BinaryOperator::Equal, BinaryOperator::Equal),
+            (c::BinaryOperator::NotEqual, BinaryOperator::NotEqual),
+            (c::BinaryOperator::LessThan, BinaryOperator::LessThan),
+            (c::BinaryOperator::LessOrEqual, BinaryOperator::LessOrEqual),
+            (c::BinaryOperator::GreaterThan, BinaryOperator::GreaterThan),
+            (
+                c::BinaryOperator::GreaterOrEqual,
+                BinaryOperator::GreaterOrEqual,
+            ),
+        ];
+        for (c_ast_node, expected_ir_ast_node) in operators {
+            let ir_ast_node = lower_binary_operator(c_ast_node);
+            assert_eq!(ir_ast_node, expected_ir_ast_node);
+        }
+    }
+
+    #[test]
+    fn parse_return_statement_containing_chained_comparisons_to_ir_instructions() {
+        // return 1 < 2 == 1;
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::Equal,
+            left: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::LessThan,
+                left: Box::new(c::Expression::NumericConstant(1)),
+                right: Box::new(c::Expression::NumericConstant(2)),
+            }),
+            right: Box::new(c::Expression::NumericConstant(1)),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::LessThan,
+                left: Value::Constant(1),
+                right: Value::Constant(2),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::Equal,
+                left: Value::Var("tmp0".to_string()),
+                right: Value::Constant(1),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp1".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_conditional_expression_to_ir_instructions() {
+        // return 1 ? 2 : 3;
+        let c_conditional_ast_node = c::Expression::Conditional {
+            condition: Box::new(c::Expression::NumericConstant(1)),
+            then: Box::new(c::Expression::NumericConstant(2)),
+            otherwise: Box::new(c::Expression::NumericConstant(3)),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_conditional_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfZero {
+                condition: Value::Constant(1),
+                target: "cond_else0".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(2),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Jump("cond_end1".to_string()),
+            Instruction::Label("cond_else0".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(3),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Label("cond_end1".to_string()),
+            Instruction::Return(Value::Var("tmp0".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_conditional_expression_nested_in_else_branch() {
+        // return a.0 ? 1 : b.1 ? 2 : 3;
+        let c_conditional_ast_node = c::Expression::Conditional {
+            condition: Box::new(c::Expression::Var("a.0".to_string())),
+            then: Box::new(c::Expression::NumericConstant(1)),
+            otherwise: Box::new(c::Expression::Conditional {
+                condition: Box::new(c::Expression::Var("b.1".to_string())),
+                then: Box::new(c::Expression::NumericConstant(2)),
+                otherwise: Box::new(c::Expression::NumericConstant(3)),
+            }),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_conditional_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "cond_else0".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Jump("cond_end1".to_string()),
+            Instruction::Label("cond_else0".to_string()),
+            Instruction::JumpIfZero {
+                condition: Value::Var("b.1".to_string()),
+                target: "cond_else2".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(2),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Jump("cond_end3".to_string()),
+            Instruction::Label("cond_else2".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(3),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Label("cond_end3".to_string()),
+            Instruction::Copy {
+                src: Value::Var("tmp1".to_string()),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Label("cond_end1".to_string()),
+            Instruction::Return(Value::Var("tmp0".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_if_statement_without_else_to_ir_instructions() {
+        // if (a.0) return 1;
+        let c_statement_ast_node = c::Statement::If {
+            condition: c::Expression::Var("a.0".to_string()),
+            then: Box::new(c::Statement::Return(c::Expression::NumericConstant(1))),
+            otherwise: None,
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "if_end1".to_string(),
+            },
+            Instruction::Return(Value::Constant(1)),
+            Instruction::Label("if_end1".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_if_else_statement_to_ir_instructions() {
+        // if (a.0) return 1; else return 2;
+        let c_statement_ast_node = c::Statement::If {
+            condition: c::Expression::Var("a.0".to_string()),
+            then: Box::new(c::Statement::Return(c::Expression::NumericConstant(1))),
+            otherwise: Some(Box::new(c::Statement::Return(
+                c::Expression::NumericConstant(2),
+            ))),
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "if_else0".to_string(),
+            },
+            Instruction::Return(Value::Constant(1)),
+            Instruction::Jump("if_end1".to_string()),
+            Instruction::Label("if_else0".to_string()),
+            Instruction::Return(Value::Constant(2)),
+            Instruction::Label("if_end1".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_if_statement_nested_in_else_block_to_ir_instructions() {
+        // if (a.0) return 1; else { if (b.1) return 2; }
+        let c_statement_ast_node = c::Statement::If {
+            condition: c::Expression::Var("a.0".to_string()),
+            then: Box::new(c::Statement::Return(c::Expression::NumericConstant(1))),
+            otherwise: Some(Box::new(c::Statement::Compound(vec![
+                c::BlockItem::Statement(
+                    c::Statement::If {
+                        condition: c::Expression::Var("b.1".to_string()),
+                        then: Box::new(c::Statement::Return(c::Expression::NumericConstant(2))),
+                        otherwise: None,
+                    },
+                    None,
+                ),
+            ]))),
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::JumpIfZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "if_else0".to_string(),
+            },
+            Instruction::Return(Value::Constant(1)),
+            Instruction::Jump("if_end1".to_string()),
+            Instruction::Label("if_else0".to_string()),
+            Instruction::JumpIfZero {
+                condition: Value::Var("b.1".to_string()),
+                target: "if_end3".to_string(),
+            },
+            Instruction::Return(Value::Constant(2)),
+            Instruction::Label("if_end3".to_string()),
+            Instruction::Label("if_end1".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_while_statement_to_ir_instructions() {
+        // while (a.0) a.0 = a.0 - 1;
+        let c_statement_ast_node = c::Statement::While {
+            condition: c::Expression::Var("a.0".to_string()),
+            body: Box::new(c::Statement::Expression(c::Expression::Assignment {
+                lhs: Box::new(c::Expression::Var("a.0".to_string())),
+                rhs: Box::new(c::Expression::Binary {
+                    op: c::BinaryOperator::Subtract,
+                    left: Box::new(c::Expression::Var("a.0".to_string())),
+                    right: Box::new(c::Expression::NumericConstant(1)),
+                }),
+            })),
+            label: Some("loop.0".to_string()),
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Label("continue_loop.0".to_string()),
+            Instruction::JumpIfZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "break_loop.0".to_string(),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::Subtract,
+                left: Value::Var("a.0".to_string()),
+                right: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Copy {
+                src: Value::Var("tmp0".to_string()),
+                dst: Value::Var("a.0".to_string()),
+            },
+            Instruction::Jump("continue_loop.0".to_string()),
+            Instruction::Label("break_loop.0".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_do_while_statement_to_ir_instructions() {
+        // do return 1; while (a.0);
+        let c_statement_ast_node = c::Statement::DoWhile {
+            body: Box::new(c::Statement::Return(c::Expression::NumericConstant(1))),
+            condition: c::Expression::Var("a.0".to_string()),
+            label: Some("loop.0".to_string()),
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Label("start_loop.0".to_string()),
+            Instruction::Return(Value::Constant(1)),
+            Instruction::Label("continue_loop.0".to_string()),
+            Instruction::JumpIfNotZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "start_loop.0".to_string(),
+            },
+            Instruction::Label("break_loop.0".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_for_statement_to_ir_instructions() {
+        // for (a.0 = 0; a.0 < 3; a.0 = a.0 + 1) ;
+        let increment_ast_node = c::Expression::Assignment {
+            lhs: Box::new(c::Expression::Var("a.0".to_string())),
+            rhs: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::Add,
+                left: Box::new(c::Expression::Var("a.0".to_string())),
+                right: Box::new(c::Expression::NumericConstant(1)),
+            }),
+        };
+        let c_statement_ast_node = c::Statement::For {
+            init: c::ForInit::InitExp(Some(c::Expression::Assignment {
+                lhs: Box::new(c::Expression::Var("a.0".to_string())),
+                rhs: Box::new(c::Expression::NumericConstant(0)),
+            })),
+            condition: Some(c::Expression::Binary {
+                op: c::BinaryOperator::LessThan,
+                left: Box::new(c::Expression::Var("a.0".to_string())),
+                right: Box::new(c::Expression::NumericConstant(3)),
+            }),
+            post: Some(increment_ast_node),
+            body: Box::new(c::Statement::Null),
+            label: Some("loop.0".to_string()),
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Copy {
+                src: Value::Constant(0),
+                dst: Value::Var("a.0".to_string()),
+            },
+            Instruction::Label("start_loop.0".to_string()),
+            Instruction::Binary {
+                op: BinaryOperator::LessThan,
+                left: Value::Var("a.0".to_string()),
+                right: Value::Constant(3),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::JumpIfZero {
+                condition: Value::Var("tmp0".to_string()),
+                target: "break_loop.0".to_string(),
+            },
+            Instruction::Label("continue_loop.0".to_string()),
+            Instruction::Binary {
+                op: BinaryOperator::Add,
+                left: Value::Var("a.0".to_string()),
+                right: Value::Constant(1),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Copy {
+                src: Value::Var("tmp1".to_string()),
+                dst: Value::Var("a.0".to_string()),
+            },
+            Instruction::Jump("start_loop.0".to_string()),
+            Instruction::Label("break_loop.0".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_for_statement_without_condition_to_ir_instructions_without_condition_check() {
+        // for (;;) return 1;
+        let c_statement_ast_node = c::Statement::For {
+            init: c::ForInit::InitExp(None),
+            condition: None,
+            post: None,
+            body: Box::new(c::Statement::Return(c::Expression::NumericConstant(1))),
+            label: Some("loop.0".to_string()),
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Label("start_loop.0".to_string()),
+            Instruction::Return(Value::Constant(1)),
+            Instruction::Label("continue_loop.0".to_string()),
+            Instruction::Jump("start_loop.0".to_string()),
+            Instruction::Label("break_loop.0".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_break_and_continue_statements_to_jumps_to_loop_labels() {
+        // while (1) { continue; break; }
+        let c_statement_ast_node = c::Statement::While {
+            condition: c::Expression::NumericConstant(1),
+            body: Box::new(c::Statement::Compound(vec![
+                c::BlockItem::Statement(c::Statement::Continue(Some("loop.0".to_string())), None),
+                c::BlockItem::Statement(c::Statement::Break(Some("loop.0".to_string())), None),
+            ])),
+            label: Some("loop.0".to_string()),
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Label("continue_loop.0".to_string()),
+            Instruction::JumpIfZero {
+                condition: Value::Constant(1),
+                target: "break_loop.0".to_string(),
+            },
+            Instruction::Jump("continue_loop.0".to_string()),
+            Instruction::Jump("break_loop.0".to_string()),
+            Instruction::Jump("continue_loop.0".to_string()),
+            Instruction::Label("break_loop.0".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_c_var_to_ir_var() {
+        let c_ast_node = c::Expression::Var("a.0".to_string());
+        let expected_ir_ast_nodes = vec![Instruction::Return(Value::Var("a.0".to_string()))];
+        let ir_ast_nodes = lower_return_in_main(c_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_ast_nodes);
+    }
+
+    #[test]
+    fn parse_declaration_with_initializer_to_ir_copy_instruction() {
+        // int a.0 = 1 + 2;
+        let c_block_item_ast_node = c::BlockItem::Declaration(
+            c::Declaration::Declaration {
+                name: "a.0".to_string(),
+                init: Some(c::Expression::Binary {
+                    op: c::BinaryOperator::Add,
+                    left: Box::new(c::Expression::NumericConstant(1)),
+                    right: Box::new(c::Expression::NumericConstant(2)),
+                }),
+            },
+            None,
+        );
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::Add,
+                left: Value::Constant(1),
+                right: Value::Constant(2),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Copy {
+                src: Value::Var("tmp0".to_string()),
+                dst: Value::Var("a.0".to_string()),
+            },
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_main(vec![c_block_item_ast_node]).unwrap();
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_declaration_without_initializer_to_no_ir_instructions() {
+        // int a.0;
+        let c_block_item_ast_node = c::BlockItem::Declaration(
+            c::Declaration::Declaration {
+                name: "a.0".to_string(),
+                init: None,
+            },
+            None,
+        );
+        let expected_ir_instruction_ast_nodes = vec![Instruction::Return(Value::Constant(0))];
+        let ir_ast_nodes = lower_main(vec![c_block_item_ast_node]).unwrap();
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_c_function_defn_using_declared_variable_in_return_to_ir_instructions() {
+        // int main(void) { int a.0 = 5; return a.0; }
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![
+                c::BlockItem::Declaration(
+                    c::Declaration::Declaration {
+                        name: "a.0".to_string(),
+                        init: Some(c::Expression::NumericConstant(5)),
+                    },
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::Var("a.0".to_string())),
+                    None,
+                ),
+            ]),
+        };
+        let expected_ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Copy {
+                    src: Value::Constant(5),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Return(Value::Var("a.0".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let ir_function_defn_ast_node =
+            lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(
+            ir_function_defn_ast_node,
+            expected_ir_function_defn_ast_node
+        );
+    }
+
+    #[test]
+    fn parse_chained_assignment_to_ir_copy_instructions() {
+        // a.0 = b.1 = 5;
+        let c_assignment_ast_node = c::Expression::Assignment {
+            lhs: Box::new(c::Expression::Var("a.0".to_string())),
+            rhs: Box::new(c::Expression::Assignment {
+                lhs: Box::new(c::Expression::Var("b.1".to_string())),
+                rhs: Box::new(c::Expression::NumericConstant(5)),
+            }),
+        };
+        let c_statement_ast_node = c::Statement::Expression(c_assignment_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Copy {
+                src: Value::Constant(5),
+                dst: Value::Var("b.1".to_string()),
+            },
+            Instruction::Copy {
+                src: Value::Var("b.1".to_string()),
+                dst: Value::Var("a.0".to_string()),
+            },
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn temporary_variables_are_unique_across_statements_in_function_defn() {
+        // int main(void) { 1 + 2; 3 + 4; }
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![
+                c::BlockItem::Statement(
+                    c::Statement::Expression(c::Expression::Binary {
+                        op: c::BinaryOperator::Add,
+                        left: Box::new(c::Expression::NumericConstant(1)),
+                        right: Box::new(c::Expression::NumericConstant(2)),
+                    }),
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Expression(c::Expression::Binary {
+                        op: c::BinaryOperator::Add,
+                        left: Box::new(c::Expression::NumericConstant(3)),
+                        right: Box::new(c::Expression::NumericConstant(4)),
+                    }),
+                    None,
+                ),
+            ]),
+        };
+        let expected_ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Constant(1),
+                    right: Value::Constant(2),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Constant(3),
+                    right: Value::Constant(4),
+                    dst: Value::Var("tmp1".to_string()),
+                },
+                Instruction::Return(Value::Constant(0)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let ir_function_defn_ast_node =
+            lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(
+            ir_function_defn_ast_node,
+            expected_ir_function_defn_ast_node
+        );
+    }
+
+    #[test]
+    fn parse_c_function_defn_with_declaration_expression_and_return_to_ir_instructions_in_order() {
+        // int main(void) { int a.0 = 1; a.0 = a.0 + 2; return a.0; }
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![
+                c::BlockItem::Declaration(
+                    c::Declaration::Declaration {
+                        name: "a.0".to_string(),
+                        init: Some(c::Expression::NumericConstant(1)),
+                    },
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Expression(c::Expression::Assignment {
+                        lhs: Box::new(c::Expression::Var("a.0".to_string())),
+                        rhs: Box::new(c::Expression::Binary {
+                            op: c::BinaryOperator::Add,
+                            left: Box::new(c::Expression::Var("a.0".to_string())),
+                            right: Box::new(c::Expression::NumericConstant(2)),
+                        }),
+                    }),
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::Var("a.0".to_string())),
+                    None,
+                ),
+            ]),
+        };
+        let expected_ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Copy {
+                    src: Value::Constant(1),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Var("a.0".to_string()),
+                    right: Value::Constant(2),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Copy {
+                    src: Value::Var("tmp0".to_string()),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Return(Value::Var("a.0".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let ir_function_defn_ast_node =
+            lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(
+            ir_function_defn_ast_node,
+            expected_ir_function_defn_ast_node
+        );
+    }
+
+    #[test]
+    fn parse_c_function_defn_with_nested_block_to_ir_instructions_sharing_temporary_numbering() {
+        // int main(void) { 1 + 2; { int a.0 = 3 * 4; } return 5 - 6; }
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![
+                c::BlockItem::Statement(
+                    c::Statement::Expression(c::Expression::Binary {
+                        op: c::BinaryOperator::Add,
+                        left: Box::new(c::Expression::NumericConstant(1)),
+                        right: Box::new(c::Expression::NumericConstant(2)),
+                    }),
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Compound(vec![c::BlockItem::Declaration(
+                        c::Declaration::Declaration {
+                            name: "a.0".to_string(),
+                            init: Some(c::Expression::Binary {
+                                op: c::BinaryOperator::Multiply,
+                                left: Box::new(c::Expression::NumericConstant(3)),
+                                right: Box::new(c::Expression::NumericConstant(4)),
+                            }),
+                        },
+                        None,
+                    )]),
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::Binary {
+                        op: c::BinaryOperator::Subtract,
+                        left: Box::new(c::Expression::NumericConstant(5)),
+                        right: Box::new(c::Expression::NumericConstant(6)),
+                    }),
+                    None,
+                ),
+            ]),
+        };
+        let expected_ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Constant(1),
+                    right: Value::Constant(2),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: Value::Constant(3),
+                    right: Value::Constant(4),
+                    dst: Value::Var("tmp1".to_string()),
+                },
+                Instruction::Copy {
+                    src: Value::Var("tmp1".to_string()),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Subtract,
+                    left: Value::Constant(5),
+                    right: Value::Constant(6),
+                    dst: Value::Var("tmp2".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp2".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let ir_function_defn_ast_node =
+            lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(
+            ir_function_defn_ast_node,
+            expected_ir_function_defn_ast_node
+        );
+    }
+
+    #[test]
+    fn parse_return_statement_containing_function_call_without_args_to_ir_instructions() {
+        // return foo();
+        let c_function_call_ast_node = c::Expression::FunctionCall {
+            name: "foo".to_string(),
+            args: vec![],
+        };
+        let c_statement_ast_node = c::Statement::Return(c_function_call_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::FunCall {
+                name: "foo".to_string(),
+                args: vec![],
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp0".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_function_call_with_computed_args_to_ir_instructions_evaluating_args_in_order() {
+        // foo(1 + 2, a.0);
+        let c_function_call_ast_node = c::Expression::FunctionCall {
+            name: "foo".to_string(),
+            args: vec![
+                c::Expression::Binary {
+                    op: c::BinaryOperator::Add,
+                    left: Box::new(c::Expression::NumericConstant(1)),
+                    right: Box::new(c::Expression::NumericConstant(2)),
+                },
+                c::Expression::Var("a.0".to_string()),
+            ],
+        };
+        let c_statement_ast_node = c::Statement::Expression(c_function_call_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::Add,
+                left: Value::Constant(1),
+                right: Value::Constant(2),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::FunCall {
+                name: "foo".to_string(),
+                args: vec![
+                    Value::Var("tmp0".to_string()),
+                    Value::Var("a.0".to_string()),
+                ],
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_return_statement_containing_function_call_in_binary_expression_to_ir_instructions() {
+        // return 1 + foo(2);
+        let c_binary_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::Add,
+            left: Box::new(c::Expression::NumericConstant(1)),
+            right: Box::new(c::Expression::FunctionCall {
+                name: "foo".to_string(),
+                args: vec![c::Expression::NumericConstant(2)],
+            }),
+        };
+        let c_statement_ast_node = c::Statement::Return(c_binary_ast_node);
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::FunCall {
+                name: "foo".to_string(),
+                args: vec![Value::Constant(2)],
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::Binary {
+                op: BinaryOperator::Add,
+                left: Value::Constant(1),
+                right: Value::Var("tmp0".to_string()),
+                dst: Value::Var("tmp1".to_string()),
+            },
+            Instruction::Return(Value::Var("tmp1".to_string())),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn temporary_variable_and_label_numbering_starts_from_zero_in_each_function_defn() {
+        // int foo(void) { return 1 && 2; } int main(void) { return 3 && 4; }
+        let c_program_defn_ast_node = c::ProgramDefinition::Program(vec![
+            c::FunctionDefinition::Function {
+                name: "foo".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::Binary {
+                        op: c::BinaryOperator::And,
+                        left: Box::new(c::Expression::NumericConstant(1)),
+                        right: Box::new(c::Expression::NumericConstant(2)),
+                    }),
+                    None,
+                )]),
+            },
+            c::FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::Binary {
+                        op: c::BinaryOperator::And,
+                        left: Box::new(c::Expression::NumericConstant(3)),
+                        right: Box::new(c::Expression::NumericConstant(4)),
+                    }),
+                    None,
+                )]),
+            },
+        ]);
+        let expected_ir_program_defn_ast_node = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                identifier: "foo".to_string(),
+                body: vec![
+                    Instruction::JumpIfZero {
+                        condition: Value::Constant(1),
+                        target: "and_false0".to_string(),
+                    },
+                    Instruction::JumpIfZero {
+                        condition: Value::Constant(2),
+                        target: "and_false0".to_string(),
+                    },
+                    Instruction::Copy {
+                        src: Value::Constant(1),
+                        dst: Value::Var("tmp0".to_string()),
+                    },
+                    Instruction::Jump("and_end1".to_string()),
+                    Instruction::Label("and_false0".to_string()),
+                    Instruction::Copy {
+                        src: Value::Constant(0),
+                        dst: Value::Var("tmp0".to_string()),
+                    },
+                    Instruction::Label("and_end1".to_string()),
+                    Instruction::Return(Value::Var("tmp0".to_string())),
+                ],
+                locations: BTreeMap::new(),
+            },
+            FunctionDefinition::Function {
+                identifier: "main".to_string(),
+                body: vec![
+                    Instruction::JumpIfZero {
+                        condition: Value::Constant(3),
+                        target: "and_false0".to_string(),
+                    },
+                    Instruction::JumpIfZero {
+                        condition: Value::Constant(4),
+                        target: "and_false0".to_string(),
+                    },
+                    Instruction::Copy {
+                        src: Value::Constant(1),
+                        dst: Value::Var("tmp0".to_string()),
+                    },
+                    Instruction::Jump("and_end1".to_string()),
+                    Instruction::Label("and_false0".to_string()),
+                    Instruction::Copy {
+                        src: Value::Constant(0),
+                        dst: Value::Var("tmp0".to_string()),
+                    },
+                    Instruction::Label("and_end1".to_string()),
+                    Instruction::Return(Value::Var("tmp0".to_string())),
+                ],
+                locations: BTreeMap::new(),
+            },
+        ]);
+        let ir_program_defn_ast_node = parse_program_definition(c_program_defn_ast_node).unwrap();
+        assert_eq!(ir_program_defn_ast_node, expected_ir_program_defn_ast_node);
+    }
+
+    #[test]
+    fn print_function_defn_with_unary_binary_and_return_instructions() {
+        let ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Constant(2),
+                    right: Value::Constant(3),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Unary {
+                    op: UnaryOperator::Negation,
+                    src: Value::Var("tmp0".to_string()),
+                    dst: Value::Var("tmp1".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp1".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let expected_output = "\
+function main:
+    tmp0 = 2 + 3
+    tmp1 = - tmp0
+    return tmp1
+";
+        assert_eq!(ir_function_defn_ast_node.to_string(), expected_output);
+    }
+
+    #[test]
+    fn print_function_defn_with_jumps_labels_copies_and_function_calls() {
+        let ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::JumpIfZero {
+                    condition: Value::Var("a.0".to_string()),
+                    target: "and_false0".to_string(),
+                },
+                Instruction::JumpIfNotZero {
+                    condition: Value::Constant(1),
+                    target: "or_true1".to_string(),
+                },
+                Instruction::Jump("and_end0".to_string()),
+                Instruction::Label("and_false0".to_string()),
+                Instruction::Copy {
+                    src: Value::Constant(0),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::FunCall {
+                    name: "foo".to_string(),
+                    args: vec![Value::Constant(1), Value::Var("tmp0".to_string())],
+                    dst: Value::Var("tmp1".to_string()),
+                },
+            ],
+            locations: BTreeMap::new(),
+        };
+        let expected_output = "\
+function main:
+    jump_if_zero a.0, and_false0
+    jump_if_not_zero 1, or_true1
+    jump and_end0
+and_false0:
+    tmp0 = 0
+    tmp1 = foo(1, tmp0)
+";
+        assert_eq!(ir_function_defn_ast_node.to_string(), expected_output);
+    }
+
+    #[test]
+    fn print_line_of_c_source_code_before_first_instruction_from_it() {
+        let ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Copy {
+                    src: Value::Constant(1),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Var("a.0".to_string()),
+                    right: Value::Constant(2),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp0".to_string())),
+                Instruction::Return(Value::Constant(0)),
+            ],
+            locations: BTreeMap::from([
+                (0, Span { line: 2, column: 5 }),
+                (1, Span { line: 3, column: 5 }),
+                (2, Span { line: 3, column: 5 }),
+            ]),
+        };
+        let expected_output = "\
+function main:
+    # line 2
+    a.0 = 1
+    # line 3
+    tmp0 = a.0 + 2
+    return tmp0
+    return 0
+";
+        assert_eq!(ir_function_defn_ast_node.to_string(), expected_output);
+    }
+
+    #[test]
+    fn tag_instructions_with_line_of_block_item_they_were_lowered_from() {
+        // int main(void) {
+        //     int a.0 = 1;
+        //     a.0 = a.0 + 2;
+        // }
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![
+                c::BlockItem::Declaration(
+                    c::Declaration::Declaration {
+                        name: "a.0".to_string(),
+                        init: Some(c::Expression::NumericConstant(1)),
+                    },
+                    Some(Span { line: 2, column: 5 }),
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Expression(c::Expression::Assignment {
+                        lhs: Box::new(c::Expression::Var("a.0".to_string())),
+                        rhs: Box::new(c::Expression::Binary {
+                            op: c::BinaryOperator::Add,
+                            left: Box::new(c::Expression::Var("a.0".to_string())),
+                            right: Box::new(c::Expression::NumericConstant(2)),
+                        }),
+                    }),
+                    Some(Span { line: 3, column: 5 }),
+                ),
+            ]),
+        };
+        let expected_ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Copy {
+                    src: Value::Constant(1),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Var("a.0".to_string()),
+                    right: Value::Constant(2),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Copy {
+                    src: Value::Var("tmp0".to_string()),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                // The implicit `return 0` isn't from any line of the C source code
+                Instruction::Return(Value::Constant(0)),
+            ],
+            locations: BTreeMap::from([
+                (0, Span { line: 2, column: 5 }),
+                (1, Span { line: 3, column: 5 }),
+                (2, Span { line: 3, column: 5 }),
+            ]),
+        };
+        let ir_function_defn_ast_node =
+            lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(
+            ir_function_defn_ast_node,
+            expected_ir_function_defn_ast_node
+        );
+    }
+
+    #[test]
+    fn tag_instructions_in_nested_block_with_line_of_nested_block_item() {
+        // int main(void) {
+        //     if (a.0) {
+        //         return 1;
+        //     }
+        //     return 2;
+        // }
+        let c_function_defn_ast_node = c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![
+                c::BlockItem::Statement(
+                    c::Statement::If {
+                        condition: c::Expression::Var("a.0".to_string()),
+                        then: Box::new(c::Statement::Compound(vec![c::BlockItem::Statement(
+                            c::Statement::Return(c::Expression::NumericConstant(1)),
+                            Some(Span { line: 3, column: 9 }),
+                        )])),
+                        otherwise: None,
+                    },
+                    Some(Span { line: 2, column: 5 }),
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::NumericConstant(2)),
+                    Some(Span { line: 5, column: 5 }),
+                ),
+            ]),
+        };
+        let expected_ir_function_defn_ast_node = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::JumpIfZero {
+                    condition: Value::Var("a.0".to_string()),
+                    target: "if_end1".to_string(),
+                },
+                Instruction::Return(Value::Constant(1)),
+                Instruction::Label("if_end1".to_string()),
+                Instruction::Return(Value::Constant(2)),
+            ],
+            locations: BTreeMap::from([
+                (0, Span { line: 2, column: 5 }),
+                (1, Span { line: 3, column: 9 }),
+                (2, Span { line: 2, column: 5 }),
+                (3, Span { line: 5, column: 5 }),
+            ]),
+        };
+        let ir_function_defn_ast_node =
+            lower_function_defn_in_program(c_function_defn_ast_node).unwrap();
+        assert_eq!(
+            ir_function_defn_ast_node,
+            expected_ir_function_defn_ast_node
+        );
+    }
+
+    #[test]
+    fn error_if_program_contains_construct_without_ir_lowering() {
+        // int main(void) { goto end; end: return 0; }
+        let c_program_defn_ast_node =
+            c::ProgramDefinition::Program(vec![c::FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![
+                    c::BlockItem::Statement(c::Statement::Goto("end".to_string()), None),
+                    c::BlockItem::Statement(
+                        c::Statement::Labeled {
+                            label: "end".to_string(),
+                            statement: Box::new(c::Statement::Return(
+                                c::Expression::NumericConstant(0),
+                            )),
+                        },
+                        None,
+                    ),
+                ]),
+            }]);
+        let expected_err = LoweringError {
+            construct: "`goto` statement".to_string(),
+            location: "main".to_string(),
+        };
+        assert_eq!(
+            parse_program_definition(c_program_defn_ast_node),
+            Err(expected_err)
+        );
+    }
+
+    #[test]
+    fn error_if_break_or_continue_is_not_labeled() {
+        let statements = [
+            (c::Statement::Break(None), "`break` without a label"),
+            (c::Statement::Continue(None), "`continue` without a label"),
+        ];
+        for (c_ast_node, construct) in statements {
+            let expected_err = LoweringError {
+                construct: construct.to_string(),
+                location: "main".to_string(),
+            };
+            assert_eq!(
+                lower_main(vec![c::BlockItem::Statement(c_ast_node, None)]),
+                Err(expected_err)
+            );
+        }
+    }
+
+    #[test]
+    fn error_if_loop_switch_or_case_is_not_labeled() {
+        let statements = [
+            (
+                c::Statement::While {
+                    condition: c::Expression::NumericConstant(1),
+                    body: Box::new(c::Statement::Null),
+                    label: None,
+                },
+                "loop without a label",
+            ),
+            (
+                c::Statement::Switch {
+                    condition: c::Expression::NumericConstant(1),
+                    body: Box::new(c::Statement::Null),
+                    label: None,
+                    cases: vec![],
+                },
+                "`switch` without a label",
+            ),
+            (
+                c::Statement::Default {
+                    statement: Box::new(c::Statement::Null),
+                    label: None,
+                },
+                "case without a label",
+            ),
+        ];
+        for (c_ast_node, construct) in statements {
+            let expected_err = LoweringError {
+                construct: construct.to_string(),
+                location: "main".to_string(),
+            };
+            assert_eq!(
+                lower_main(vec![c::BlockItem::Statement(c_ast_node, None)]),
+                Err(expected_err)
+            );
+        }
+    }
+
+    #[test]
+    fn error_for_unlabeled_break_reports_function_containing_it() {
+        // int main(void) { while (1) break; }, without loop labeling having been run
+        let c_program_defn_ast_node =
+            c::ProgramDefinition::Program(vec![c::FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::While {
+                        condition: c::Expression::NumericConstant(1),
+                        body: Box::new(c::Statement::Break(None)),
+                        label: Some("loop.0".to_string()),
+                    },
+                    None,
+                )]),
+            }]);
+        let expected_err = LoweringError {
+            construct: "`break` without a label".to_string(),
+            location: "main".to_string(),
+        };
+        assert_eq!(
+            parse_program_definition(c_program_defn_ast_node),
+            Err(expected_err)
+        );
+    }
+
+    #[test]
+    fn error_if_expression_without_ir_lowering_is_nested_in_supported_expression() {
+        // return 1 + a.0++;
+        let c_ast_node = c::Expression::Binary {
+            op: c::BinaryOperator::Add,
+            left: Box::new(c::Expression::NumericConstant(1)),
+            right: Box::new(c::Expression::Postfix {
+                op: c::IncrementOperator::Increment,
+                operand: Box::new(c::Expression::Var("a.0".to_string())),
+            }),
+        };
+        let expected_err = LoweringError {
+            construct: "postfix increment/decrement".to_string(),
+            location: "main".to_string(),
+        };
+        assert_eq!(
+            lower_main(vec![c::BlockItem::Statement(
+                c::Statement::Return(c_ast_node),
+                None
+            )]),
+            Err(expected_err)
+        );
+    }
+
+    #[test]
+    fn parse_switch_statement_with_fall_through_and_default_to_ir_instructions() {
+        // switch (a.0) { case 1: a.0 = 5; case 2: return a.0; case 3: break; default: return 0; }
+        let c_statement_ast_node = c::Statement::Switch {
+            condition: c::Expression::Var("a.0".to_string()),
+            body: Box::new(c::Statement::Compound(vec![
+                c::BlockItem::Statement(
+                    c::Statement::Case {
+                        value: c::Expression::NumericConstant(1),
+                        statement: Box::new(c::Statement::Expression(c::Expression::Assignment {
+                            lhs: Box::new(c::Expression::Var("a.0".to_string())),
+                            rhs: Box::new(c::Expression::NumericConstant(5)),
+                        })),
+                        label: Some("case.1".to_string()),
+                    },
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Case {
+                        value: c::Expression::NumericConstant(2),
+                        statement: Box::new(c::Statement::Return(c::Expression::Var(
+                            "a.0".to_string(),
+                        ))),
+                        label: Some("case.2".to_string()),
+                    },
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Case {
+                        value: c::Expression::NumericConstant(3),
+                        statement: Box::new(c::Statement::Break(Some("switch.0".to_string()))),
+                        label: Some("case.3".to_string()),
+                    },
+                    None,
+                ),
+                c::BlockItem::Statement(
+                    c::Statement::Default {
+                        statement: Box::new(c::Statement::Return(c::Expression::NumericConstant(
+                            0,
+                        ))),
+                        label: Some("default.4".to_string()),
+                    },
+                    None,
+                ),
+            ])),
+            label: Some("switch.0".to_string()),
+            cases: vec![
+                (Some(1), "case.1".to_string()),
+                (Some(2), "case.2".to_string()),
+                (Some(3), "case.3".to_string()),
+                (None, "default.4".to_string()),
+            ],
+        };
+        let mut expected_ir_instruction_ast_nodes = Vec::new();
+        for (i, value) in [1, 2, 3].into_iter().enumerate() {
+            expected_ir_instruction_ast_nodes.append(&mut vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Equal,
+                    left: Value::Var("a.0".to_string()),
+                    right: Value::Constant(value),
+                    dst: Value::Var(format!("tmp{}", i)),
+                },
+                Instruction::JumpIfNotZero {
+                    condition: Value::Var(format!("tmp{}", i)),
+                    target: format!("case.{}", value),
+                },
+            ]);
+        }
+        expected_ir_instruction_ast_nodes.append(&mut vec![
+            Instruction::Jump("default.4".to_string()),
+            // Nothing jumps past `case 1`, so execution falls through into `case 2`
+            Instruction::Label("case.1".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(5),
+                dst: Value::Var("a.0".to_string()),
+            },
+            Instruction::Label("case.2".to_string()),
+            Instruction::Return(Value::Var("a.0".to_string())),
+            Instruction::Label("case.3".to_string()),
+            Instruction::Jump("break_switch.0".to_string()),
+            Instruction::Label("default.4".to_string()),
+            Instruction::Return(Value::Constant(0)),
+            Instruction::Label("break_switch.0".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ]);
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[test]
+    fn parse_switch_statement_without_default_to_ir_instructions_jumping_to_end() {
+        // switch (2) { case 1: return 1; }
+        let c_statement_ast_node = c::Statement::Switch {
+            condition: c::Expression::NumericConstant(2),
+            body: Box::new(c::Statement::Compound(vec![c::BlockItem::Statement(
+                c::Statement::Case {
+                    value: c::Expression::NumericConstant(1),
+                    statement: Box::new(c::Statement::Return(c::Expression::NumericConstant(1))),
+                    label: Some("case.1".to_string()),
+                },
+                None,
+            )])),
+            label: Some("switch.0".to_string()),
+            cases: vec![(Some(1), "case.1".to_string())],
+        };
+        let expected_ir_instruction_ast_nodes = vec![
+            Instruction::Binary {
+                op: BinaryOperator::Equal,
+                left: Value::Constant(2),
+                right: Value::Constant(1),
+                dst: Value::Var("tmp0".to_string()),
+            },
+            Instruction::JumpIfNotZero {
+                condition: Value::Var("tmp0".to_string()),
+                target: "case.1".to_string(),
+            },
+            Instruction::Jump("break_switch.0".to_string()),
+            Instruction::Label("case.1".to_string()),
+            Instruction::Return(Value::Constant(1)),
+            Instruction::Label("break_switch.0".to_string()),
+            Instruction::Return(Value::Constant(0)),
+        ];
+        let ir_ast_nodes = lower_statement_in_main(c_statement_ast_node);
+        assert_eq!(ir_ast_nodes, expected_ir_instruction_ast_nodes);
+    }
+
+    #[cfg(feature = "ast-json")]
+    #[test]
+    fn serialize_program_defn_to_json() {
+        let ir_ast_node = ProgramDefinition::Program(vec![FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Unary {
+                    op: UnaryOperator::Negation,
+                    src: Value::Var("a.0".to_string()),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Var("tmp0".to_string()),
+                    right: Value::Constant(3),
+                    dst: Value::Var("tmp1".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp1".to_string())),
+            ],
+            locations: BTreeMap::from([(2, Span { line: 3, column: 5 })]),
+        }]);
+        let expected_json = serde_json::json!({
+            "Program": [{
+                "Function": {
+                    "identifier": "main",
+                    "body": [
+                        {
+                            "Unary": {
+                                "op": "Negation",
+                                "src": { "Var": "a.0" },
+                                "dst": { "Var": "tmp0" },
+                            }
+                        },
+                        {
+                            "Binary": {
+                                "op": "Add",
+                                "left": { "Var": "tmp0" },
+                                "right": { "Constant": 3 },
+                                "dst": { "Var": "tmp1" },
+                            }
+                        },
+                        { "Return": { "Var": "tmp1" } },
+                    ],
+                    "locations": { "2": { "line": 3, "column": 5 } },
+                }
+            }]
+        });
+        let json: serde_json::Value = serde_json::from_str(&ir_to_json(&ir_ast_node)).unwrap();
+        assert_eq!(json, expected_json);
+    }
+
+    #[test]
+    fn lowering_error_location_is_function_containing_unsupported_construct() {
+        // int main(void) { return 1; } int foo(void) { a.0 += 1; }
+        let c_program_defn_ast_node = c::ProgramDefinition::Program(vec![
+            c::FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::NumericConstant(1)),
+                    None,
+                )]),
+            },
+            c::FunctionDefinition::Function {
+                name: "foo".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::Expression(c::Expression::CompoundAssignment {
+                        op: c::BinaryOperator::Add,
+                        lhs: Box::new(c::Expression::Var("a.0".to_string())),
+                        rhs: Box::new(c::Expression::NumericConstant(1)),
+                    }),
+                    None,
+                )]),
+            },
+        ]);
+        let expected_err = LoweringError {
+            construct: "compound assignment".to_string(),
+            location: "foo".to_string(),
+        };
+        assert_eq!(
+            parse_program_definition(c_program_defn_ast_node),
+            Err(expected_err)
+        );
+    }
 }
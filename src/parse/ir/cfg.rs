@@ -0,0 +1,285 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::lex::Span;
+use crate::parse::ir::{
+    located_instructions, split_located_instructions, FunctionDefinition, Instruction,
+};
+use crate::parse::Identifier;
+
+/// Where control can go after the last instruction of a basic block
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Successor {
+    /// The basic block at the given index in the graph
+    Block(usize),
+    /// Returning from the function
+    Exit,
+}
+
+/// A sequence of instructions that can only be entered at the first instruction, and only left
+/// after the last instruction
+#[derive(Debug, PartialEq)]
+pub struct BasicBlock {
+    pub instructions: Vec<Instruction>,
+    /// The positions in the C source code of the instructions, keyed by the index of the
+    /// instruction in the block
+    pub locations: BTreeMap<usize, Span>,
+    pub successors: Vec<Successor>,
+    pub predecessors: Vec<usize>,
+}
+
+/// The basic blocks of a function, in the order that their instructions appear in the function.
+/// Execution of the function starts at the first basic block.
+#[derive(Debug, PartialEq)]
+pub struct ControlFlowGraph {
+    pub identifier: Identifier,
+    pub blocks: Vec<BasicBlock>,
+}
+
+/// Split instructions into the instructions of each basic block. A label starts a new block, since
+/// it can be jumped to, and any jump or return ends the current block.
+fn partition(body: Vec<(Instruction, Option<Span>)>) -> Vec<Vec<(Instruction, Option<Span>)>> {
+    let mut partitions = Vec::new();
+    let mut current = Vec::new();
+    for (instruction, span) in body.into_iter() {
+        if matches!(instruction, Instruction::Label(_)) && !current.is_empty() {
+            partitions.push(std::mem::take(&mut current));
+        }
+        let ends_block = matches!(
+            instruction,
+            Instruction::Return(_)
+                | Instruction::Jump(_)
+                | Instruction::JumpIfZero { .. }
+                | Instruction::JumpIfNotZero { .. }
+        );
+        current.push((instruction, span));
+        if ends_block {
+            partitions.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        partitions.push(current);
+    }
+    partitions
+}
+
+impl ControlFlowGraph {
+    /// Split the instructions of the function into basic blocks, and connect each block to the
+    /// blocks that control can go to after it.
+    ///
+    /// Panics if a jump targets a label that isn't in the function, so the graph must only be built
+    /// from IR that has passed [`validate`](crate::parse::ir::validation::validate).
+    pub fn from_function_definition(node: FunctionDefinition) -> ControlFlowGraph {
+        match node {
+            FunctionDefinition::Function {
+                identifier,
+                body,
+                locations,
+            } => {
+                let partitions = partition(located_instructions(body, locations));
+
+                let mut label_blocks: HashMap<Identifier, usize> = HashMap::new();
+                for (index, instructions) in partitions.iter().enumerate() {
+                    if let Some((Instruction::Label(label), _)) = instructions.first() {
+                        label_blocks.insert(label.clone(), index);
+                    }
+                }
+
+                let block_count = partitions.len();
+                let target_block = |label: &Identifier| {
+                    Successor::Block(
+                        *label_blocks
+                            .get(label)
+                            .expect("Jump target should be a label in the same function"),
+                    )
+                };
+                // Falling off the end of the last block leaves the function
+                let next_block = |index: usize| {
+                    if index + 1 < block_count {
+                        Successor::Block(index + 1)
+                    } else {
+                        Successor::Exit
+                    }
+                };
+
+                let mut blocks: Vec<BasicBlock> = partitions
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, located)| {
+                        let (instructions, locations) = split_located_instructions(located);
+                        let mut successors = match instructions.last() {
+                            Some(Instruction::Return(_)) => vec![Successor::Exit],
+                            Some(Instruction::Jump(target)) => vec![target_block(target)],
+                            Some(
+                                Instruction::JumpIfZero { target, .. }
+                                | Instruction::JumpIfNotZero { target, .. },
+                            ) => vec![target_block(target), next_block(index)],
+                            _ => vec![next_block(index)],
+                        };
+                        successors.sort();
+                        successors.dedup();
+                        BasicBlock {
+                            instructions,
+                            locations,
+                            successors,
+                            predecessors: Vec::new(),
+                        }
+                    })
+                    .collect();
+
+                for index in 0..blocks.len() {
+                    for successor in blocks[index].successors.clone() {
+                        if let Successor::Block(successor) = successor {
+                            blocks[successor].predecessors.push(index);
+                        }
+                    }
+                }
+
+                ControlFlowGraph { identifier, blocks }
+            }
+        }
+    }
+
+    /// The indices of the basic blocks that can't be reached from the start of the function
+    pub fn unreachable_blocks(&self) -> Vec<usize> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = Vec::new();
+        if !self.blocks.is_empty() {
+            stack.push(0);
+        }
+        while let Some(index) = stack.pop() {
+            if reachable[index] {
+                continue;
+            }
+            reachable[index] = true;
+            for successor in self.blocks[index].successors.iter() {
+                if let Successor::Block(successor) = successor {
+                    stack.push(*successor);
+                }
+            }
+        }
+        reachable
+            .into_iter()
+            .enumerate()
+            .filter(|(_, reachable)| !reachable)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Put the instructions of the basic blocks back into a single sequence of instructions, in the
+    /// order of the blocks
+    pub fn into_function_definition(self) -> FunctionDefinition {
+        let (body, locations) = split_located_instructions(
+            self.blocks
+                .into_iter()
+                .flat_map(|block| located_instructions(block.instructions, block.locations))
+                .collect(),
+        );
+        FunctionDefinition::Function {
+            identifier: self.identifier,
+            body,
+            locations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::ir::Value;
+
+    /// The IR of `if (a.0) b.1 = 1; else b.1 = 2; return b.1;`
+    fn make_if_else_body() -> Vec<Instruction> {
+        vec![
+            Instruction::JumpIfZero {
+                condition: Value::Var("a.0".to_string()),
+                target: "if_else0".to_string(),
+            },
+            Instruction::Copy {
+                src: Value::Constant(1),
+                dst: Value::Var("b.1".to_string()),
+            },
+            Instruction::Jump("if_end1".to_string()),
+            Instruction::Label("if_else0".to_string()),
+            Instruction::Copy {
+                src: Value::Constant(2),
+                dst: Value::Var("b.1".to_string()),
+            },
+            Instruction::Label("if_end1".to_string()),
+            Instruction::Return(Value::Var("b.1".to_string())),
+        ]
+    }
+
+    #[test]
+    fn split_if_else_diamond_into_basic_blocks() {
+        let body = make_if_else_body();
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: body.clone(),
+            locations: BTreeMap::new(),
+        };
+        let mut instructions = body.into_iter();
+        let mut take = |count: usize| instructions.by_ref().take(count).collect::<Vec<_>>();
+        let expected_cfg = ControlFlowGraph {
+            identifier: "main".to_string(),
+            blocks: vec![
+                BasicBlock {
+                    instructions: take(1),
+                    successors: vec![Successor::Block(1), Successor::Block(2)],
+                    predecessors: vec![],
+                    locations: BTreeMap::new(),
+                },
+                BasicBlock {
+                    instructions: take(2),
+                    successors: vec![Successor::Block(3)],
+                    predecessors: vec![0],
+                    locations: BTreeMap::new(),
+                },
+                BasicBlock {
+                    instructions: take(2),
+                    successors: vec![Successor::Block(3)],
+                    predecessors: vec![0],
+                    locations: BTreeMap::new(),
+                },
+                BasicBlock {
+                    instructions: take(2),
+                    successors: vec![Successor::Exit],
+                    predecessors: vec![1, 2],
+                    locations: BTreeMap::new(),
+                },
+            ],
+        };
+        assert_eq!(
+            ControlFlowGraph::from_function_definition(func_defn),
+            expected_cfg
+        );
+    }
+
+    #[test]
+    fn find_block_that_is_never_jumped_to() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Jump("end".to_string()),
+                Instruction::Label("unused".to_string()),
+                Instruction::Return(Value::Constant(1)),
+                Instruction::Label("end".to_string()),
+                Instruction::Return(Value::Constant(2)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let cfg = ControlFlowGraph::from_function_definition(func_defn);
+        assert_eq!(cfg.unreachable_blocks(), vec![1]);
+    }
+
+    #[test]
+    fn linearize_cfg_back_into_original_instructions() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: make_if_else_body(),
+            locations: BTreeMap::new(),
+        };
+        let cfg = ControlFlowGraph::from_function_definition(func_defn.clone());
+        assert!(cfg.unreachable_blocks().is_empty());
+        assert_eq!(cfg.into_function_definition(), func_defn);
+    }
+}
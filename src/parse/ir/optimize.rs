@@ -0,0 +1,597 @@
+use std::collections::HashMap;
+
+use crate::parse::ir::cfg::ControlFlowGraph;
+use crate::parse::ir::{
+    located_instructions, split_located_instructions, BinaryOperator, FunctionDefinition,
+    Instruction, ProgramDefinition, UnaryOperator, Value,
+};
+use crate::parse::Identifier;
+
+/// Evaluate a unary operator applied to a constant, wrapping in the same way as `int` arithmetic
+/// does at runtime
+fn fold_unary(op: &UnaryOperator, src: i64) -> i64 {
+    let src = src as i32;
+    let value = match op {
+        UnaryOperator::BitwiseComplement => !src,
+        UnaryOperator::Negation => src.wrapping_neg(),
+        UnaryOperator::Not => (src == 0) as i32,
+    };
+    value.into()
+}
+
+/// Evaluate a binary operator applied to two constants, wrapping in the same way as `int`
+/// arithmetic does at runtime. Division by zero isn't folded, so that it still happens at runtime.
+fn fold_binary(op: &BinaryOperator, left: i64, right: i64) -> Option<i64> {
+    let left = left as i32;
+    let right = right as i32;
+    if matches!(op, BinaryOperator::Divide | BinaryOperator::Modulo) && right == 0 {
+        return None;
+    }
+    let value = match op {
+        BinaryOperator::Add => left.wrapping_add(right),
+        BinaryOperator::Subtract => left.wrapping_sub(right),
+        BinaryOperator::Multiply => left.wrapping_mul(right),
+        BinaryOperator::Divide => left.wrapping_div(right),
+        BinaryOperator::Modulo => left.wrapping_rem(right),
+        BinaryOperator::BitwiseAnd => left & right,
+        BinaryOperator::BitwiseOr => left | right,
+        BinaryOperator::BitwiseXor => left ^ right,
+        BinaryOperator::ShiftLeft => left.wrapping_shl(right as u32),
+        BinaryOperator::ShiftRight => left.wrapping_shr(right as u32),
+        BinaryOperator::Equal => (left == right) as i32,
+        BinaryOperator::NotEqual => (left != right) as i32,
+        BinaryOperator::LessThan => (left < right) as i32,
+        BinaryOperator::LessOrEqual => (left <= right) as i32,
+        BinaryOperator::GreaterThan => (left > right) as i32,
+        BinaryOperator::GreaterOrEqual => (left >= right) as i32,
+    };
+    Some(value.into())
+}
+
+/// The variable that the instruction writes to, if any
+fn destination(node: &Instruction) -> Option<&Identifier> {
+    match node {
+        Instruction::Unary { dst, .. }
+        | Instruction::Binary { dst, .. }
+        | Instruction::Copy { dst, .. }
+        | Instruction::FunCall { dst, .. } => match dst {
+            Value::Var(identifier) => Some(identifier),
+            Value::Constant(_) => None,
+        },
+        Instruction::Return(_)
+        | Instruction::Jump(_)
+        | Instruction::JumpIfZero { .. }
+        | Instruction::JumpIfNotZero { .. }
+        | Instruction::Label(_) => None,
+    }
+}
+
+/// Replace a variable with the constant that it's known to hold
+fn substitute_value(node: Value, constants: &HashMap<Identifier, i64>) -> Value {
+    match node {
+        Value::Var(identifier) => match constants.get(&identifier) {
+            Some(val) => Value::Constant(*val),
+            None => Value::Var(identifier),
+        },
+        Value::Constant(_) => node,
+    }
+}
+
+/// Replace any variables that the instruction reads from with the constants they're known to hold
+fn substitute_instruction(node: Instruction, constants: &HashMap<Identifier, i64>) -> Instruction {
+    match node {
+        Instruction::Return(val) => Instruction::Return(substitute_value(val, constants)),
+        Instruction::Unary { op, src, dst } => Instruction::Unary {
+            op,
+            src: substitute_value(src, constants),
+            dst,
+        },
+        Instruction::Binary {
+            op,
+            left,
+            right,
+            dst,
+        } => Instruction::Binary {
+            op,
+            left: substitute_value(left, constants),
+            right: substitute_value(right, constants),
+            dst,
+        },
+        Instruction::Copy { src, dst } => Instruction::Copy {
+            src: substitute_value(src, constants),
+            dst,
+        },
+        Instruction::JumpIfZero { condition, target } => Instruction::JumpIfZero {
+            condition: substitute_value(condition, constants),
+            target,
+        },
+        Instruction::JumpIfNotZero { condition, target } => Instruction::JumpIfNotZero {
+            condition: substitute_value(condition, constants),
+            target,
+        },
+        Instruction::FunCall { name, args, dst } => Instruction::FunCall {
+            name,
+            args: args
+                .into_iter()
+                .map(|arg| substitute_value(arg, constants))
+                .collect(),
+            dst,
+        },
+        Instruction::Jump(_) | Instruction::Label(_) => node,
+    }
+}
+
+/// Evaluate unary and binary instructions whose operands are all constants, removing them and
+/// substituting the computed constant into the instructions that use their result.
+///
+/// This is only done for destinations that no other instruction writes to (which is the case for
+/// the temporary variables holding the results of unary and binary instructions), since the
+/// destination then holds the computed constant everywhere it's used.
+pub fn constant_fold(node: FunctionDefinition) -> FunctionDefinition {
+    match node {
+        FunctionDefinition::Function {
+            identifier,
+            body,
+            locations,
+        } => {
+            let mut write_counts: HashMap<Identifier, usize> = HashMap::new();
+            for instruction in body.iter() {
+                if let Some(dst) = destination(instruction) {
+                    *write_counts.entry(dst.clone()).or_default() += 1;
+                }
+            }
+
+            let mut constants = HashMap::new();
+            let mut folded_body = Vec::new();
+            for (instruction, span) in located_instructions(body, locations) {
+                let instruction = substitute_instruction(instruction, &constants);
+                let folded_value = match &instruction {
+                    Instruction::Unary {
+                        op,
+                        src: Value::Constant(src),
+                        ..
+                    } => Some(fold_unary(op, *src)),
+                    Instruction::Binary {
+                        op,
+                        left: Value::Constant(left),
+                        right: Value::Constant(right),
+                        ..
+                    } => fold_binary(op, *left, *right),
+                    _ => None,
+                };
+                match (folded_value, destination(&instruction)) {
+                    (Some(val), Some(dst)) if write_counts[dst] == 1 => {
+                        constants.insert(dst.clone(), val);
+                    }
+                    _ => folded_body.push((instruction, span)),
+                }
+            }
+
+            let (body, locations) = split_located_instructions(folded_body);
+            FunctionDefinition::Function {
+                identifier,
+                body,
+                locations,
+            }
+        }
+    }
+}
+
+/// The exponent of the value if it's a constant positive power of two that fits in an `int`
+fn power_of_two_exponent(node: &Value) -> Option<i64> {
+    match node {
+        Value::Constant(val) if *val > 0 && *val <= i32::MAX.into() && val & (val - 1) == 0 => {
+            Some(val.trailing_zeros().into())
+        }
+        _ => None,
+    }
+}
+
+/// Replace multiplications of a variable by a constant power of two with a left shift, which is
+/// cheaper than a multiplication and wraps in the same way as `int` multiplication does.
+///
+/// Multiplications of two constants are left for [`constant_fold`]. Division by a power of two
+/// isn't replaced, since an arithmetic right shift rounds negative dividends towards negative
+/// infinity rather than towards zero, so it would need extra instructions to correct the result.
+pub fn reduce_strength(node: FunctionDefinition) -> FunctionDefinition {
+    match node {
+        FunctionDefinition::Function {
+            identifier,
+            body,
+            locations,
+        } => {
+            // Each instruction is replaced by exactly one instruction, so the positions of the
+            // instructions don't change
+            let reduced_body = body
+                .into_iter()
+                .map(|instruction| match instruction {
+                    Instruction::Binary {
+                        op: BinaryOperator::Multiply,
+                        left,
+                        right,
+                        dst,
+                    } => {
+                        let shift = match (&left, &right) {
+                            (Value::Var(_), _) => {
+                                power_of_two_exponent(&right).map(|exp| (left.clone(), exp))
+                            }
+                            (_, Value::Var(_)) => {
+                                power_of_two_exponent(&left).map(|exp| (right.clone(), exp))
+                            }
+                            _ => None,
+                        };
+                        match shift {
+                            Some((var, exponent)) => Instruction::Binary {
+                                op: BinaryOperator::ShiftLeft,
+                                left: var,
+                                right: Value::Constant(exponent),
+                                dst,
+                            },
+                            None => Instruction::Binary {
+                                op: BinaryOperator::Multiply,
+                                left,
+                                right,
+                                dst,
+                            },
+                        }
+                    }
+                    _ => instruction,
+                })
+                .collect();
+
+            FunctionDefinition::Function {
+                identifier,
+                body: reduced_body,
+                locations,
+            }
+        }
+    }
+}
+
+/// Remove the basic blocks of a function that can't be reached from its start, such as the
+/// instructions following a `Return` or a `Jump` that no other instruction jumps to. As with
+/// building the [`ControlFlowGraph`], the function must have passed validation.
+pub fn remove_unreachable_instructions(node: FunctionDefinition) -> FunctionDefinition {
+    let mut cfg = ControlFlowGraph::from_function_definition(node);
+    // The successors and predecessors of the remaining blocks are left referring to the old
+    // indices, but only the instructions of the blocks are needed to linearise the graph
+    for index in cfg.unreachable_blocks().into_iter().rev() {
+        cfg.blocks.remove(index);
+    }
+    cfg.into_function_definition()
+}
+
+/// Run [`remove_unreachable_instructions`] on every function in the program
+pub fn remove_unreachable_instructions_program_definition(
+    node: ProgramDefinition,
+) -> ProgramDefinition {
+    match node {
+        ProgramDefinition::Program(func_defns) => ProgramDefinition::Program(
+            func_defns
+                .into_iter()
+                .map(remove_unreachable_instructions)
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::lex::Span;
+    use crate::parse::{c, ir};
+
+    /// Lower a program containing only a `main` function whose body is the given statement
+    fn lower_main(statement: c::Statement) -> FunctionDefinition {
+        let c_program_defn = c::ProgramDefinition::Program(vec![c::FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(vec![c::BlockItem::Statement(statement, None)]),
+        }]);
+        match ir::parse_program_definition(c_program_defn).unwrap() {
+            ProgramDefinition::Program(mut func_defns) => func_defns.remove(0),
+        }
+    }
+
+    #[test]
+    fn fold_nested_binary_expression_to_single_return_instruction() {
+        // return 2 + 3 * 4;
+        let func_defn = lower_main(c::Statement::Return(c::Expression::Binary {
+            op: c::BinaryOperator::Add,
+            left: Box::new(c::Expression::NumericConstant(2)),
+            right: Box::new(c::Expression::Binary {
+                op: c::BinaryOperator::Multiply,
+                left: Box::new(c::Expression::NumericConstant(3)),
+                right: Box::new(c::Expression::NumericConstant(4)),
+            }),
+        }));
+        let expected_func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![Instruction::Return(Value::Constant(14))],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(constant_fold(func_defn), expected_func_defn);
+    }
+
+    #[test]
+    fn fold_bitwise_complement_of_negative_constant() {
+        // return ~-1;
+        let func_defn = lower_main(c::Statement::Return(c::Expression::Unary(
+            c::UnaryOperator::BitwiseComplement,
+            Box::new(c::Expression::NumericConstant(-1)),
+        )));
+        let expected_func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![Instruction::Return(Value::Constant(0))],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(constant_fold(func_defn), expected_func_defn);
+    }
+
+    #[test]
+    fn division_by_zero_is_not_folded() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Divide,
+                    left: Value::Constant(1),
+                    right: Value::Constant(0),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp0".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(constant_fold(func_defn.clone()), func_defn);
+    }
+
+    #[test]
+    fn folded_arithmetic_wraps_like_int_arithmetic() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Constant(2147483647),
+                    right: Value::Constant(1),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp0".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let expected_func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![Instruction::Return(Value::Constant(-2147483648))],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(constant_fold(func_defn), expected_func_defn);
+    }
+
+    #[test]
+    fn destination_written_by_several_instructions_is_not_folded() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Constant(1),
+                    right: Value::Constant(2),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Copy {
+                    src: Value::Constant(5),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Return(Value::Var("a.0".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(constant_fold(func_defn.clone()), func_defn);
+    }
+
+    #[test]
+    fn remove_second_of_two_return_instructions() {
+        // return 1; return 2;
+        let func_defn = lower_main(c::Statement::Compound(vec![
+            c::BlockItem::Statement(
+                c::Statement::Return(c::Expression::NumericConstant(1)),
+                None,
+            ),
+            c::BlockItem::Statement(
+                c::Statement::Return(c::Expression::NumericConstant(2)),
+                None,
+            ),
+        ]));
+        let expected_func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![Instruction::Return(Value::Constant(1))],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(
+            remove_unreachable_instructions(func_defn),
+            expected_func_defn
+        );
+    }
+
+    #[test]
+    fn remove_instructions_after_jump_up_to_next_label() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Jump("end".to_string()),
+                Instruction::Copy {
+                    src: Value::Constant(1),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Return(Value::Var("a.0".to_string())),
+                Instruction::Label("end".to_string()),
+                Instruction::Return(Value::Constant(2)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let expected_func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Jump("end".to_string()),
+                Instruction::Label("end".to_string()),
+                Instruction::Return(Value::Constant(2)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(
+            remove_unreachable_instructions(func_defn),
+            expected_func_defn
+        );
+    }
+
+    #[test]
+    fn remove_labeled_instructions_that_are_never_jumped_to() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Jump("end".to_string()),
+                Instruction::Label("unused".to_string()),
+                Instruction::Return(Value::Constant(1)),
+                Instruction::Label("end".to_string()),
+                Instruction::Return(Value::Constant(2)),
+            ],
+            locations: BTreeMap::from([
+                (0, Span { line: 2, column: 5 }),
+                (
+                    2,
+                    Span {
+                        line: 3,
+                        column: 13,
+                    },
+                ),
+                (
+                    4,
+                    Span {
+                        line: 4,
+                        column: 10,
+                    },
+                ),
+            ]),
+        };
+        let expected_func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Jump("end".to_string()),
+                Instruction::Label("end".to_string()),
+                Instruction::Return(Value::Constant(2)),
+            ],
+            locations: BTreeMap::from([
+                (0, Span { line: 2, column: 5 }),
+                (
+                    2,
+                    Span {
+                        line: 4,
+                        column: 10,
+                    },
+                ),
+            ]),
+        };
+        assert_eq!(
+            remove_unreachable_instructions(func_defn),
+            expected_func_defn
+        );
+    }
+
+    #[test]
+    fn keep_instructions_after_label_following_return() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::JumpIfZero {
+                    condition: Value::Var("a.0".to_string()),
+                    target: "if_end0".to_string(),
+                },
+                Instruction::Return(Value::Constant(1)),
+                Instruction::Label("if_end0".to_string()),
+                Instruction::Return(Value::Constant(2)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(
+            remove_unreachable_instructions(func_defn.clone()),
+            func_defn
+        );
+    }
+
+    #[test]
+    fn multiplication_of_variable_by_power_of_two_becomes_left_shift() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: Value::Var("x.0".to_string()),
+                    right: Value::Constant(8),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: Value::Constant(2),
+                    right: Value::Var("tmp0".to_string()),
+                    dst: Value::Var("tmp1".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp1".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let expected_func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::ShiftLeft,
+                    left: Value::Var("x.0".to_string()),
+                    right: Value::Constant(3),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::ShiftLeft,
+                    left: Value::Var("tmp0".to_string()),
+                    right: Value::Constant(1),
+                    dst: Value::Var("tmp1".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp1".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(reduce_strength(func_defn), expected_func_defn);
+    }
+
+    #[test]
+    fn multiplication_by_constant_that_is_not_power_of_two_is_not_reduced() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: Value::Var("x.0".to_string()),
+                    right: Value::Constant(7),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: Value::Var("x.0".to_string()),
+                    right: Value::Constant(-8),
+                    dst: Value::Var("tmp1".to_string()),
+                },
+                Instruction::Binary {
+                    op: BinaryOperator::Divide,
+                    left: Value::Var("tmp0".to_string()),
+                    right: Value::Constant(4),
+                    dst: Value::Var("tmp2".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp2".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(reduce_strength(func_defn.clone()), func_defn);
+    }
+}
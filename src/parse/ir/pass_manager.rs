@@ -0,0 +1,227 @@
+use crate::parse::ir::optimize;
+use crate::parse::ir::{FunctionDefinition, ProgramDefinition};
+
+/// A transformation of the IR of a function that doesn't change the behaviour of the function
+pub trait IrPass {
+    /// The name used to select the pass with the `--opt=` flag
+    fn name(&self) -> &'static str;
+    fn run(&self, node: FunctionDefinition) -> FunctionDefinition;
+}
+
+/// Runs [`optimize::constant_fold`]
+pub struct ConstantFolding;
+
+impl IrPass for ConstantFolding {
+    fn name(&self) -> &'static str {
+        "fold"
+    }
+
+    fn run(&self, node: FunctionDefinition) -> FunctionDefinition {
+        optimize::constant_fold(node)
+    }
+}
+
+/// Runs [`optimize::reduce_strength`]
+pub struct StrengthReduction;
+
+impl IrPass for StrengthReduction {
+    fn name(&self) -> &'static str {
+        "reduce-strength"
+    }
+
+    fn run(&self, node: FunctionDefinition) -> FunctionDefinition {
+        optimize::reduce_strength(node)
+    }
+}
+
+/// A pass name given on the command line that doesn't match any pass
+#[derive(Debug, PartialEq)]
+pub struct UnknownPass {
+    pub name: String,
+}
+
+impl std::fmt::Display for UnknownPass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown optimisation pass `{}`", self.name)
+    }
+}
+
+/// Every pass that can be selected by name
+fn available_passes() -> Vec<Box<dyn IrPass>> {
+    vec![Box::new(ConstantFolding), Box::new(StrengthReduction)]
+}
+
+/// Runs a sequence of passes over every function in a program, in the order they were added
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn IrPass>>,
+}
+
+impl PassManager {
+    pub fn new() -> PassManager {
+        PassManager::default()
+    }
+
+    /// The passes enabled by `-O`. Constant folding runs first, so that operations on constants
+    /// are evaluated rather than being rewritten into cheaper operations.
+    pub fn default_pipeline() -> PassManager {
+        let mut pass_manager = PassManager::new();
+        pass_manager.add_pass(Box::new(ConstantFolding));
+        pass_manager.add_pass(Box::new(StrengthReduction));
+        pass_manager
+    }
+
+    /// Create a pass manager running the passes with the given comma-separated names, in the
+    /// order that they're given
+    pub fn from_pass_names(names: &str) -> Result<PassManager, UnknownPass> {
+        let mut pass_manager = PassManager::new();
+        for name in names.split(',').filter(|name| !name.is_empty()) {
+            let pass = available_passes()
+                .into_iter()
+                .find(|pass| pass.name() == name)
+                .ok_or_else(|| UnknownPass {
+                    name: name.to_string(),
+                })?;
+            pass_manager.add_pass(pass);
+        }
+        Ok(pass_manager)
+    }
+
+    pub fn add_pass(&mut self, pass: Box<dyn IrPass>) {
+        self.passes.push(pass);
+    }
+
+    pub fn run(&self, node: FunctionDefinition) -> FunctionDefinition {
+        self.passes
+            .iter()
+            .fold(node, |func_defn, pass| pass.run(func_defn))
+    }
+
+    /// Run the passes on every function in the program
+    pub fn run_program_definition(&self, node: ProgramDefinition) -> ProgramDefinition {
+        match node {
+            ProgramDefinition::Program(func_defns) => ProgramDefinition::Program(
+                func_defns
+                    .into_iter()
+                    .map(|func_defn| self.run(func_defn))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::parse::ir::{BinaryOperator, Instruction, Value};
+    use crate::parse::{c, ir};
+
+    /// A pass that doesn't change the function, but records that it was run
+    struct RecordingPass {
+        name: &'static str,
+        runs: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl IrPass for RecordingPass {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn run(&self, node: FunctionDefinition) -> FunctionDefinition {
+            self.runs.borrow_mut().push(self.name);
+            node
+        }
+    }
+
+    fn make_func_defn() -> FunctionDefinition {
+        FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: Value::Constant(2),
+                    right: Value::Constant(3),
+                    dst: Value::Var("tmp0".to_string()),
+                },
+                Instruction::Return(Value::Var("tmp0".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn passes_run_in_order_they_were_added() {
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        let mut pass_manager = PassManager::new();
+        for name in ["second", "first", "third"] {
+            pass_manager.add_pass(Box::new(RecordingPass {
+                name,
+                runs: Rc::clone(&runs),
+            }));
+        }
+        pass_manager.run(make_func_defn());
+        assert_eq!(*runs.borrow(), vec!["second", "first", "third"]);
+    }
+
+    #[test]
+    fn empty_pass_manager_does_not_change_function_defn() {
+        let pass_manager = PassManager::new();
+        let func_defn = make_func_defn();
+        assert_eq!(pass_manager.run(func_defn.clone()), func_defn);
+    }
+
+    #[test]
+    fn default_pipeline_folds_constants() {
+        // int main(void) { return 2 + 3 * 4; }
+        let c_program_defn_ast_node =
+            c::ProgramDefinition::Program(vec![c::FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![c::BlockItem::Statement(
+                    c::Statement::Return(c::Expression::Binary {
+                        op: c::BinaryOperator::Add,
+                        left: Box::new(c::Expression::NumericConstant(2)),
+                        right: Box::new(c::Expression::Binary {
+                            op: c::BinaryOperator::Multiply,
+                            left: Box::new(c::Expression::NumericConstant(3)),
+                            right: Box::new(c::Expression::NumericConstant(4)),
+                        }),
+                    }),
+                    None,
+                )]),
+            }]);
+        let program_defn = ir::parse_program_definition(c_program_defn_ast_node).unwrap();
+        let expected_program_defn =
+            ProgramDefinition::Program(vec![FunctionDefinition::Function {
+                identifier: "main".to_string(),
+                body: vec![Instruction::Return(Value::Constant(14))],
+                locations: BTreeMap::new(),
+            }]);
+        assert_eq!(
+            PassManager::default_pipeline().run_program_definition(program_defn),
+            expected_program_defn
+        );
+    }
+
+    #[test]
+    fn select_passes_by_name_in_given_order() {
+        let pass_manager = PassManager::from_pass_names("reduce-strength,fold").unwrap();
+        let names: Vec<&str> = pass_manager.passes.iter().map(|pass| pass.name()).collect();
+        assert_eq!(names, vec!["reduce-strength", "fold"]);
+    }
+
+    #[test]
+    fn error_if_pass_name_is_unknown() {
+        let res = PassManager::from_pass_names("fold,copy-prop");
+        assert_eq!(
+            res.err(),
+            Some(UnknownPass {
+                name: "copy-prop".to_string()
+            })
+        );
+    }
+}
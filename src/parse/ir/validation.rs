@@ -0,0 +1,278 @@
+use std::collections::HashSet;
+
+use crate::parse::ir::{FunctionDefinition, Instruction, ProgramDefinition, Value};
+use crate::parse::Identifier;
+
+/// An invariant of the IR that doesn't hold, which means there's a bug in the lowering to IR (or in
+/// an optimisation pass) rather than in the C source code
+#[derive(Debug, PartialEq)]
+pub enum IrValidationError {
+    ConstantDestination {
+        function: Identifier,
+        instruction: String,
+    },
+    UndefinedLabel {
+        function: Identifier,
+        label: Identifier,
+    },
+    DuplicateLabel {
+        function: Identifier,
+        label: Identifier,
+    },
+    MissingFinalControlTransfer {
+        function: Identifier,
+    },
+}
+
+impl std::fmt::Display for IrValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IrValidationError::ConstantDestination {
+                function,
+                instruction,
+            } => write!(
+                f,
+                "Instruction `{}` in function `{}` has a constant destination",
+                instruction, function
+            ),
+            IrValidationError::UndefinedLabel { function, label } => write!(
+                f,
+                "Jump to undefined label `{}` in function `{}`",
+                label, function
+            ),
+            IrValidationError::DuplicateLabel { function, label } => {
+                write!(f, "Duplicate label `{}` in function `{}`", label, function)
+            }
+            IrValidationError::MissingFinalControlTransfer { function } => write!(
+                f,
+                "Function `{}` doesn't end with a return or a jump",
+                function
+            ),
+        }
+    }
+}
+
+/// Check the invariants that the later stages of the compiler assume hold for the IR of a function:
+/// instructions only write to variables, every jump targets a label in the same function, labels
+/// are unique, and execution can't run off the end of the function.
+pub fn validate(node: &FunctionDefinition) -> Result<(), Vec<IrValidationError>> {
+    let mut errors = Vec::new();
+
+    match node {
+        FunctionDefinition::Function {
+            identifier, body, ..
+        } => {
+            let mut labels = HashSet::new();
+            for instruction in body {
+                if let Instruction::Label(label) = instruction {
+                    if !labels.insert(label) {
+                        errors.push(IrValidationError::DuplicateLabel {
+                            function: identifier.clone(),
+                            label: label.clone(),
+                        });
+                    }
+                }
+            }
+
+            for instruction in body {
+                match instruction {
+                    Instruction::Unary {
+                        dst: Value::Constant(_),
+                        ..
+                    }
+                    | Instruction::Binary {
+                        dst: Value::Constant(_),
+                        ..
+                    }
+                    | Instruction::Copy {
+                        dst: Value::Constant(_),
+                        ..
+                    }
+                    | Instruction::FunCall {
+                        dst: Value::Constant(_),
+                        ..
+                    } => errors.push(IrValidationError::ConstantDestination {
+                        function: identifier.clone(),
+                        instruction: instruction.to_string(),
+                    }),
+                    Instruction::Jump(target)
+                    | Instruction::JumpIfZero { target, .. }
+                    | Instruction::JumpIfNotZero { target, .. }
+                        if !labels.contains(target) =>
+                    {
+                        errors.push(IrValidationError::UndefinedLabel {
+                            function: identifier.clone(),
+                            label: target.clone(),
+                        })
+                    }
+                    _ => (),
+                }
+            }
+
+            if !matches!(
+                body.last(),
+                Some(Instruction::Return(_) | Instruction::Jump(_))
+            ) {
+                errors.push(IrValidationError::MissingFinalControlTransfer {
+                    function: identifier.clone(),
+                });
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Run [`validate`] on every function in the program, collecting the errors from all of them
+pub fn validate_program_definition(node: &ProgramDefinition) -> Result<(), Vec<IrValidationError>> {
+    match node {
+        ProgramDefinition::Program(func_defns) => {
+            let errors: Vec<IrValidationError> = func_defns
+                .iter()
+                .filter_map(|func_defn| validate(func_defn).err())
+                .flatten()
+                .collect();
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(errors)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::parse::ir::BinaryOperator;
+
+    #[test]
+    fn valid_function_defn_passes_validation() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::JumpIfZero {
+                    condition: Value::Var("a.0".to_string()),
+                    target: "if_end0".to_string(),
+                },
+                Instruction::Copy {
+                    src: Value::Constant(1),
+                    dst: Value::Var("a.0".to_string()),
+                },
+                Instruction::Label("if_end0".to_string()),
+                Instruction::Return(Value::Var("a.0".to_string())),
+            ],
+            locations: BTreeMap::new(),
+        };
+        assert_eq!(validate(&func_defn), Ok(()));
+    }
+
+    #[test]
+    fn error_if_instruction_has_constant_destination() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Binary {
+                    op: BinaryOperator::Add,
+                    left: Value::Constant(1),
+                    right: Value::Constant(2),
+                    dst: Value::Constant(3),
+                },
+                Instruction::Return(Value::Constant(0)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let expected_errors = vec![IrValidationError::ConstantDestination {
+            function: "main".to_string(),
+            instruction: "3 = 1 + 2".to_string(),
+        }];
+        assert_eq!(validate(&func_defn), Err(expected_errors));
+    }
+
+    #[test]
+    fn error_if_jump_to_missing_label() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::JumpIfNotZero {
+                    condition: Value::Constant(1),
+                    target: "or_true0".to_string(),
+                },
+                Instruction::Return(Value::Constant(0)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let expected_errors = vec![IrValidationError::UndefinedLabel {
+            function: "main".to_string(),
+            label: "or_true0".to_string(),
+        }];
+        assert_eq!(validate(&func_defn), Err(expected_errors));
+    }
+
+    #[test]
+    fn error_if_function_defn_does_not_end_with_control_transfer() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![Instruction::Label("end".to_string())],
+            locations: BTreeMap::new(),
+        };
+        let expected_errors = vec![IrValidationError::MissingFinalControlTransfer {
+            function: "main".to_string(),
+        }];
+        assert_eq!(validate(&func_defn), Err(expected_errors));
+    }
+
+    #[test]
+    fn error_if_label_defined_twice() {
+        let func_defn = FunctionDefinition::Function {
+            identifier: "main".to_string(),
+            body: vec![
+                Instruction::Label("end".to_string()),
+                Instruction::Label("end".to_string()),
+                Instruction::Return(Value::Constant(0)),
+            ],
+            locations: BTreeMap::new(),
+        };
+        let expected_errors = vec![IrValidationError::DuplicateLabel {
+            function: "main".to_string(),
+            label: "end".to_string(),
+        }];
+        assert_eq!(validate(&func_defn), Err(expected_errors));
+    }
+
+    #[test]
+    fn collect_errors_from_every_function_defn_in_program() {
+        let program_defn = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                identifier: "foo".to_string(),
+                body: vec![Instruction::Jump("end".to_string())],
+                locations: BTreeMap::new(),
+            },
+            FunctionDefinition::Function {
+                identifier: "main".to_string(),
+                body: vec![Instruction::Jump("end".to_string())],
+                locations: BTreeMap::new(),
+            },
+        ]);
+        let expected_errors = vec![
+            IrValidationError::UndefinedLabel {
+                function: "foo".to_string(),
+                label: "end".to_string(),
+            },
+            IrValidationError::UndefinedLabel {
+                function: "main".to_string(),
+                label: "end".to_string(),
+            },
+        ];
+        assert_eq!(
+            validate_program_definition(&program_defn),
+            Err(expected_errors)
+        );
+    }
+}
@@ -0,0 +1,118 @@
+mod constant_evaluation;
+mod label_validation;
+mod loop_labeling;
+mod unreachable_code;
+mod variable_resolution;
+
+use crate::lex::Span;
+use crate::parse::{c, Identifier};
+
+/// Errors in C source code that is syntactically valid, but doesn't have a meaning
+#[derive(Debug, PartialEq)]
+pub enum SemanticError {
+    /// A variable is used without having been declared in an enclosing scope
+    UndeclaredVariable { name: Identifier },
+    /// A variable is declared more than once in the same scope
+    DuplicateDeclaration { name: Identifier },
+    /// Something other than a variable is assigned to, or incremented or decremented
+    InvalidLvalue,
+    /// A `break` statement isn't inside a loop or `switch`
+    BreakOutsideLoop,
+    /// A `continue` statement isn't inside a loop
+    ContinueOutsideLoop,
+    /// A `case` isn't inside a `switch`
+    CaseOutsideSwitch,
+    /// A `default` isn't inside a `switch`
+    DefaultOutsideSwitch,
+    /// An expression that must be constant (such as the value of a `case`) contains something
+    /// that can't be evaluated at compile time
+    NonConstantExpression,
+    /// An expression that must be constant divides by zero
+    DivisionByZeroInConstant,
+    /// Two cases in the same `switch` have the same value
+    DuplicateCase { value: i64 },
+    /// There's more than one `default` in the same `switch`
+    DuplicateDefault,
+    /// A label is used more than once in the same function
+    DuplicateLabel { label: Identifier },
+    /// A `goto` jumps to a label that isn't in the same function
+    UndefinedLabel { label: Identifier },
+}
+
+impl std::fmt::Display for SemanticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemanticError::UndeclaredVariable { name } => {
+                write!(f, "Use of undeclared variable `{}`", name)
+            }
+            SemanticError::DuplicateDeclaration { name } => {
+                write!(f, "Duplicate declaration of variable `{}`", name)
+            }
+            SemanticError::InvalidLvalue => write!(f, "Invalid lvalue in assignment"),
+            SemanticError::BreakOutsideLoop => {
+                write!(f, "`break` statement not in loop or `switch`")
+            }
+            SemanticError::ContinueOutsideLoop => write!(f, "`continue` statement not in loop"),
+            SemanticError::CaseOutsideSwitch => write!(f, "`case` label not in `switch`"),
+            SemanticError::DefaultOutsideSwitch => write!(f, "`default` label not in `switch`"),
+            SemanticError::NonConstantExpression => {
+                write!(f, "Expression is not an integer constant expression")
+            }
+            SemanticError::DivisionByZeroInConstant => {
+                write!(f, "Division by zero in constant expression")
+            }
+            SemanticError::DuplicateCase { value } => write!(f, "Duplicate `case` value {}", value),
+            SemanticError::DuplicateDefault => write!(f, "Multiple `default` labels in `switch`"),
+            SemanticError::DuplicateLabel { label } => {
+                write!(f, "Duplicate label `{}`", label)
+            }
+            SemanticError::UndefinedLabel { label } => {
+                write!(f, "Use of undefined label `{}`", label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SemanticError {}
+
+/// Problems in C source code that has a meaning, but which are likely to be a mistake
+#[derive(Debug, PartialEq)]
+pub enum Warning {
+    /// A statement in the function can never be executed, such as one following a `return`. The
+    /// position is that of the first unreachable block item, if it was parsed from C source code.
+    UnreachableStatement {
+        function: Identifier,
+        span: Option<Span>,
+    },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::UnreachableStatement {
+                function,
+                span: Some(span),
+            } => write!(
+                f,
+                "{}:{}: Unreachable statement in function `{}`",
+                span.line, span.column, function
+            ),
+            Warning::UnreachableStatement {
+                function,
+                span: None,
+            } => write!(f, "Unreachable statement in function `{}`", function),
+        }
+    }
+}
+
+/// Check the C AST for errors that parsing can't detect, and rewrite it into the form expected by
+/// lowering to IR. Any warnings about the program are returned alongside the rewritten C AST.
+pub fn analyse_program_definition(
+    c_ast: c::ProgramDefinition,
+) -> Result<(c::ProgramDefinition, Vec<Warning>), SemanticError> {
+    let c_ast = variable_resolution::resolve_program_definition(c_ast)?;
+    label_validation::validate_program_definition(&c_ast)?;
+    let c_ast = loop_labeling::label_program_definition(c_ast)?;
+    let warnings = unreachable_code::check_program_definition(&c_ast);
+    Ok((c_ast, warnings))
+}
@@ -0,0 +1,182 @@
+use crate::parse::c::{BinaryOperator, Expression, UnaryOperator};
+use crate::parse::semantics::SemanticError;
+
+/// Evaluate an integer constant expression (such as the value of a `case`), which can't contain
+/// any variables or function calls. Arithmetic is done on `int` values, so wraps in the same way as
+/// it would at runtime.
+pub fn eval_const_expr(node: &Expression) -> Result<i64, SemanticError> {
+    let value = match node {
+        Expression::NumericConstant(val) => *val as i32,
+        Expression::Unary(op, inner) => {
+            let inner = eval_const_expr(inner)? as i32;
+            match op {
+                UnaryOperator::BitwiseComplement => !inner,
+                UnaryOperator::Negation => inner.wrapping_neg(),
+                UnaryOperator::Not => (inner == 0) as i32,
+            }
+        }
+        // As at runtime, the right operand of `&&` and `||` is only evaluated if the result
+        // depends on it, so `0 && (1 / 0)` is a valid constant expression
+        Expression::Binary {
+            op: BinaryOperator::And,
+            left,
+            right,
+        } => (eval_const_expr(left)? != 0 && eval_const_expr(right)? != 0) as i32,
+        Expression::Binary {
+            op: BinaryOperator::Or,
+            left,
+            right,
+        } => (eval_const_expr(left)? != 0 || eval_const_expr(right)? != 0) as i32,
+        Expression::Binary { op, left, right } => {
+            let left = eval_const_expr(left)? as i32;
+            let right = eval_const_expr(right)? as i32;
+            if matches!(op, BinaryOperator::Divide | BinaryOperator::Modulo) && right == 0 {
+                return Err(SemanticError::DivisionByZeroInConstant);
+            }
+            match op {
+                BinaryOperator::Add => left.wrapping_add(right),
+                BinaryOperator::Subtract => left.wrapping_sub(right),
+                BinaryOperator::Multiply => left.wrapping_mul(right),
+                BinaryOperator::Divide => left.wrapping_div(right),
+                BinaryOperator::Modulo => left.wrapping_rem(right),
+                BinaryOperator::ShiftLeft => left.wrapping_shl(right as u32),
+                BinaryOperator::ShiftRight => left.wrapping_shr(right as u32),
+                BinaryOperator::LessThan => (left < right) as i32,
+                BinaryOperator::GreaterThan => (left > right) as i32,
+                BinaryOperator::LessOrEqual => (left <= right) as i32,
+                BinaryOperator::GreaterOrEqual => (left >= right) as i32,
+                BinaryOperator::Equal => (left == right) as i32,
+                BinaryOperator::NotEqual => (left != right) as i32,
+                BinaryOperator::BitwiseAnd => left & right,
+                BinaryOperator::BitwiseXor => left ^ right,
+                BinaryOperator::BitwiseOr => left | right,
+                BinaryOperator::And | BinaryOperator::Or => {
+                    unreachable!("Logical operators are evaluated without evaluating both operands")
+                }
+            }
+        }
+        Expression::Conditional {
+            condition,
+            then,
+            otherwise,
+        } => {
+            if eval_const_expr(condition)? != 0 {
+                eval_const_expr(then)? as i32
+            } else {
+                eval_const_expr(otherwise)? as i32
+            }
+        }
+        Expression::Var(_)
+        | Expression::Assignment { .. }
+        | Expression::CompoundAssignment { .. }
+        | Expression::Prefix { .. }
+        | Expression::Postfix { .. }
+        | Expression::Comma { .. }
+        | Expression::FunctionCall { .. } => return Err(SemanticError::NonConstantExpression),
+    };
+    Ok(value.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::lex::lex;
+    use crate::parse::c::{self, BlockItem, FunctionDefinition, ProgramDefinition, Statement};
+
+    /// Parse an expression by parsing a program that returns it
+    fn parse_expression(source_code: &str) -> Expression {
+        let program = format!("int main(void) {{ return {}; }}", source_code);
+        let mut tokens = VecDeque::from(lex(&program).unwrap());
+        match c::parse_program_definition(&mut tokens).unwrap() {
+            ProgramDefinition::Program(mut func_defns) => match func_defns.remove(0) {
+                FunctionDefinition::Function { body, .. } => match body.unwrap().remove(0) {
+                    BlockItem::Statement(Statement::Return(exp), _) => exp,
+                    item => panic!("Expected return statement, got {:?}", item),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn evaluate_arithmetic_respecting_precedence() {
+        let value = eval_const_expr(&parse_expression("1 + 2 * 3"));
+        assert_eq!(value, Ok(7));
+    }
+
+    #[test]
+    fn evaluate_negated_shift() {
+        let value = eval_const_expr(&parse_expression("-(1 << 4)"));
+        assert_eq!(value, Ok(-16));
+    }
+
+    #[test]
+    fn evaluate_bitwise_complement_of_zero() {
+        let value = eval_const_expr(&parse_expression("~0"));
+        assert_eq!(value, Ok(-1));
+    }
+
+    #[test]
+    fn evaluate_logical_and_relational_operators() {
+        let value = eval_const_expr(&parse_expression("!(2 > 1) || (3 == 3 && 4 != 5)"));
+        assert_eq!(value, Ok(1));
+    }
+
+    #[test]
+    fn evaluate_conditional_expression() {
+        let value = eval_const_expr(&parse_expression("0 ? 1 : 2 ^ 7"));
+        assert_eq!(value, Ok(5));
+    }
+
+    #[test]
+    fn skip_right_operand_of_logical_operator_that_does_not_affect_result() {
+        let cases = [("0 && (1 / 0)", 0), ("1 || (1 / 0)", 1)];
+        for (source_code, expected_value) in cases {
+            let value = eval_const_expr(&parse_expression(source_code));
+            assert_eq!(value, Ok(expected_value));
+        }
+    }
+
+    #[test]
+    fn error_if_right_operand_of_logical_operator_that_affects_result_divides_by_zero() {
+        let res = eval_const_expr(&parse_expression("1 && (1 / 0)"));
+        assert_eq!(res, Err(SemanticError::DivisionByZeroInConstant));
+    }
+
+    #[test]
+    fn evaluate_arithmetic_that_overflows_int_with_wrapping() {
+        let value = eval_const_expr(&parse_expression("2147483647 + 1"));
+        assert_eq!(value, Ok(-2147483648));
+    }
+
+    #[test]
+    fn error_if_division_by_zero() {
+        let res = eval_const_expr(&parse_expression("1 / (2 - 2)"));
+        assert_eq!(res, Err(SemanticError::DivisionByZeroInConstant));
+    }
+
+    #[test]
+    fn error_if_remainder_by_zero() {
+        let res = eval_const_expr(&parse_expression("1 % 0"));
+        assert_eq!(res, Err(SemanticError::DivisionByZeroInConstant));
+    }
+
+    #[test]
+    fn error_if_expression_contains_variable() {
+        let res = eval_const_expr(&parse_expression("1 + a"));
+        assert_eq!(res, Err(SemanticError::NonConstantExpression));
+    }
+
+    #[test]
+    fn error_if_expression_contains_function_call() {
+        let res = eval_const_expr(&parse_expression("foo() * 2"));
+        assert_eq!(res, Err(SemanticError::NonConstantExpression));
+    }
+
+    #[test]
+    fn evaluate_division_of_smallest_int_by_negative_one_with_wrapping() {
+        let value = eval_const_expr(&parse_expression("-2147483648 / -1"));
+        assert_eq!(value, Ok(-2147483648));
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use crate::parse::c::{BlockItem, FunctionDefinition, ProgramDefinition, Statement};
+use crate::parse::semantics::SemanticError;
+use crate::parse::Identifier;
+
+/// Add every label in the statement (including labels of statements nested inside it) to the
+/// labels found so far in the function
+fn collect_labels(node: &Statement, labels: &mut HashSet<Identifier>) -> Result<(), SemanticError> {
+    match node {
+        Statement::If {
+            then, otherwise, ..
+        } => {
+            collect_labels(then, labels)?;
+            match otherwise {
+                Some(otherwise) => collect_labels(otherwise, labels),
+                None => Ok(()),
+            }
+        }
+        Statement::Labeled { label, statement } => {
+            if !labels.insert(label.clone()) {
+                return Err(SemanticError::DuplicateLabel {
+                    label: label.clone(),
+                });
+            }
+            collect_labels(statement, labels)
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Switch { body, .. } => collect_labels(body, labels),
+        Statement::Case { statement, .. } | Statement::Default { statement, .. } => {
+            collect_labels(statement, labels)
+        }
+        Statement::Compound(block_items) => {
+            for statement in block_item_statements(block_items) {
+                collect_labels(statement, labels)?;
+            }
+            Ok(())
+        }
+        Statement::Return(_)
+        | Statement::Expression(_)
+        | Statement::Break(_)
+        | Statement::Continue(_)
+        | Statement::Goto(_)
+        | Statement::Null => Ok(()),
+    }
+}
+
+/// Check that every `goto` in the statement jumps to one of the labels in the function
+fn check_goto_targets(node: &Statement, labels: &HashSet<Identifier>) -> Result<(), SemanticError> {
+    match node {
+        Statement::Goto(label) if !labels.contains(label) => Err(SemanticError::UndefinedLabel {
+            label: label.clone(),
+        }),
+        Statement::If {
+            then, otherwise, ..
+        } => {
+            check_goto_targets(then, labels)?;
+            match otherwise {
+                Some(otherwise) => check_goto_targets(otherwise, labels),
+                None => Ok(()),
+            }
+        }
+        Statement::Labeled { statement, .. } => check_goto_targets(statement, labels),
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Switch { body, .. } => check_goto_targets(body, labels),
+        Statement::Case { statement, .. } | Statement::Default { statement, .. } => {
+            check_goto_targets(statement, labels)
+        }
+        Statement::Compound(block_items) => {
+            for statement in block_item_statements(block_items) {
+                check_goto_targets(statement, labels)?;
+            }
+            Ok(())
+        }
+        Statement::Return(_)
+        | Statement::Expression(_)
+        | Statement::Break(_)
+        | Statement::Continue(_)
+        | Statement::Goto(_)
+        | Statement::Null => Ok(()),
+    }
+}
+
+/// The statements among the block items, skipping any declarations
+fn block_item_statements(block_items: &[BlockItem]) -> impl Iterator<Item = &Statement> {
+    block_items
+        .iter()
+        .filter_map(|block_item| match block_item {
+            BlockItem::Statement(statement, _) => Some(statement),
+            BlockItem::Declaration(..) => None,
+        })
+}
+
+fn validate_function_definition(node: &FunctionDefinition) -> Result<(), SemanticError> {
+    match node {
+        FunctionDefinition::Function { body: None, .. } => Ok(()),
+        FunctionDefinition::Function {
+            body: Some(block_items),
+            ..
+        } => {
+            // A `goto` can jump forwards to a label later in the function, so all labels need to
+            // be known before any `goto` is checked
+            let mut labels = HashSet::new();
+            for statement in block_item_statements(block_items) {
+                collect_labels(statement, &mut labels)?;
+            }
+            for statement in block_item_statements(block_items) {
+                check_goto_targets(statement, &labels)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Check that the labels in each function are distinct, and that every `goto` jumps to a label in
+/// the same function. Labels are in a different namespace to variables, so they aren't affected
+/// by variable resolution.
+pub fn validate_program_definition(node: &ProgramDefinition) -> Result<(), SemanticError> {
+    match node {
+        ProgramDefinition::Program(func_defns) => {
+            func_defns.iter().try_for_each(validate_function_definition)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::lex::lex;
+    use crate::parse::c;
+
+    fn validate(source_code: &str) -> Result<(), SemanticError> {
+        let mut tokens = VecDeque::from(lex(source_code).unwrap());
+        let c_ast = c::parse_program_definition(&mut tokens).unwrap();
+        validate_program_definition(&c_ast)
+    }
+
+    #[test]
+    fn goto_label_defined_later_in_function() {
+        let res = validate("int main(void) { goto end; return 1; end: return 0; }");
+        assert_eq!(res, Ok(()));
+    }
+
+    #[test]
+    fn goto_label_defined_earlier_in_function() {
+        let res = validate("int main(void) { int a = 0; start: a = a + 1; goto start; }");
+        assert_eq!(res, Ok(()));
+    }
+
+    #[test]
+    fn goto_label_nested_inside_loop_and_block() {
+        let res = validate("int main(void) { goto inner; while (1) { { inner: break; } } }");
+        assert_eq!(res, Ok(()));
+    }
+
+    #[test]
+    fn label_with_same_name_as_variable() {
+        let res = validate("int main(void) { int end = 0; goto end; end: return end; }");
+        assert_eq!(res, Ok(()));
+    }
+
+    #[test]
+    fn error_if_goto_label_not_defined() {
+        let res = validate("int main(void) { goto end; return 0; }");
+        let expected_error = SemanticError::UndefinedLabel {
+            label: "end".to_string(),
+        };
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_goto_label_only_defined_in_another_function() {
+        let res = validate("int foo(void) { end: return 0; } int main(void) { goto end; }");
+        let expected_error = SemanticError::UndefinedLabel {
+            label: "end".to_string(),
+        };
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_label_defined_twice_in_function() {
+        let res = validate("int main(void) { end: ; { end: return 0; } }");
+        let expected_error = SemanticError::DuplicateLabel {
+            label: "end".to_string(),
+        };
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn same_label_in_different_functions() {
+        let res = validate("int foo(void) { end: return 1; } int main(void) { end: return 0; }");
+        assert_eq!(res, Ok(()));
+    }
+}
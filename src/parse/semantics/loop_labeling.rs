@@ -0,0 +1,531 @@
+use crate::parse::c::{BlockItem, Expression, FunctionDefinition, ProgramDefinition, Statement};
+use crate::parse::semantics::constant_evaluation::eval_const_expr;
+use crate::parse::semantics::SemanticError;
+use crate::parse::Identifier;
+
+/// The value of each `case` (or `None` for `default`) in a `switch`, and the label of the
+/// statement it jumps to
+type SwitchCases = Vec<(Option<i64>, Identifier)>;
+
+/// The labels of the innermost statements enclosing the statement being labeled
+#[derive(Clone, Copy, Default)]
+struct Enclosing<'a> {
+    /// The innermost loop or `switch`, which a `break` jumps out of
+    break_label: Option<&'a Identifier>,
+    /// The innermost loop, which a `continue` jumps to the next iteration of
+    continue_label: Option<&'a Identifier>,
+}
+
+/// Generate a label that's unique across the whole program. The label contains a period so that it
+/// can't clash with any label in the C source code.
+fn make_label(prefix: &str, id: &mut usize) -> Identifier {
+    let label = format!("{}.{}", prefix, *id);
+    *id += 1;
+    label
+}
+
+/// Add a `case` (or `default`, if `value` is `None`) to the cases of the innermost `switch`
+/// enclosing it
+fn add_switch_case(
+    value: Option<i64>,
+    label: &Identifier,
+    cases: Option<&mut SwitchCases>,
+) -> Result<(), SemanticError> {
+    let Some(cases) = cases else {
+        return Err(match value {
+            Some(_) => SemanticError::CaseOutsideSwitch,
+            None => SemanticError::DefaultOutsideSwitch,
+        });
+    };
+
+    if cases
+        .iter()
+        .any(|(existing_value, _)| *existing_value == value)
+    {
+        return Err(match value {
+            Some(value) => SemanticError::DuplicateCase { value },
+            None => SemanticError::DuplicateDefault,
+        });
+    }
+    cases.push((value, label.clone()));
+    Ok(())
+}
+
+/// Label each loop and `switch` in the statement, annotate each `break` and `continue` with the
+/// label of the statement it refers to, and add each `case` and `default` to the cases of the
+/// innermost `switch` enclosing it (which are `cases`)
+fn label_statement(
+    node: Statement,
+    enclosing: Enclosing,
+    mut cases: Option<&mut SwitchCases>,
+    id: &mut usize,
+) -> Result<Statement, SemanticError> {
+    let labeled = match node {
+        Statement::If {
+            condition,
+            then,
+            otherwise,
+        } => Statement::If {
+            condition,
+            then: Box::new(label_statement(*then, enclosing, cases.as_deref_mut(), id)?),
+            otherwise: match otherwise {
+                Some(otherwise) => {
+                    Some(Box::new(label_statement(*otherwise, enclosing, cases, id)?))
+                }
+                None => None,
+            },
+        },
+        Statement::While {
+            condition, body, ..
+        } => {
+            let label = make_label("loop", id);
+            let enclosing = Enclosing {
+                break_label: Some(&label),
+                continue_label: Some(&label),
+            };
+            Statement::While {
+                condition,
+                body: Box::new(label_statement(*body, enclosing, cases, id)?),
+                label: Some(label),
+            }
+        }
+        Statement::DoWhile {
+            body, condition, ..
+        } => {
+            let label = make_label("loop", id);
+            let enclosing = Enclosing {
+                break_label: Some(&label),
+                continue_label: Some(&label),
+            };
+            Statement::DoWhile {
+                body: Box::new(label_statement(*body, enclosing, cases, id)?),
+                condition,
+                label: Some(label),
+            }
+        }
+        Statement::For {
+            init,
+            condition,
+            post,
+            body,
+            ..
+        } => {
+            let label = make_label("loop", id);
+            let enclosing = Enclosing {
+                break_label: Some(&label),
+                continue_label: Some(&label),
+            };
+            Statement::For {
+                init,
+                condition,
+                post,
+                body: Box::new(label_statement(*body, enclosing, cases, id)?),
+                label: Some(label),
+            }
+        }
+        Statement::Switch {
+            condition, body, ..
+        } => {
+            // A `continue` inside a `switch` refers to the loop enclosing the `switch`, but the
+            // cases of an enclosing `switch` don't include the cases of this one
+            let label = make_label("switch", id);
+            let enclosing = Enclosing {
+                break_label: Some(&label),
+                continue_label: enclosing.continue_label,
+            };
+            let mut switch_cases = Vec::new();
+            let body = label_statement(*body, enclosing, Some(&mut switch_cases), id)?;
+            Statement::Switch {
+                condition,
+                body: Box::new(body),
+                label: Some(label),
+                cases: switch_cases,
+            }
+        }
+        Statement::Case {
+            value, statement, ..
+        } => {
+            let value = eval_const_expr(&value)?;
+            let label = make_label("case", id);
+            add_switch_case(Some(value), &label, cases.as_deref_mut())?;
+            Statement::Case {
+                value: Expression::NumericConstant(value),
+                statement: Box::new(label_statement(*statement, enclosing, cases, id)?),
+                label: Some(label),
+            }
+        }
+        Statement::Default { statement, .. } => {
+            let label = make_label("default", id);
+            add_switch_case(None, &label, cases.as_deref_mut())?;
+            Statement::Default {
+                statement: Box::new(label_statement(*statement, enclosing, cases, id)?),
+                label: Some(label),
+            }
+        }
+        Statement::Break(_) => match enclosing.break_label {
+            Some(label) => Statement::Break(Some(label.clone())),
+            None => return Err(SemanticError::BreakOutsideLoop),
+        },
+        Statement::Continue(_) => match enclosing.continue_label {
+            Some(label) => Statement::Continue(Some(label.clone())),
+            None => return Err(SemanticError::ContinueOutsideLoop),
+        },
+        Statement::Labeled { label, statement } => Statement::Labeled {
+            label,
+            statement: Box::new(label_statement(*statement, enclosing, cases, id)?),
+        },
+        Statement::Compound(block_items) => {
+            Statement::Compound(label_block_items(block_items, enclosing, cases, id)?)
+        }
+        Statement::Return(_) | Statement::Expression(_) | Statement::Goto(_) | Statement::Null => {
+            node
+        }
+    };
+    Ok(labeled)
+}
+
+fn label_block_items(
+    nodes: Vec<BlockItem>,
+    enclosing: Enclosing,
+    mut cases: Option<&mut SwitchCases>,
+    id: &mut usize,
+) -> Result<Vec<BlockItem>, SemanticError> {
+    nodes
+        .into_iter()
+        .map(|node| match node {
+            BlockItem::Statement(statement, span) => Ok(BlockItem::Statement(
+                label_statement(statement, enclosing, cases.as_deref_mut(), id)?,
+                span,
+            )),
+            BlockItem::Declaration(..) => Ok(node),
+        })
+        .collect()
+}
+
+fn label_function_definition(
+    node: FunctionDefinition,
+    id: &mut usize,
+) -> Result<FunctionDefinition, SemanticError> {
+    match node {
+        FunctionDefinition::Function { name, params, body } => {
+            let body = body
+                .map(|block_items| label_block_items(block_items, Enclosing::default(), None, id))
+                .transpose()?;
+            Ok(FunctionDefinition::Function { name, params, body })
+        }
+    }
+}
+
+/// Give every loop and `switch` in the program a label that's unique across the whole program,
+/// annotate every `break` and `continue` with the label of the statement it belongs to, and collect
+/// the cases of every `switch`, so that lowering knows where to jump to
+pub fn label_program_definition(
+    node: ProgramDefinition,
+) -> Result<ProgramDefinition, SemanticError> {
+    let mut id = 0;
+    match node {
+        ProgramDefinition::Program(func_defns) => Ok(ProgramDefinition::Program(
+            func_defns
+                .into_iter()
+                .map(|func_defn| label_function_definition(func_defn, &mut id))
+                .collect::<Result<_, _>>()?,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::lex::{lex, Span};
+    use crate::parse::c::{self, Expression, ForInit};
+
+    /// The position of a block item in source code that's all on the first line
+    fn span_at(column: usize) -> Option<Span> {
+        Some(Span { line: 1, column })
+    }
+
+    /// Parse a program and get the block items in the body of its only function
+    fn label_main_function_body(source_code: &str) -> Result<Vec<BlockItem>, SemanticError> {
+        let mut tokens = VecDeque::from(lex(source_code).unwrap());
+        let c_ast = c::parse_program_definition(&mut tokens).unwrap();
+        match label_program_definition(c_ast)? {
+            ProgramDefinition::Program(mut func_defns) => match func_defns.remove(0) {
+                FunctionDefinition::Function { body, .. } => Ok(body.unwrap()),
+            },
+        }
+    }
+
+    #[test]
+    fn break_in_nested_loops_refers_to_innermost_loop() {
+        let block_items = label_main_function_body(
+            "int main(void) { while (1) { do break; while (1); break; } return 0; }",
+        )
+        .unwrap();
+        let expected_block_items = vec![
+            BlockItem::Statement(
+                Statement::While {
+                    condition: Expression::NumericConstant(1),
+                    body: Box::new(Statement::Compound(vec![
+                        BlockItem::Statement(
+                            Statement::DoWhile {
+                                body: Box::new(Statement::Break(Some("loop.1".to_string()))),
+                                condition: Expression::NumericConstant(1),
+                                label: Some("loop.1".to_string()),
+                            },
+                            span_at(30),
+                        ),
+                        BlockItem::Statement(
+                            Statement::Break(Some("loop.0".to_string())),
+                            span_at(51),
+                        ),
+                    ])),
+                    label: Some("loop.0".to_string()),
+                },
+                span_at(18),
+            ),
+            BlockItem::Statement(
+                Statement::Return(Expression::NumericConstant(0)),
+                span_at(60),
+            ),
+        ];
+        assert_eq!(block_items, expected_block_items);
+    }
+
+    #[test]
+    fn continue_in_for_loop_refers_to_for_loop() {
+        let block_items =
+            label_main_function_body("int main(void) { for (;;) continue; return 0; }").unwrap();
+        let expected_block_items = vec![
+            BlockItem::Statement(
+                Statement::For {
+                    init: ForInit::InitExp(None),
+                    condition: None,
+                    post: None,
+                    body: Box::new(Statement::Continue(Some("loop.0".to_string()))),
+                    label: Some("loop.0".to_string()),
+                },
+                span_at(18),
+            ),
+            BlockItem::Statement(
+                Statement::Return(Expression::NumericConstant(0)),
+                span_at(37),
+            ),
+        ];
+        assert_eq!(block_items, expected_block_items);
+    }
+
+    #[test]
+    fn loops_in_different_functions_have_distinct_labels() {
+        let mut tokens = VecDeque::from(
+            lex("int foo(void) { while (1) break; } int main(void) { while (1) break; }").unwrap(),
+        );
+        let c_ast = c::parse_program_definition(&mut tokens).unwrap();
+        let labels = match label_program_definition(c_ast).unwrap() {
+            ProgramDefinition::Program(func_defns) => func_defns
+                .into_iter()
+                .map(|func_defn| match func_defn {
+                    FunctionDefinition::Function { mut body, .. } => {
+                        match body.as_mut().unwrap().remove(0) {
+                            BlockItem::Statement(Statement::While { label, .. }, _) => {
+                                label.unwrap()
+                            }
+                            item => panic!("Expected while loop, got {:?}", item),
+                        }
+                    }
+                })
+                .collect::<Vec<_>>(),
+        };
+        assert_eq!(labels, vec!["loop.0".to_string(), "loop.1".to_string()]);
+    }
+
+    #[test]
+    fn error_if_break_outside_loop() {
+        let res = label_main_function_body("int main(void) { break; return 0; }");
+        assert_eq!(res, Err(SemanticError::BreakOutsideLoop));
+    }
+
+    #[test]
+    fn error_if_continue_outside_loop() {
+        let res = label_main_function_body("int main(void) { { continue; } return 0; }");
+        assert_eq!(res, Err(SemanticError::ContinueOutsideLoop));
+    }
+
+    #[test]
+    fn error_if_break_after_loop_has_ended() {
+        let res = label_main_function_body("int main(void) { while (1) ; break; }");
+        assert_eq!(res, Err(SemanticError::BreakOutsideLoop));
+    }
+
+    #[test]
+    fn collect_cases_of_switch_and_label_break_with_switch() {
+        let block_items = label_main_function_body(
+            "int main(void) { switch (1) { case 1: break; default: return 2; } return 0; }",
+        )
+        .unwrap();
+        let expected_block_items = vec![
+            BlockItem::Statement(
+                Statement::Switch {
+                    condition: Expression::NumericConstant(1),
+                    body: Box::new(Statement::Compound(vec![
+                        BlockItem::Statement(
+                            Statement::Case {
+                                value: Expression::NumericConstant(1),
+                                statement: Box::new(Statement::Break(Some("switch.0".to_string()))),
+                                label: Some("case.1".to_string()),
+                            },
+                            span_at(31),
+                        ),
+                        BlockItem::Statement(
+                            Statement::Default {
+                                statement: Box::new(Statement::Return(
+                                    Expression::NumericConstant(2),
+                                )),
+                                label: Some("default.2".to_string()),
+                            },
+                            span_at(46),
+                        ),
+                    ])),
+                    label: Some("switch.0".to_string()),
+                    cases: vec![
+                        (Some(1), "case.1".to_string()),
+                        (None, "default.2".to_string()),
+                    ],
+                },
+                span_at(18),
+            ),
+            BlockItem::Statement(
+                Statement::Return(Expression::NumericConstant(0)),
+                span_at(67),
+            ),
+        ];
+        assert_eq!(block_items, expected_block_items);
+    }
+
+    #[test]
+    fn case_value_is_evaluated_as_constant_expression() {
+        let block_items =
+            label_main_function_body("int main(void) { switch (4) case 2 * 3 - 4 : ; }").unwrap();
+        match &block_items[0] {
+            BlockItem::Statement(Statement::Switch { body, cases, .. }, _) => {
+                assert_eq!(*cases, vec![(Some(2), "case.1".to_string())]);
+                let expected_body = Statement::Case {
+                    value: Expression::NumericConstant(2),
+                    statement: Box::new(Statement::Null),
+                    label: Some("case.1".to_string()),
+                };
+                assert_eq!(**body, expected_body);
+            }
+            item => panic!("Expected switch statement, got {:?}", item),
+        }
+    }
+
+    #[test]
+    fn continue_in_switch_refers_to_enclosing_loop() {
+        let block_items = label_main_function_body(
+            "int main(void) { while (1) switch (1) { default: continue; } }",
+        )
+        .unwrap();
+        let expected_block_items = vec![BlockItem::Statement(
+            Statement::While {
+                condition: Expression::NumericConstant(1),
+                body: Box::new(Statement::Switch {
+                    condition: Expression::NumericConstant(1),
+                    body: Box::new(Statement::Compound(vec![BlockItem::Statement(
+                        Statement::Default {
+                            statement: Box::new(Statement::Continue(Some("loop.0".to_string()))),
+                            label: Some("default.2".to_string()),
+                        },
+                        span_at(41),
+                    )])),
+                    label: Some("switch.1".to_string()),
+                    cases: vec![(None, "default.2".to_string())],
+                }),
+                label: Some("loop.0".to_string()),
+            },
+            span_at(18),
+        )];
+        assert_eq!(block_items, expected_block_items);
+    }
+
+    #[test]
+    fn cases_of_nested_switch_are_not_cases_of_outer_switch() {
+        let block_items = label_main_function_body(
+            "int main(void) { switch (1) { case 1: switch (2) { case 1: default: ; } default: ; } }",
+        )
+        .unwrap();
+        let (outer_cases, inner_cases) = match &block_items[0] {
+            BlockItem::Statement(Statement::Switch { body, cases, .. }, _) => match &**body {
+                Statement::Compound(items) => match &items[0] {
+                    BlockItem::Statement(Statement::Case { statement, .. }, _) => {
+                        match &**statement {
+                            Statement::Switch {
+                                cases: inner_cases, ..
+                            } => (cases, inner_cases),
+                            statement => panic!("Expected switch statement, got {:?}", statement),
+                        }
+                    }
+                    item => panic!("Expected case, got {:?}", item),
+                },
+                statement => panic!("Expected compound statement, got {:?}", statement),
+            },
+            item => panic!("Expected switch statement, got {:?}", item),
+        };
+        assert_eq!(
+            *outer_cases,
+            vec![
+                (Some(1), "case.1".to_string()),
+                (None, "default.5".to_string())
+            ]
+        );
+        assert_eq!(
+            *inner_cases,
+            vec![
+                (Some(1), "case.3".to_string()),
+                (None, "default.4".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn error_if_duplicate_case_values_in_switch() {
+        let res = label_main_function_body(
+            "int main(void) { switch (1) { case 3: ; case 4: ; case 3: ; } }",
+        );
+        assert_eq!(res, Err(SemanticError::DuplicateCase { value: 3 }));
+    }
+
+    #[test]
+    fn error_if_case_values_are_duplicates_after_constant_evaluation() {
+        let res =
+            label_main_function_body("int main(void) { switch (1) { case 1 + 1: ; case 2: ; } }");
+        assert_eq!(res, Err(SemanticError::DuplicateCase { value: 2 }));
+    }
+
+    #[test]
+    fn error_if_multiple_defaults_in_switch() {
+        let res =
+            label_main_function_body("int main(void) { switch (1) { default: ; default: ; } }");
+        assert_eq!(res, Err(SemanticError::DuplicateDefault));
+    }
+
+    #[test]
+    fn error_if_case_outside_switch() {
+        let res = label_main_function_body("int main(void) { while (1) { case 1: break; } }");
+        assert_eq!(res, Err(SemanticError::CaseOutsideSwitch));
+    }
+
+    #[test]
+    fn error_if_default_outside_switch() {
+        let res = label_main_function_body("int main(void) { default: return 0; }");
+        assert_eq!(res, Err(SemanticError::DefaultOutsideSwitch));
+    }
+
+    #[test]
+    fn error_if_case_value_is_not_constant() {
+        let res =
+            label_main_function_body("int main(void) { int a = 1; switch (a) { case a: ; } }");
+        assert_eq!(res, Err(SemanticError::NonConstantExpression));
+    }
+}
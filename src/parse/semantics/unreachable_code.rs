@@ -0,0 +1,256 @@
+use crate::parse::c::{BlockItem, FunctionDefinition, ProgramDefinition, Statement};
+use crate::parse::semantics::Warning;
+use crate::parse::Identifier;
+
+/// Whether the statement is, or contains, a labeled statement, and so can be reached by jumping
+/// into it even if the statements before it can't reach it. The `case` and `default` labels of a
+/// nested `switch` can only be jumped to from that `switch`, so they don't count.
+fn is_jump_target(node: &Statement, include_cases: bool) -> bool {
+    match node {
+        Statement::Labeled { .. } => true,
+        Statement::Case { statement, .. } | Statement::Default { statement, .. } => {
+            include_cases || is_jump_target(statement, include_cases)
+        }
+        Statement::Compound(block_items) => block_items.iter().any(|block_item| {
+            matches!(
+                block_item,
+                BlockItem::Statement(statement, _) if is_jump_target(statement, include_cases)
+            )
+        }),
+        Statement::If {
+            then, otherwise, ..
+        } => {
+            is_jump_target(then, include_cases)
+                || otherwise
+                    .as_ref()
+                    .is_some_and(|otherwise| is_jump_target(otherwise, include_cases))
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. } => is_jump_target(body, include_cases),
+        Statement::Switch { body, .. } => is_jump_target(body, false),
+        Statement::Return(_)
+        | Statement::Expression(_)
+        | Statement::Break(_)
+        | Statement::Continue(_)
+        | Statement::Goto(_)
+        | Statement::Null => false,
+    }
+}
+
+/// Check for unreachable statements nested inside the statement, given whether execution can reach
+/// the start of the statement, returning whether execution can continue to whatever follows the
+/// statement
+fn check_statement(
+    node: &Statement,
+    reachable: bool,
+    function: &Identifier,
+    warnings: &mut Vec<Warning>,
+) -> bool {
+    match node {
+        Statement::Return(_)
+        | Statement::Goto(_)
+        | Statement::Break(_)
+        | Statement::Continue(_) => false,
+        Statement::If {
+            then, otherwise, ..
+        } => {
+            let then_continues = check_statement(then, reachable, function, warnings);
+            let otherwise_continues = match otherwise {
+                Some(otherwise) => check_statement(otherwise, reachable, function, warnings),
+                None => reachable,
+            };
+            then_continues || otherwise_continues
+        }
+        Statement::Compound(block_items) => {
+            check_block_items(block_items, reachable, function, warnings)
+        }
+        Statement::Labeled { statement, .. }
+        | Statement::Case { statement, .. }
+        | Statement::Default { statement, .. } => {
+            check_statement(statement, true, function, warnings)
+        }
+        // Whether a loop (or `switch`) finishes depends on its condition, so execution is assumed
+        // to be able to continue after it if it can be reached at all
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Switch { body, .. } => {
+            _ = check_statement(body, reachable, function, warnings);
+            reachable || is_jump_target(node, true)
+        }
+        Statement::Expression(_) | Statement::Null => reachable,
+    }
+}
+
+/// Check for block items that follow a statement which never lets execution continue to the next
+/// block item (such as a `return`), given whether execution can reach the first block item,
+/// returning whether execution can reach the end of the block items. Only the first unreachable
+/// block item in each run of them is warned about, and a block item containing a label is checked
+/// for unreachable block items before the label instead.
+fn check_block_items(
+    nodes: &[BlockItem],
+    reachable: bool,
+    function: &Identifier,
+    warnings: &mut Vec<Warning>,
+) -> bool {
+    let mut reachable = reachable;
+    let mut warned = false;
+
+    for node in nodes {
+        match node {
+            BlockItem::Statement(statement, _) if reachable || is_jump_target(statement, true) => {
+                warned = false;
+                reachable = check_statement(statement, reachable, function, warnings);
+            }
+            BlockItem::Declaration(..) | BlockItem::Statement(..) if reachable => (),
+            BlockItem::Statement(_, span) | BlockItem::Declaration(_, span) => {
+                if !warned {
+                    warnings.push(Warning::UnreachableStatement {
+                        function: function.clone(),
+                        span: *span,
+                    });
+                    warned = true;
+                }
+            }
+        }
+    }
+
+    reachable
+}
+
+fn check_function_definition(node: &FunctionDefinition, warnings: &mut Vec<Warning>) {
+    match node {
+        FunctionDefinition::Function {
+            name,
+            body: Some(block_items),
+            ..
+        } => {
+            _ = check_block_items(block_items, true, name, warnings);
+        }
+        FunctionDefinition::Function { body: None, .. } => (),
+    }
+}
+
+/// Find statements that can never be executed, which are valid C but are likely to be a mistake
+pub fn check_program_definition(node: &ProgramDefinition) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    match node {
+        ProgramDefinition::Program(func_defns) => {
+            for func_defn in func_defns {
+                check_function_definition(func_defn, &mut warnings);
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::lex::{lex, Span};
+    use crate::parse::c;
+
+    fn check(source_code: &str) -> Vec<Warning> {
+        let mut tokens = VecDeque::from(lex(source_code).unwrap());
+        let c_ast = c::parse_program_definition(&mut tokens).unwrap();
+        check_program_definition(&c_ast)
+    }
+
+    fn unreachable_statement_in(function: &str, column: usize) -> Warning {
+        Warning::UnreachableStatement {
+            function: function.to_string(),
+            span: Some(Span { line: 1, column }),
+        }
+    }
+
+    #[test]
+    fn warn_about_statement_after_return_in_function_body() {
+        let warnings = check("int main(void) { return 1; return 2; }");
+        assert_eq!(warnings, vec![unreachable_statement_in("main", 28)]);
+    }
+
+    #[test]
+    fn warn_once_about_several_statements_after_return() {
+        let warnings = check("int main(void) { return 1; 2; int a = 3; return a; }");
+        assert_eq!(warnings, vec![unreachable_statement_in("main", 28)]);
+    }
+
+    #[test]
+    fn warn_about_statement_after_return_in_nested_block() {
+        let warnings = check("int foo(void) { { return 1; 2; } }");
+        assert_eq!(warnings, vec![unreachable_statement_in("foo", 29)]);
+    }
+
+    #[test]
+    fn warn_about_statement_after_block_that_always_returns() {
+        let warnings = check("int main(void) { { return 1; } return 2; }");
+        assert_eq!(warnings, vec![unreachable_statement_in("main", 32)]);
+    }
+
+    #[test]
+    fn warn_about_statement_after_if_with_both_branches_returning() {
+        let warnings = check("int main(void) { if (1) return 1; else return 2; return 3; }");
+        assert_eq!(warnings, vec![unreachable_statement_in("main", 50)]);
+    }
+
+    #[test]
+    fn no_warning_if_return_in_only_one_branch_of_if() {
+        let warnings = check("int main(void) { if (1) return 1; return 2; }");
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn warn_about_statement_after_goto_but_not_labeled_statement_after_it() {
+        let warnings = check("int main(void) { goto end; return 1; end: return 2; }");
+        assert_eq!(warnings, vec![unreachable_statement_in("main", 28)]);
+    }
+
+    #[test]
+    fn no_warning_for_case_after_break() {
+        let warnings = check("int main(void) { switch (1) { case 1: break; case 2: return 2; } }");
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn no_warning_for_statement_after_loop_containing_return() {
+        let warnings = check("int main(void) { while (1) return 1; return 2; }");
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn no_warning_for_block_after_return_containing_label_jumped_to() {
+        let warnings = check("int main(void) { int x; return 0; { lbl: x = 1; } goto lbl; }");
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn warn_about_statement_before_label_nested_in_unreachable_statement() {
+        let warnings =
+            check("int main(void) { return 0; if (1) { 1; lbl: return 1; } else return 2; 3; }");
+        assert_eq!(
+            warnings,
+            vec![
+                unreachable_statement_in("main", 37),
+                unreachable_statement_in("main", 72)
+            ]
+        );
+    }
+
+    #[test]
+    fn warn_about_switch_after_return_despite_its_cases() {
+        let warnings = check("int main(void) { return 0; switch (1) { case 1: return 1; } }");
+        assert_eq!(warnings, vec![unreachable_statement_in("main", 28)]);
+    }
+
+    #[test]
+    fn print_line_and_column_of_unreachable_statement() {
+        let warning = unreachable_statement_in("main", 28);
+        assert_eq!(
+            warning.to_string(),
+            "1:28: Unreachable statement in function `main`"
+        );
+    }
+}
@@ -0,0 +1,684 @@
+use std::collections::HashMap;
+
+use crate::parse::c::{
+    BlockItem, Declaration, Expression, ForInit, FunctionDefinition, ProgramDefinition, Statement,
+};
+use crate::parse::semantics::SemanticError;
+use crate::parse::Identifier;
+
+/// For each scope that encloses the code being resolved, a map from the name of each variable
+/// declared in the scope to the unique name it's renamed to. The innermost scope is last.
+type Scopes = Vec<HashMap<Identifier, Identifier>>;
+
+/// Generate a name for a variable that's unique across the whole program. The name contains a
+/// period so that it can't clash with any identifier in the C source code.
+fn make_unique_name(name: &str, id: &mut usize) -> Identifier {
+    let unique_name = format!("{}.{}", name, *id);
+    *id += 1;
+    unique_name
+}
+
+/// Add a variable to the innermost scope, returning the unique name it's renamed to
+///
+/// Declaring a variable that's already declared in an outer scope is fine (it shadows the outer
+/// one), but declaring it twice in the same scope is an error.
+fn declare_variable(
+    name: Identifier,
+    scopes: &mut Scopes,
+    id: &mut usize,
+) -> Result<Identifier, SemanticError> {
+    let scope = scopes
+        .last_mut()
+        .expect("Should always be at least one scope");
+    if scope.contains_key(&name) {
+        return Err(SemanticError::DuplicateDeclaration { name });
+    }
+    let unique_name = make_unique_name(&name, id);
+    scope.insert(name, unique_name.clone());
+    Ok(unique_name)
+}
+
+/// Find the unique name of the variable that the name refers to, searching from the innermost
+/// scope outwards
+fn resolve_variable(name: Identifier, scopes: &Scopes) -> Result<Identifier, SemanticError> {
+    match scopes.iter().rev().find_map(|scope| scope.get(&name)) {
+        Some(unique_name) => Ok(unique_name.clone()),
+        None => Err(SemanticError::UndeclaredVariable { name }),
+    }
+}
+
+fn resolve_expression(node: Expression, scopes: &Scopes) -> Result<Expression, SemanticError> {
+    let resolved = match node {
+        Expression::NumericConstant(_) => node,
+        Expression::Var(name) => Expression::Var(resolve_variable(name, scopes)?),
+        Expression::Unary(op, inner) => {
+            Expression::Unary(op, Box::new(resolve_expression(*inner, scopes)?))
+        }
+        Expression::Binary { op, left, right } => Expression::Binary {
+            op,
+            left: Box::new(resolve_expression(*left, scopes)?),
+            right: Box::new(resolve_expression(*right, scopes)?),
+        },
+        Expression::Assignment { lhs, rhs } => Expression::Assignment {
+            lhs: Box::new(resolve_lvalue(*lhs, scopes)?),
+            rhs: Box::new(resolve_expression(*rhs, scopes)?),
+        },
+        Expression::CompoundAssignment { op, lhs, rhs } => Expression::CompoundAssignment {
+            op,
+            lhs: Box::new(resolve_lvalue(*lhs, scopes)?),
+            rhs: Box::new(resolve_expression(*rhs, scopes)?),
+        },
+        Expression::Prefix { op, operand } => Expression::Prefix {
+            op,
+            operand: Box::new(resolve_lvalue(*operand, scopes)?),
+        },
+        Expression::Postfix { op, operand } => Expression::Postfix {
+            op,
+            operand: Box::new(resolve_lvalue(*operand, scopes)?),
+        },
+        Expression::Conditional {
+            condition,
+            then,
+            otherwise,
+        } => Expression::Conditional {
+            condition: Box::new(resolve_expression(*condition, scopes)?),
+            then: Box::new(resolve_expression(*then, scopes)?),
+            otherwise: Box::new(resolve_expression(*otherwise, scopes)?),
+        },
+        Expression::Comma { left, right } => Expression::Comma {
+            left: Box::new(resolve_expression(*left, scopes)?),
+            right: Box::new(resolve_expression(*right, scopes)?),
+        },
+        // Function names aren't variables, so only the arguments are resolved
+        Expression::FunctionCall { name, args } => Expression::FunctionCall {
+            name,
+            args: args
+                .into_iter()
+                .map(|arg| resolve_expression(arg, scopes))
+                .collect::<Result<_, _>>()?,
+        },
+    };
+    Ok(resolved)
+}
+
+/// Resolve an expression that's assigned to, which must be a variable
+fn resolve_lvalue(node: Expression, scopes: &Scopes) -> Result<Expression, SemanticError> {
+    match node {
+        Expression::Var(name) => Ok(Expression::Var(resolve_variable(name, scopes)?)),
+        _ => Err(SemanticError::InvalidLvalue),
+    }
+}
+
+fn resolve_optional_expression(
+    node: Option<Expression>,
+    scopes: &Scopes,
+) -> Result<Option<Expression>, SemanticError> {
+    node.map(|exp| resolve_expression(exp, scopes)).transpose()
+}
+
+fn resolve_declaration(
+    node: Declaration,
+    scopes: &mut Scopes,
+    id: &mut usize,
+) -> Result<Declaration, SemanticError> {
+    match node {
+        Declaration::Declaration { name, init } => {
+            // The variable is in scope in its own initializer, so is declared before the
+            // initializer is resolved
+            let name = declare_variable(name, scopes, id)?;
+            let init = resolve_optional_expression(init, scopes)?;
+            Ok(Declaration::Declaration { name, init })
+        }
+    }
+}
+
+fn resolve_statement(
+    node: Statement,
+    scopes: &mut Scopes,
+    id: &mut usize,
+) -> Result<Statement, SemanticError> {
+    let resolved = match node {
+        Statement::Return(exp) => Statement::Return(resolve_expression(exp, scopes)?),
+        Statement::Expression(exp) => Statement::Expression(resolve_expression(exp, scopes)?),
+        Statement::If {
+            condition,
+            then,
+            otherwise,
+        } => Statement::If {
+            condition: resolve_expression(condition, scopes)?,
+            then: Box::new(resolve_statement(*then, scopes, id)?),
+            otherwise: match otherwise {
+                Some(otherwise) => Some(Box::new(resolve_statement(*otherwise, scopes, id)?)),
+                None => None,
+            },
+        },
+        Statement::While {
+            condition,
+            body,
+            label,
+        } => Statement::While {
+            condition: resolve_expression(condition, scopes)?,
+            body: Box::new(resolve_statement(*body, scopes, id)?),
+            label,
+        },
+        Statement::DoWhile {
+            body,
+            condition,
+            label,
+        } => Statement::DoWhile {
+            body: Box::new(resolve_statement(*body, scopes, id)?),
+            condition: resolve_expression(condition, scopes)?,
+            label,
+        },
+        Statement::For {
+            init,
+            condition,
+            post,
+            body,
+            label,
+        } => {
+            // A declaration in the initial clause is only in scope for the loop
+            scopes.push(HashMap::new());
+            let init = match init {
+                ForInit::InitDecl(decl) => {
+                    ForInit::InitDecl(resolve_declaration(decl, scopes, id)?)
+                }
+                ForInit::InitExp(exp) => {
+                    ForInit::InitExp(resolve_optional_expression(exp, scopes)?)
+                }
+            };
+            let statement = Statement::For {
+                init,
+                condition: resolve_optional_expression(condition, scopes)?,
+                post: resolve_optional_expression(post, scopes)?,
+                body: Box::new(resolve_statement(*body, scopes, id)?),
+                label,
+            };
+            scopes.pop();
+            statement
+        }
+        Statement::Switch {
+            condition,
+            body,
+            label,
+            cases,
+        } => Statement::Switch {
+            condition: resolve_expression(condition, scopes)?,
+            body: Box::new(resolve_statement(*body, scopes, id)?),
+            label,
+            cases,
+        },
+        Statement::Case {
+            value,
+            statement,
+            label,
+        } => Statement::Case {
+            value: resolve_expression(value, scopes)?,
+            statement: Box::new(resolve_statement(*statement, scopes, id)?),
+            label,
+        },
+        Statement::Default { statement, label } => Statement::Default {
+            statement: Box::new(resolve_statement(*statement, scopes, id)?),
+            label,
+        },
+        Statement::Break(_) | Statement::Continue(_) | Statement::Goto(_) | Statement::Null => node,
+        Statement::Labeled { label, statement } => Statement::Labeled {
+            label,
+            statement: Box::new(resolve_statement(*statement, scopes, id)?),
+        },
+        Statement::Compound(block_items) => {
+            scopes.push(HashMap::new());
+            let block_items = resolve_block_items(block_items, scopes, id)?;
+            scopes.pop();
+            Statement::Compound(block_items)
+        }
+    };
+    Ok(resolved)
+}
+
+fn resolve_block_items(
+    nodes: Vec<BlockItem>,
+    scopes: &mut Scopes,
+    id: &mut usize,
+) -> Result<Vec<BlockItem>, SemanticError> {
+    nodes
+        .into_iter()
+        .map(|node| match node {
+            BlockItem::Statement(statement, span) => Ok(BlockItem::Statement(
+                resolve_statement(statement, scopes, id)?,
+                span,
+            )),
+            BlockItem::Declaration(decl, span) => Ok(BlockItem::Declaration(
+                resolve_declaration(decl, scopes, id)?,
+                span,
+            )),
+        })
+        .collect()
+}
+
+fn resolve_function_definition(
+    node: FunctionDefinition,
+    id: &mut usize,
+) -> Result<FunctionDefinition, SemanticError> {
+    match node {
+        FunctionDefinition::Function { name, params, body } => {
+            // The parameters are in the same scope as the outermost block of the function body
+            let mut scopes = vec![HashMap::new()];
+            let params = params
+                .into_iter()
+                .map(|param| declare_variable(param, &mut scopes, id))
+                .collect::<Result<_, _>>()?;
+            let body = body
+                .map(|block_items| resolve_block_items(block_items, &mut scopes, id))
+                .transpose()?;
+            Ok(FunctionDefinition::Function { name, params, body })
+        }
+    }
+}
+
+/// Rename every variable in the program to a name that's unique across the whole program, so that
+/// later stages don't need to be aware of scopes
+pub fn resolve_program_definition(
+    node: ProgramDefinition,
+) -> Result<ProgramDefinition, SemanticError> {
+    let mut id = 0;
+    match node {
+        ProgramDefinition::Program(func_defns) => Ok(ProgramDefinition::Program(
+            func_defns
+                .into_iter()
+                .map(|func_defn| resolve_function_definition(func_defn, &mut id))
+                .collect::<Result<_, _>>()?,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::lex::{lex, Span};
+    use crate::parse::{c, ir};
+
+    /// The position of a block item in source code that's all on the first line
+    fn span_at(column: usize) -> Option<Span> {
+        Some(Span { line: 1, column })
+    }
+
+    fn parse(source_code: &str) -> ProgramDefinition {
+        let mut tokens = VecDeque::from(lex(source_code).unwrap());
+        c::parse_program_definition(&mut tokens).unwrap()
+    }
+
+    fn main_function(body: Vec<BlockItem>) -> ProgramDefinition {
+        ProgramDefinition::Program(vec![FunctionDefinition::Function {
+            name: "main".to_string(),
+            params: vec![],
+            body: Some(body),
+        }])
+    }
+
+    fn declaration(name: &str, value: i64, column: usize) -> BlockItem {
+        BlockItem::Declaration(
+            Declaration::Declaration {
+                name: name.to_string(),
+                init: Some(Expression::NumericConstant(value)),
+            },
+            span_at(column),
+        )
+    }
+
+    fn var(name: &str) -> Box<Expression> {
+        Box::new(Expression::Var(name.to_string()))
+    }
+
+    #[test]
+    fn resolve_two_distinct_variables_to_distinct_unique_names() {
+        let c_ast = parse("int main(void) { int a = 1; int b = 2; return a + b; }");
+        let expected_c_ast = main_function(vec![
+            declaration("a.0", 1, 18),
+            declaration("b.1", 2, 29),
+            BlockItem::Statement(
+                Statement::Return(Expression::Binary {
+                    op: c::BinaryOperator::Add,
+                    left: var("a.0"),
+                    right: var("b.1"),
+                }),
+                span_at(40),
+            ),
+        ]);
+        let resolved_c_ast = resolve_program_definition(c_ast).unwrap();
+        assert_eq!(resolved_c_ast, expected_c_ast);
+    }
+
+    #[test]
+    fn variable_declared_in_nested_block_shadows_outer_variable() {
+        let c_ast = parse("int main(void) { int a = 1; { int a = 2; a = 3; } return a; }");
+        let expected_c_ast = main_function(vec![
+            declaration("a.0", 1, 18),
+            BlockItem::Statement(
+                Statement::Compound(vec![
+                    declaration("a.1", 2, 31),
+                    BlockItem::Statement(
+                        Statement::Expression(Expression::Assignment {
+                            lhs: var("a.1"),
+                            rhs: Box::new(Expression::NumericConstant(3)),
+                        }),
+                        span_at(42),
+                    ),
+                ]),
+                span_at(29),
+            ),
+            BlockItem::Statement(
+                Statement::Return(Expression::Var("a.0".to_string())),
+                span_at(51),
+            ),
+        ]);
+        let resolved_c_ast = resolve_program_definition(c_ast).unwrap();
+        assert_eq!(resolved_c_ast, expected_c_ast);
+    }
+
+    #[test]
+    fn variable_is_in_scope_in_its_own_initializer() {
+        let c_ast = parse("int main(void) { int a = 1; { int a = a; } return a; }");
+        let expected_c_ast = main_function(vec![
+            declaration("a.0", 1, 18),
+            BlockItem::Statement(
+                Statement::Compound(vec![BlockItem::Declaration(
+                    Declaration::Declaration {
+                        name: "a.1".to_string(),
+                        init: Some(Expression::Var("a.1".to_string())),
+                    },
+                    span_at(31),
+                )]),
+                span_at(29),
+            ),
+            BlockItem::Statement(
+                Statement::Return(Expression::Var("a.0".to_string())),
+                span_at(44),
+            ),
+        ]);
+        let resolved_c_ast = resolve_program_definition(c_ast).unwrap();
+        assert_eq!(resolved_c_ast, expected_c_ast);
+    }
+
+    #[test]
+    fn resolve_function_parameters_and_call_arguments() {
+        let c_ast =
+            parse("int foo(int a) { return a; } int main(void) { int a = 1; return foo(a); }");
+        let expected_c_ast = ProgramDefinition::Program(vec![
+            FunctionDefinition::Function {
+                name: "foo".to_string(),
+                params: vec!["a.0".to_string()],
+                body: Some(vec![BlockItem::Statement(
+                    Statement::Return(Expression::Var("a.0".to_string())),
+                    span_at(18),
+                )]),
+            },
+            FunctionDefinition::Function {
+                name: "main".to_string(),
+                params: vec![],
+                body: Some(vec![
+                    declaration("a.1", 1, 47),
+                    BlockItem::Statement(
+                        Statement::Return(Expression::FunctionCall {
+                            name: "foo".to_string(),
+                            args: vec![Expression::Var("a.1".to_string())],
+                        }),
+                        span_at(58),
+                    ),
+                ]),
+            },
+        ]);
+        let resolved_c_ast = resolve_program_definition(c_ast).unwrap();
+        assert_eq!(resolved_c_ast, expected_c_ast);
+    }
+
+    #[test]
+    fn variable_declared_in_for_loop_init_clause_shadows_outer_variable() {
+        let c_ast =
+            parse("int main(void) { int i = 5; for (int i = 0; i < 3; i += 1) ; return i; }");
+        let expected_c_ast = main_function(vec![
+            declaration("i.0", 5, 18),
+            BlockItem::Statement(
+                Statement::For {
+                    init: ForInit::InitDecl(Declaration::Declaration {
+                        name: "i.1".to_string(),
+                        init: Some(Expression::NumericConstant(0)),
+                    }),
+                    condition: Some(Expression::Binary {
+                        op: c::BinaryOperator::LessThan,
+                        left: var("i.1"),
+                        right: Box::new(Expression::NumericConstant(3)),
+                    }),
+                    post: Some(Expression::CompoundAssignment {
+                        op: c::BinaryOperator::Add,
+                        lhs: var("i.1"),
+                        rhs: Box::new(Expression::NumericConstant(1)),
+                    }),
+                    body: Box::new(Statement::Null),
+                    label: None,
+                },
+                span_at(29),
+            ),
+            BlockItem::Statement(
+                Statement::Return(Expression::Var("i.0".to_string())),
+                span_at(62),
+            ),
+        ]);
+        let resolved_c_ast = resolve_program_definition(c_ast).unwrap();
+        assert_eq!(resolved_c_ast, expected_c_ast);
+    }
+
+    #[test]
+    fn resolved_program_lowers_to_same_ir_as_unresolved_program() {
+        let source_code = "int main(void) { 1 + 2; return ~(3 * 4) - 5; }";
+        let expected_ir_ast = ir::parse_program_definition(parse(source_code));
+        let resolved_c_ast = resolve_program_definition(parse(source_code)).unwrap();
+        let ir_ast = ir::parse_program_definition(resolved_c_ast);
+        assert_eq!(ir_ast, expected_ir_ast);
+    }
+
+    #[test]
+    fn error_if_undeclared_variable_used_in_return_statement() {
+        let c_ast = parse("int main(void) { return a; }");
+        let expected_error = SemanticError::UndeclaredVariable {
+            name: "a".to_string(),
+        };
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_undeclared_variable_used_in_initializer() {
+        let c_ast = parse("int main(void) { int a = b + 1; return a; }");
+        let expected_error = SemanticError::UndeclaredVariable {
+            name: "b".to_string(),
+        };
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn variable_used_after_declaration_is_not_an_error() {
+        let c_ast = parse("int main(void) { int a = 1; int b = a; return b; }");
+        let res = resolve_program_definition(c_ast);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn error_if_variable_declared_twice_in_same_scope() {
+        let c_ast = parse("int main(void) { int a = 1; int a = 2; return a; }");
+        let expected_error = SemanticError::DuplicateDeclaration {
+            name: "a".to_string(),
+        };
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_variable_declared_with_same_name_as_parameter() {
+        let c_ast = parse("int foo(int a) { int a = 2; return a; }");
+        let expected_error = SemanticError::DuplicateDeclaration {
+            name: "a".to_string(),
+        };
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn variable_declared_again_in_nested_block_is_not_an_error() {
+        let c_ast = parse("int main(void) { int a = 1; { int a = 2; } return a; }");
+        let res = resolve_program_definition(c_ast);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn return_in_nested_block_resolves_to_inner_variable() {
+        let c_ast = parse("int main(void) { int a = 1; { int a = 2; return a; } return a; }");
+        let expected_c_ast = main_function(vec![
+            declaration("a.0", 1, 18),
+            BlockItem::Statement(
+                Statement::Compound(vec![
+                    declaration("a.1", 2, 31),
+                    BlockItem::Statement(
+                        Statement::Return(Expression::Var("a.1".to_string())),
+                        span_at(42),
+                    ),
+                ]),
+                span_at(29),
+            ),
+            BlockItem::Statement(
+                Statement::Return(Expression::Var("a.0".to_string())),
+                span_at(54),
+            ),
+        ]);
+        let resolved_c_ast = resolve_program_definition(c_ast).unwrap();
+        assert_eq!(resolved_c_ast, expected_c_ast);
+    }
+
+    #[test]
+    fn variable_in_outer_scope_is_found_from_doubly_nested_block() {
+        let c_ast = parse("int main(void) { int a = 1; { int b = 2; { return a + b; } } }");
+        let expected_c_ast = main_function(vec![
+            declaration("a.0", 1, 18),
+            BlockItem::Statement(
+                Statement::Compound(vec![
+                    declaration("b.1", 2, 31),
+                    BlockItem::Statement(
+                        Statement::Compound(vec![BlockItem::Statement(
+                            Statement::Return(Expression::Binary {
+                                op: c::BinaryOperator::Add,
+                                left: var("a.0"),
+                                right: var("b.1"),
+                            }),
+                            span_at(44),
+                        )]),
+                        span_at(42),
+                    ),
+                ]),
+                span_at(29),
+            ),
+        ]);
+        let resolved_c_ast = resolve_program_definition(c_ast).unwrap();
+        assert_eq!(resolved_c_ast, expected_c_ast);
+    }
+
+    #[test]
+    fn error_if_variable_declared_in_nested_block_used_outside_it() {
+        let c_ast = parse("int main(void) { { int b = 2; } return b; }");
+        let expected_error = SemanticError::UndeclaredVariable {
+            name: "b".to_string(),
+        };
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_variable_declared_in_for_loop_init_clause_used_after_loop() {
+        let c_ast = parse("int main(void) { for (int i = 0; i < 3; i += 1) ; return i; }");
+        let expected_error = SemanticError::UndeclaredVariable {
+            name: "i".to_string(),
+        };
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(expected_error));
+    }
+
+    #[test]
+    fn error_if_constant_assigned_to() {
+        let c_ast = parse("int main(void) { int a = 1; 2 = a; return a; }");
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(SemanticError::InvalidLvalue));
+    }
+
+    #[test]
+    fn error_if_parenthesized_binary_expression_assigned_to() {
+        let c_ast = parse("int main(void) { int a = 1; (a + 1) = 3; return a; }");
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(SemanticError::InvalidLvalue));
+    }
+
+    #[test]
+    fn error_if_constant_is_target_of_compound_assignment() {
+        let c_ast = parse("int main(void) { int a = 1; 2 += a; return a; }");
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(SemanticError::InvalidLvalue));
+    }
+
+    #[test]
+    fn error_if_prefix_increment_applied_to_binary_expression() {
+        let c_ast = parse("int main(void) { int a = 1; ++(a + 1); return a; }");
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(SemanticError::InvalidLvalue));
+    }
+
+    #[test]
+    fn error_if_postfix_decrement_applied_to_constant() {
+        let c_ast = parse("int main(void) { 1--; return 0; }");
+        let res = resolve_program_definition(c_ast);
+        assert_eq!(res, Err(SemanticError::InvalidLvalue));
+    }
+
+    #[test]
+    fn resolve_variable_targets_of_assignment_and_increment() {
+        let c_ast = parse("int main(void) { int a = 1; (a) = 2; a *= 3; ++a; a--; return a; }");
+        let expected_c_ast = main_function(vec![
+            declaration("a.0", 1, 18),
+            BlockItem::Statement(
+                Statement::Expression(Expression::Assignment {
+                    lhs: var("a.0"),
+                    rhs: Box::new(Expression::NumericConstant(2)),
+                }),
+                span_at(29),
+            ),
+            BlockItem::Statement(
+                Statement::Expression(Expression::CompoundAssignment {
+                    op: c::BinaryOperator::Multiply,
+                    lhs: var("a.0"),
+                    rhs: Box::new(Expression::NumericConstant(3)),
+                }),
+                span_at(38),
+            ),
+            BlockItem::Statement(
+                Statement::Expression(Expression::Prefix {
+                    op: c::IncrementOperator::Increment,
+                    operand: var("a.0"),
+                }),
+                span_at(46),
+            ),
+            BlockItem::Statement(
+                Statement::Expression(Expression::Postfix {
+                    op: c::IncrementOperator::Decrement,
+                    operand: var("a.0"),
+                }),
+                span_at(51),
+            ),
+            BlockItem::Statement(
+                Statement::Return(Expression::Var("a.0".to_string())),
+                span_at(56),
+            ),
+        ]);
+        let resolved_c_ast = resolve_program_definition(c_ast).unwrap();
+        assert_eq!(resolved_c_ast, expected_c_ast);
+    }
+}
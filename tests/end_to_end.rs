@@ -0,0 +1,873 @@
+use std::{fs, path::PathBuf, process::Command};
+
+/// Compile the given C source code with the compiler binary, assemble and link the generated
+/// assembly with `gcc`, and then run the resulting executable, returning its exit status.
+///
+/// Each program is compiled in its own directory (named after the given program name) since the
+/// compiler writes the assembly file to the current working directory.
+fn compile_and_run(program_name: &str, source_code: &str) -> i32 {
+    compile_with_flags_and_run(program_name, source_code, &[])
+}
+
+/// Same as [`compile_and_run`], but passing the given flags to the compiler
+fn compile_with_flags_and_run(program_name: &str, source_code: &str, flags: &[&str]) -> i32 {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "c_compiler_end_to_end_{}_{}",
+        std::process::id(),
+        program_name
+    ));
+    fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+    let c_filepath = dir.join(format!("{}.c", program_name));
+    fs::write(&c_filepath, source_code).expect("Unable to write C source code file");
+
+    let compiler_status = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+        .args(flags)
+        .arg(&c_filepath)
+        .current_dir(&dir)
+        .status()
+        .expect("Unable to run compiler");
+    assert!(compiler_status.success(), "Compiler failed");
+
+    let executable_filepath = dir.join(program_name);
+    let gcc_output = Command::new("gcc")
+        .arg(dir.join(format!("{}.s", program_name)))
+        .arg("-o")
+        .arg(&executable_filepath)
+        .output()
+        .expect("Unable to run gcc");
+    assert!(
+        gcc_output.status.success(),
+        "gcc failed: {}",
+        String::from_utf8_lossy(&gcc_output.stderr)
+    );
+
+    let program_status = Command::new(&executable_filepath)
+        .status()
+        .expect("Unable to run compiled program");
+    fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+    program_status
+        .code()
+        .expect("Compiled program should exit with a status code")
+}
+
+#[test]
+fn return_constant() {
+    let exit_status = compile_and_run("return_constant", "int main() { return 2; }");
+    assert_eq!(exit_status, 2);
+}
+
+#[test]
+fn return_modulo_expression() {
+    let exit_status = compile_and_run("return_modulo_expression", "int main() { return 7 % 3; }");
+    assert_eq!(exit_status, 1);
+}
+
+#[test]
+fn return_modulo_of_variables() {
+    let exit_status = compile_and_run(
+        "return_modulo_of_variables",
+        "int main(void) { int a = 17; int b = 5; return a % b; }",
+    );
+    assert_eq!(exit_status, 2);
+}
+
+#[test]
+fn return_division_expression() {
+    let exit_status = compile_and_run("return_division_expression", "int main() { return 7 / 3; }");
+    assert_eq!(exit_status, 2);
+}
+
+#[test]
+fn return_expression_mixing_operator_precedences() {
+    let exit_status = compile_and_run(
+        "return_expression_mixing_operator_precedences",
+        "int main() { return 20 - 2 * 3 + 4; }",
+    );
+    assert_eq!(exit_status, 18);
+}
+
+#[test]
+fn function_body_with_expression_statement_before_return() {
+    let exit_status = compile_and_run(
+        "function_body_with_expression_statement_before_return",
+        "int main() { 1 + 1; return 2; }",
+    );
+    assert_eq!(exit_status, 2);
+}
+
+#[test]
+fn program_with_two_function_definitions() {
+    let exit_status = compile_and_run(
+        "program_with_two_function_definitions",
+        "int foo() { return 1 + 2; } int main() { return -(4 * 2); }",
+    );
+    assert_eq!(exit_status, 248);
+}
+
+#[test]
+fn program_with_function_declaration_before_main() {
+    let exit_status = compile_and_run(
+        "program_with_function_declaration_before_main",
+        "int foo(void); int main(void) { return 3; }",
+    );
+    assert_eq!(exit_status, 3);
+}
+
+#[test]
+fn compiler_reports_parse_error_and_exits_with_failure() {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "c_compiler_end_to_end_{}_missing_semicolon",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+    let c_filepath = dir.join("missing_semicolon.c");
+    fs::write(&c_filepath, "int main() {\n    return 2\n}").expect("Unable to write C file");
+
+    let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+        .arg(&c_filepath)
+        .current_dir(&dir)
+        .output()
+        .expect("Unable to run compiler");
+    fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+    assert!(!compiler_output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&compiler_output.stderr),
+        "Parsing error: 3:1: Expected `;`, found `}`\n"
+    );
+}
+
+#[test]
+fn compiler_reports_construct_without_ir_lowering_and_exits_with_failure() {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("c_compiler_end_to_end_{}_goto", std::process::id()));
+    fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+    let c_filepath = dir.join("goto.c");
+    fs::write(&c_filepath, "int main(void) { goto end; end: return 2; }")
+        .expect("Unable to write C file");
+
+    let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+        .arg(&c_filepath)
+        .current_dir(&dir)
+        .output()
+        .expect("Unable to run compiler");
+    fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+    assert!(!compiler_output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&compiler_output.stderr),
+        "Internal error: lowering for `goto` statement in function `main` not implemented\n"
+    );
+}
+
+#[cfg(feature = "ast-json")]
+#[test]
+fn compiler_prints_c_ast_as_json_with_emit_ast_flag() {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "c_compiler_end_to_end_{}_emit_ast",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+    let c_filepath = dir.join("emit_ast.c");
+    fs::write(&c_filepath, "int main() { return 2; }").expect("Unable to write C file");
+
+    let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+        .arg("--emit-ast")
+        .arg(&c_filepath)
+        .current_dir(&dir)
+        .output()
+        .expect("Unable to run compiler");
+    let asm_file_exists = dir.join("emit_ast.s").exists();
+    fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+    assert!(compiler_output.status.success());
+    assert!(!asm_file_exists);
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&compiler_output.stdout).expect("Output should be valid JSON");
+    let expected_json = serde_json::json!({
+        "Program": [{
+            "Function": {
+                "name": "main",
+                "params": [],
+                "body": [{
+                    "Statement": [
+                        { "Return": { "NumericConstant": 2 } },
+                        { "line": 1, "column": 14 },
+                    ]
+                }],
+            }
+        }]
+    });
+    assert_eq!(json, expected_json);
+}
+
+#[cfg(feature = "ast-json")]
+#[test]
+fn compiler_prints_ir_as_json_with_emit_ir_flag() {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "c_compiler_end_to_end_{}_emit_ir",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+    let c_filepath = dir.join("emit_ir.c");
+    fs::write(&c_filepath, "int main(void) { return ~2; }").expect("Unable to write C file");
+
+    let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+        .arg("--emit-ir=json")
+        .arg(&c_filepath)
+        .current_dir(&dir)
+        .output()
+        .expect("Unable to run compiler");
+    let asm_file_exists = dir.join("emit_ir.s").exists();
+    fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+    assert!(compiler_output.status.success());
+    assert!(!asm_file_exists);
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&compiler_output.stdout).expect("Output should be valid JSON");
+    let expected_json = serde_json::json!({
+        "Program": [{
+            "Function": {
+                "identifier": "main",
+                "body": [
+                    {
+                        "Unary": {
+                            "op": "BitwiseComplement",
+                            "src": { "Constant": 2 },
+                            "dst": { "Var": "tmp0" },
+                        }
+                    },
+                    { "Return": { "Var": "tmp0" } },
+                ],
+                "locations": {
+                    "0": { "line": 1, "column": 18 },
+                    "1": { "line": 1, "column": 18 },
+                },
+            }
+        }]
+    });
+    assert_eq!(json, expected_json);
+}
+
+#[test]
+fn return_largest_int_constant() {
+    let exit_status = compile_and_run(
+        "return_largest_int_constant",
+        "int main(void) { return 2147483647; }",
+    );
+    assert_eq!(exit_status, 255);
+}
+
+#[test]
+fn return_smallest_int_constant_divided_by_large_constant() {
+    // INT_MIN / 16777216 = -128, which has the exit status 128
+    let exit_status = compile_and_run(
+        "return_smallest_int_constant_divided_by_large_constant",
+        "int main(void) { return -2147483648 / 16777216; }",
+    );
+    assert_eq!(exit_status, 128);
+}
+
+#[test]
+fn return_negated_parenthesized_constant() {
+    let exit_status = compile_and_run(
+        "return_negated_parenthesized_constant",
+        "int main(void) { return 10 + -(5); }",
+    );
+    assert_eq!(exit_status, 5);
+}
+
+#[test]
+fn return_comma_operator_expression() {
+    let exit_status = compile_and_run(
+        "return_comma_operator_expression",
+        "int main(void) { return (1, 2); }",
+    );
+    assert_eq!(exit_status, 2);
+}
+
+#[test]
+fn return_expression_with_unary_plus() {
+    let exit_status = compile_and_run(
+        "return_expression_with_unary_plus",
+        "int main(void) { return 1 + +2 * +(-(+3)) + 10; }",
+    );
+    assert_eq!(exit_status, 5);
+}
+
+#[test]
+fn compiler_reports_semantic_error_and_exits_with_failure() {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "c_compiler_end_to_end_{}_undeclared_variable",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+    let c_filepath = dir.join("undeclared_variable.c");
+    fs::write(&c_filepath, "int main(void) { return a; }").expect("Unable to write C file");
+
+    let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+        .arg(&c_filepath)
+        .current_dir(&dir)
+        .output()
+        .expect("Unable to run compiler");
+    let asm_file_exists = dir.join("undeclared_variable.s").exists();
+    fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+    assert!(!compiler_output.status.success());
+    assert!(!asm_file_exists);
+    assert_eq!(
+        String::from_utf8_lossy(&compiler_output.stderr),
+        "Semantic error: Use of undeclared variable `a`\n"
+    );
+}
+
+#[test]
+fn main_without_return_statement_returns_zero() {
+    let exit_status = compile_and_run(
+        "main_without_return_statement_returns_zero",
+        "int main() { 1 + 1; }",
+    );
+    assert_eq!(exit_status, 0);
+}
+
+#[test]
+fn compiler_warns_about_unreachable_statement_and_still_compiles() {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "c_compiler_end_to_end_{}_unreachable_statement",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+    let c_filepath = dir.join("unreachable_statement.c");
+    fs::write(&c_filepath, "int main(void) { return 1; return 2; }")
+        .expect("Unable to write C file");
+
+    let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+        .arg(&c_filepath)
+        .current_dir(&dir)
+        .output()
+        .expect("Unable to run compiler");
+    let asm_file_exists = dir.join("unreachable_statement.s").exists();
+    fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+    assert!(compiler_output.status.success());
+    assert!(asm_file_exists);
+    assert_eq!(
+        String::from_utf8_lossy(&compiler_output.stderr),
+        "Warning: 1:28: Unreachable statement in function `main`\n"
+    );
+}
+
+#[test]
+fn logical_and_skips_right_operand_if_left_operand_is_zero() {
+    let exit_status = compile_and_run(
+        "logical_and_skips_right_operand_if_left_operand_is_zero",
+        "int main(void) { return 0 && (1 / 0); }",
+    );
+    assert_eq!(exit_status, 0);
+}
+
+#[test]
+fn return_logical_and_of_non_zero_constants() {
+    let exit_status = compile_and_run(
+        "return_logical_and_of_non_zero_constants",
+        "int main(void) { return 3 && 4; }",
+    );
+    assert_eq!(exit_status, 1);
+}
+
+#[test]
+fn logical_or_skips_right_operand_if_left_operand_is_non_zero() {
+    let exit_status = compile_and_run(
+        "logical_or_skips_right_operand_if_left_operand_is_non_zero",
+        "int main(void) { return 2 || (1 / 0); }",
+    );
+    assert_eq!(exit_status, 1);
+}
+
+#[test]
+fn return_logical_or_of_zero_constants() {
+    let exit_status = compile_and_run(
+        "return_logical_or_of_zero_constants",
+        "int main(void) { return 0 || 0; }",
+    );
+    assert_eq!(exit_status, 0);
+}
+
+#[test]
+fn return_logical_not_of_zero() {
+    let exit_status = compile_and_run(
+        "return_logical_not_of_zero",
+        "int main(void) { return !0; }",
+    );
+    assert_eq!(exit_status, 1);
+}
+
+#[test]
+fn return_logical_not_of_non_zero_constant() {
+    let exit_status = compile_and_run(
+        "return_logical_not_of_non_zero_constant",
+        "int main(void) { return !5; }",
+    );
+    assert_eq!(exit_status, 0);
+}
+
+#[test]
+fn return_less_than_comparison() {
+    let exit_status = compile_and_run(
+        "return_less_than_comparison",
+        "int main(void) { return 1 < 2; }",
+    );
+    assert_eq!(exit_status, 1);
+}
+
+#[test]
+fn return_greater_or_equal_comparison() {
+    let exit_status = compile_and_run(
+        "return_greater_or_equal_comparison",
+        "int main(void) { return 3 >= 4; }",
+    );
+    assert_eq!(exit_status, 0);
+}
+
+#[test]
+fn return_chained_comparisons() {
+    let exit_status = compile_and_run(
+        "return_chained_comparisons",
+        "int main(void) { return 3 > 2 != 5 <= 4; }",
+    );
+    assert_eq!(exit_status, 1);
+}
+
+#[test]
+fn return_conditional_expression_with_non_zero_condition() {
+    let exit_status = compile_and_run(
+        "return_conditional_expression_with_non_zero_condition",
+        "int main(void) { return 1 ? 2 : 3; }",
+    );
+    assert_eq!(exit_status, 2);
+}
+
+#[test]
+fn return_conditional_expression_with_zero_condition() {
+    let exit_status = compile_and_run(
+        "return_conditional_expression_with_zero_condition",
+        "int main(void) { return 2 < 1 ? 2 : 0 ? 4 : 3; }",
+    );
+    assert_eq!(exit_status, 3);
+}
+
+#[test]
+fn if_else_statement_with_non_zero_condition_takes_if_branch() {
+    let exit_status = compile_and_run(
+        "if_else_statement_with_non_zero_condition_takes_if_branch",
+        "int main(void) { if (2 > 1) return 4; else return 5; }",
+    );
+    assert_eq!(exit_status, 4);
+}
+
+#[test]
+fn if_else_statement_with_zero_condition_takes_else_branch() {
+    let exit_status = compile_and_run(
+        "if_else_statement_with_zero_condition_takes_else_branch",
+        "int main(void) { if (0) return 4; else { if (1) return 5; } }",
+    );
+    assert_eq!(exit_status, 5);
+}
+
+#[test]
+fn while_loop_with_non_zero_condition_runs_body() {
+    let exit_status = compile_and_run(
+        "while_loop_with_non_zero_condition_runs_body",
+        "int main(void) { while (2 > 1) return 6; return 7; }",
+    );
+    assert_eq!(exit_status, 6);
+}
+
+#[test]
+fn while_loop_with_zero_condition_skips_body() {
+    let exit_status = compile_and_run(
+        "while_loop_with_zero_condition_skips_body",
+        "int main(void) { while (0) return 6; return 7; }",
+    );
+    assert_eq!(exit_status, 7);
+}
+
+#[test]
+fn do_while_loop_runs_body_before_checking_condition() {
+    let exit_status = compile_and_run(
+        "do_while_loop_runs_body_before_checking_condition",
+        "int main(void) { do return 4; while (0); }",
+    );
+    assert_eq!(exit_status, 4);
+}
+
+#[test]
+fn for_loop_without_condition_runs_body() {
+    let exit_status = compile_and_run(
+        "for_loop_without_condition_runs_body",
+        "int main(void) { for (;;) return 3; }",
+    );
+    assert_eq!(exit_status, 3);
+}
+
+#[test]
+fn break_exits_infinite_while_loop() {
+    let exit_status = compile_and_run(
+        "break_exits_infinite_while_loop",
+        "int main(void) { while (1) break; return 3; }",
+    );
+    assert_eq!(exit_status, 3);
+}
+
+#[test]
+fn continue_skips_rest_of_loop_body() {
+    let exit_status = compile_and_run(
+        "continue_skips_rest_of_loop_body",
+        "int main(void) { do { continue; return 1; } while (0); return 2; }",
+    );
+    assert_eq!(exit_status, 2);
+}
+
+#[test]
+fn break_in_nested_loop_only_exits_inner_loop() {
+    let exit_status = compile_and_run(
+        "break_in_nested_loop_only_exits_inner_loop",
+        "int main(void) { for (;;) { while (1) break; return 5; } }",
+    );
+    assert_eq!(exit_status, 5);
+}
+
+#[test]
+fn return_variable_declared_with_initializer() {
+    let exit_status = compile_and_run(
+        "return_variable_declared_with_initializer",
+        "int main(void) { int a = 1 + 2; int b; b = a * 2; return b; }",
+    );
+    assert_eq!(exit_status, 6);
+}
+
+#[test]
+fn return_chained_assignment() {
+    let exit_status = compile_and_run(
+        "return_chained_assignment",
+        "int main(void) { int a; int b; a = b = 5; return a + b; }",
+    );
+    assert_eq!(exit_status, 10);
+}
+
+#[test]
+fn return_assignment_used_as_subexpression() {
+    let exit_status = compile_and_run(
+        "return_assignment_used_as_subexpression",
+        "int main(void) { int a; return (a = 3) + 1; }",
+    );
+    assert_eq!(exit_status, 4);
+}
+
+#[test]
+fn for_loop_computes_sum_in_variable() {
+    let exit_status = compile_and_run(
+        "for_loop_computes_sum_in_variable",
+        "int main(void) { int sum = 0; for (int i = 0; i < 5; i = i + 1) sum = sum + i; return sum; }",
+    );
+    assert_eq!(exit_status, 10);
+}
+
+#[test]
+fn function_body_with_several_if_statements() {
+    let exit_status = compile_and_run(
+        "function_body_with_several_if_statements",
+        "int main(void) { int a = 1 && 2; int b = 0 || 3; if (a) b = b + 1; if (b) return a + b; return 0; }",
+    );
+    assert_eq!(exit_status, 3);
+}
+
+#[test]
+fn variable_named_like_temporary_variable_keeps_its_value() {
+    let exit_status = compile_and_run(
+        "variable_named_like_temporary_variable_keeps_its_value",
+        "int main(void) { int tmp0 = 5; int tmp1 = 2 * 3; return tmp0 + tmp1 * 1; }",
+    );
+    assert_eq!(exit_status, 11);
+}
+
+#[test]
+fn program_with_two_function_definitions_using_same_labels() {
+    let exit_status = compile_and_run(
+        "program_with_two_function_definitions_using_same_labels",
+        "int foo(void) { if (1) return 1; return 2; } int main(void) { if (0) return 3; return 4; }",
+    );
+    assert_eq!(exit_status, 4);
+}
+
+#[test]
+fn return_expression_with_constants_folded() {
+    let exit_status = compile_with_flags_and_run(
+        "return_expression_with_constants_folded",
+        "int main(void) { int a = 2 + 3 * 4; return a - ~-1 + (1 < 2); }",
+        &["--opt=fold"],
+    );
+    assert_eq!(exit_status, 15);
+}
+
+#[test]
+fn switch_jumps_to_matching_case_or_default() {
+    // Case 1 falls through into case 2, and case 3 breaks out of the `switch`
+    let cases = [(1, 11), (2, 3), (3, 30), (7, 40)];
+    for (value, expected_exit_status) in cases {
+        let program_name = format!("switch_jumps_to_matching_case_or_default_{}", value);
+        let source_code = format!(
+            "int main(void) {{ int a = {}; switch (a) {{ case 1: a = 10; case 2: return a + 1; case 3: a = 30; break; default: return 40; }} return a; }}",
+            value
+        );
+        let exit_status = compile_and_run(&program_name, &source_code);
+        assert_eq!(exit_status, expected_exit_status);
+    }
+}
+
+#[test]
+fn switch_without_default_or_matching_case_skips_body() {
+    let exit_status = compile_and_run(
+        "switch_without_default_or_matching_case_skips_body",
+        "int main(void) { int a = 4; switch (a + 1) { case 1: return 1; case 2: return 2; } return a; }",
+    );
+    assert_eq!(exit_status, 4);
+}
+
+#[test]
+fn return_multiplication_by_power_of_two_with_and_without_strength_reduction() {
+    let flag_combinations: [&[&str]; 4] = [
+        &[],
+        &["--opt=reduce-strength"],
+        &["-O"],
+        &["-O", "--no-opt"],
+    ];
+    for (i, flags) in flag_combinations.into_iter().enumerate() {
+        let program_name = format!(
+            "return_multiplication_by_power_of_two_with_and_without_strength_reduction_{}",
+            i
+        );
+        let exit_status =
+            compile_with_flags_and_run(&program_name, "int main(void) { return 5 * 4; }", flags);
+        assert_eq!(exit_status, 20);
+    }
+}
+
+#[test]
+fn return_bitwise_and_expression() {
+    let exit_status = compile_and_run(
+        "return_bitwise_and_expression",
+        "int main(void) { return 12 & 10; }",
+    );
+    assert_eq!(exit_status, 8);
+}
+
+#[test]
+fn return_bitwise_or_and_xor_of_variables() {
+    let exit_status = compile_and_run(
+        "return_bitwise_or_and_xor_of_variables",
+        "int main(void) { int a = 12; int b = 10; return (a | b) ^ 3; }",
+    );
+    assert_eq!(exit_status, 13);
+}
+
+#[test]
+fn return_left_shift_by_constant() {
+    let exit_status = compile_and_run(
+        "return_left_shift_by_constant",
+        "int main(void) { return 1 << 4; }",
+    );
+    assert_eq!(exit_status, 16);
+}
+
+#[test]
+fn return_right_shift_of_negative_value_is_arithmetic() {
+    // -4 as an exit status is 252
+    let exit_status = compile_and_run(
+        "return_right_shift_of_negative_value_is_arithmetic",
+        "int main(void) { return -8 >> 1; }",
+    );
+    assert_eq!(exit_status, 252);
+}
+
+#[test]
+fn return_left_shift_by_variable() {
+    let exit_status = compile_and_run(
+        "return_left_shift_by_variable",
+        "int main(void) { int a = 3; int b = 5; return b << a; }",
+    );
+    assert_eq!(exit_status, 40);
+}
+
+#[test]
+fn return_strength_reduced_multiplication_of_variable() {
+    let exit_status = compile_with_flags_and_run(
+        "return_strength_reduced_multiplication_of_variable",
+        "int main(void) { int a = 5; return a * 8; }",
+        &["-O"],
+    );
+    assert_eq!(exit_status, 40);
+}
+
+#[test]
+fn return_false_less_than_comparison() {
+    let exit_status = compile_and_run(
+        "return_false_less_than_comparison",
+        "int main(void) { return 3 < 2; }",
+    );
+    assert_eq!(exit_status, 0);
+}
+
+#[test]
+fn return_from_branch_of_if_else_chosen_by_variable() {
+    for (value, expected_exit_status) in [(0, 5), (3, 4)] {
+        let program_name = format!("return_from_branch_of_if_else_chosen_by_variable_{}", value);
+        let source_code = format!(
+            "int main(void) {{ int a = {}; if (a) return 4; else return 5; }}",
+            value
+        );
+        let exit_status = compile_and_run(&program_name, &source_code);
+        assert_eq!(exit_status, expected_exit_status);
+    }
+}
+
+#[test]
+fn return_expression_needing_more_than_one_byte_of_stack_offsets() {
+    let sum = vec!["a"; 40].join(" + ");
+    let source_code = format!("int main(void) {{ int a = 1; return {}; }}", sum);
+    let exit_status = compile_and_run(
+        "return_expression_needing_more_than_one_byte_of_stack_offsets",
+        &source_code,
+    );
+    assert_eq!(exit_status, 40);
+}
+
+#[test]
+fn switch_case_with_short_circuited_division_by_zero() {
+    let exit_status = compile_and_run(
+        "switch_case_with_short_circuited_division_by_zero",
+        "int main(void) { switch (0) { case 0 && (1 / 0): return 3; default: return 4; } }",
+    );
+    assert_eq!(exit_status, 3);
+}
+
+#[test]
+fn compiler_reports_instruction_without_asm_translation_and_exits_with_failure() {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "c_compiler_end_to_end_{}_function_call",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+    let c_filepath = dir.join("function_call.c");
+    fs::write(
+        &c_filepath,
+        "int foo(void); int main(void) { return foo(); }",
+    )
+    .expect("Unable to write C file");
+
+    let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+        .arg(&c_filepath)
+        .current_dir(&dir)
+        .output()
+        .expect("Unable to run compiler");
+    let asm_file_exists = dir.join("function_call.s").exists();
+    fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+    assert!(!compiler_output.status.success());
+    assert!(!asm_file_exists);
+    assert_eq!(
+        String::from_utf8_lossy(&compiler_output.stderr),
+        "Internal error: translation to asm for function call in function `main` not implemented\n"
+    );
+}
+
+#[test]
+fn compiler_rejects_unknown_flags_and_exits_with_failure() {
+    // `--fold-constants` was replaced by `--opt=fold`
+    for flag in ["--fold-constants", "--bogus"] {
+        let dir: PathBuf = std::env::temp_dir().join(format!(
+            "c_compiler_end_to_end_{}_unknown_flag{}",
+            std::process::id(),
+            flag
+        ));
+        fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+        let c_filepath = dir.join("unknown_flag.c");
+        fs::write(&c_filepath, "int main(void) { return 2; }").expect("Unable to write C file");
+
+        let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+            .arg(flag)
+            .arg(&c_filepath)
+            .current_dir(&dir)
+            .output()
+            .expect("Unable to run compiler");
+        let asm_file_exists = dir.join("unknown_flag.s").exists();
+        fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+        assert!(!compiler_output.status.success());
+        assert!(!asm_file_exists);
+        assert_eq!(
+            String::from_utf8_lossy(&compiler_output.stderr),
+            format!("Unknown flag `{}`\n", flag)
+        );
+    }
+}
+
+#[test]
+fn compiler_reports_pathologically_deep_nesting_as_parse_error() {
+    let cases = [
+        (
+            "long_binary_chain",
+            format!("int main(void) {{ return {}; }}", vec!["1"; 2000].join("+")),
+            "Parsing error: 1:281: Expression nesting too deep\n",
+        ),
+        (
+            "deeply_nested_blocks",
+            format!(
+                "int main(void) {{ {}return 1;{} }}",
+                "{".repeat(400),
+                "}".repeat(400)
+            ),
+            "Parsing error: 1:147: Statement nesting too deep\n",
+        ),
+        (
+            "deeply_nested_if_statements",
+            format!(
+                "int main(void) {{ {}return 1; return 0; }}",
+                "if (1) ".repeat(1000)
+            ),
+            "Parsing error: 1:921: Statement nesting too deep\n",
+        ),
+    ];
+    for (program_name, source_code, expected_stderr) in cases {
+        let dir: PathBuf = std::env::temp_dir().join(format!(
+            "c_compiler_end_to_end_{}_{}",
+            std::process::id(),
+            program_name
+        ));
+        fs::create_dir_all(&dir).expect("Unable to create directory for test program");
+        let c_filepath = dir.join(format!("{}.c", program_name));
+        fs::write(&c_filepath, source_code).expect("Unable to write C file");
+
+        let compiler_output = Command::new(env!("CARGO_BIN_EXE_c_compiler"))
+            .arg(&c_filepath)
+            .current_dir(&dir)
+            .output()
+            .expect("Unable to run compiler");
+        fs::remove_dir_all(&dir).expect("Unable to remove directory for test program");
+        assert_eq!(compiler_output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&compiler_output.stderr),
+            expected_stderr
+        );
+    }
+}
+
+#[test]
+fn binary_chain_and_nested_statements_up_to_nesting_limits_compile() {
+    // 127 binary operators, inside 63 `if` statements and 64 loops around a block
+    let source_code = format!(
+        "int main(void) {{ {}{}{{ return {}; }} return 0; }}",
+        "if (1) ".repeat(63),
+        "while (1) ".repeat(64),
+        vec!["1"; 128].join("+")
+    );
+    let exit_status = compile_and_run(
+        "binary_chain_and_nested_statements_up_to_nesting_limits_compile",
+        &source_code,
+    );
+    assert_eq!(exit_status, 128);
+}